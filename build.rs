@@ -0,0 +1,41 @@
+//! 启用 `cuda-ptx` feature 时用 nvcc 把 CUDA 内核预编译为 PTX 并嵌入库中，
+//! 部署时无需 NVRTC。nvcc 不可用时只给出警告，运行时仍按 NVRTC 编译
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+const KERNEL_SRC: &str = "src/optimizer/kernels.cu";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_CUDA_PTX").is_none() {
+        return;
+    }
+    println!("cargo:rerun-if-changed={KERNEL_SRC}");
+    println!("cargo:rerun-if-env-changed=NVCC");
+    println!("cargo:rerun-if-env-changed=BEZIER_DP_FIT_CUDA_ARCH");
+
+    let out =
+        PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo")).join("kernels.ptx");
+    let nvcc = env::var("NVCC").unwrap_or_else(|_| "nvcc".to_string());
+    // PTX 可由驱动 JIT 到更新的架构，默认取较旧的 compute_52 以覆盖更多设备
+    let arch = env::var("BEZIER_DP_FIT_CUDA_ARCH").unwrap_or_else(|_| "compute_52".to_string());
+    let status = Command::new(&nvcc)
+        .args(["--ptx", &format!("-arch={arch}"), "-o"])
+        .arg(&out)
+        .arg(KERNEL_SRC)
+        .status();
+
+    match status {
+        Ok(status) if status.success() => {}
+        result => {
+            let reason = match result {
+                Ok(status) => format!("exited with {status}"),
+                Err(e) => e.to_string(),
+            };
+            println!("cargo:warning={nvcc} failed ({reason}); the CUDA kernel will be compiled with NVRTC at runtime");
+            std::fs::write(&out, "").expect("write empty ptx");
+        }
+    }
+}
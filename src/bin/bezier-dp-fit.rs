@@ -0,0 +1,174 @@
+//! 命令行工具：从 CSV / JSON / 标准输入读取点集，输出 SVG 路径、完整 SVG 文档或 DXF
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use bezier_dp_fit::io::{csv, CsvOptions};
+use bezier_dp_fit::{
+    try_fit_curve, DxfOptions, FitParams, Point2D, SvgDocumentOptions, SvgOptions,
+};
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    Auto,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "bezier-dp-fit",
+    version,
+    about = "Fit quadratic Bezier curves to x,y points"
+)]
+struct Args {
+    /// 输入文件，省略或为 `-` 时读取标准输入
+    input: Option<PathBuf>,
+
+    /// 输入格式，auto 根据扩展名或内容判断
+    #[arg(long, value_enum, default_value_t = InputFormat::Auto)]
+    format: InputFormat,
+
+    #[arg(long = "min-len", default_value_t = 30)]
+    min_len: usize,
+
+    #[arg(long = "max-len", default_value_t = 200)]
+    max_len: usize,
+
+    #[arg(long = "max-error", default_value_t = 2.0)]
+    max_error: f64,
+
+    /// 坐标保留的小数位数
+    #[arg(long, default_value_t = 2)]
+    precision: usize,
+
+    /// 使用相对路径指令
+    #[arg(long)]
+    relative: bool,
+
+    /// 输出完整 SVG 文档而不是路径字符串
+    #[arg(long)]
+    svg: bool,
+
+    /// 输出 DXF（二次 SPLINE 实体），供 CAM / 激光切割使用
+    #[arg(long, conflicts_with = "svg")]
+    dxf: bool,
+
+    /// 在 SVG 文档中叠加显示输入点
+    #[arg(long, requires = "svg")]
+    show_points: bool,
+
+    /// 输出文件，省略时写到标准输出
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// JSON 输入支持 `[[x, y], ...]` 和 `[{"x": .., "y": ..}, ...]` 两种形式
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonPoint {
+    Pair([f64; 2]),
+    Object(Point2D),
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("bezier-dp-fit: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let text = read_input(args.input.as_ref())?;
+    let format = match args.format {
+        InputFormat::Auto => detect_format(args.input.as_ref(), &text),
+        other => other,
+    };
+    let points = match format {
+        InputFormat::Json => parse_json(&text)?,
+        _ => csv::parse_points(&text, &CsvOptions::default()).map_err(|e| e.to_string())?,
+    };
+
+    let config = FitParams {
+        min_segment_len: Some(args.min_len),
+        max_segment_len: Some(args.max_len),
+        max_error: Some(args.max_error),
+        ..FitParams::default()
+    }
+    .to_config()
+    .map_err(|e| e.to_string())?;
+    let result = try_fit_curve(&points, &config).map_err(|e| e.to_string())?;
+
+    let path_options = SvgOptions {
+        precision: args.precision,
+        relative: args.relative,
+        ..Default::default()
+    };
+    let mut out = if args.dxf {
+        result.to_dxf(&DxfOptions::default())
+    } else if args.svg {
+        let options = SvgDocumentOptions {
+            path: path_options,
+            overlay_points: args.show_points.then_some(points.as_slice()),
+            ..Default::default()
+        };
+        result.to_svg_document(&options)
+    } else {
+        result.to_svg_path_with(&path_options)
+    };
+    out.push('\n');
+
+    match &args.output {
+        Some(path) => fs::write(path, out).map_err(|e| format!("{}: {e}", path.display())),
+        None => io::stdout()
+            .write_all(out.as_bytes())
+            .map_err(|e| format!("stdout: {e}")),
+    }
+}
+
+fn read_input(path: Option<&PathBuf>) -> Result<String, String> {
+    match path {
+        Some(p) if p.as_os_str() != "-" => {
+            fs::read_to_string(p).map_err(|e| format!("{}: {e}", p.display()))
+        }
+        _ => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("stdin: {e}"))?;
+            Ok(text)
+        }
+    }
+}
+
+fn detect_format(path: Option<&PathBuf>, text: &str) -> InputFormat {
+    let ext = path
+        .and_then(|p| p.extension())
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("json") => InputFormat::Json,
+        Some("csv") | Some("txt") => InputFormat::Csv,
+        _ if text.trim_start().starts_with('[') => InputFormat::Json,
+        _ => InputFormat::Csv,
+    }
+}
+
+fn parse_json(text: &str) -> Result<Vec<Point2D>, String> {
+    let raw: Vec<JsonPoint> = serde_json::from_str(text).map_err(|e| format!("json: {e}"))?;
+    Ok(raw
+        .into_iter()
+        .map(|p| match p {
+            JsonPoint::Pair([x, y]) => Point2D::new(x, y),
+            JsonPoint::Object(p) => p,
+        })
+        .collect())
+}
@@ -0,0 +1,26 @@
+//! 拟合服务：`bezier-dp-serve [ADDR]`，默认监听 127.0.0.1:3000
+
+use std::net::SocketAddr;
+use std::process::ExitCode;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:3000".to_string());
+    let addr: SocketAddr = match addr.parse() {
+        Ok(addr) => addr,
+        Err(e) => {
+            eprintln!("bezier-dp-serve: invalid address {addr}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("bezier-dp-serve: listening on http://{addr}");
+    match bezier_dp_fit::service::serve(addr).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("bezier-dp-serve: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
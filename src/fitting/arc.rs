@@ -0,0 +1,81 @@
+use core::f64::consts::{PI, TAU};
+
+use super::fitter::FitError;
+use crate::geometry::{CircularArc, Point2D};
+#[cfg(not(feature = "std"))]
+use crate::math::FloatMath;
+
+/// 固定端点拟合圆弧。圆心限定在弦的中垂线上，代数误差对圆心位置是线性的，
+/// 可直接求闭式解。点集近似共线、首尾重合或圆心角不小于 π 时返回 None
+pub fn fit_arc_segment(points: &[Point2D]) -> Option<FitError> {
+    let n = points.len();
+    if n < 3 {
+        return None;
+    }
+    let p0 = points[0];
+    let p2 = points[n - 1];
+    let chord = p0.distance_to(&p2);
+    if chord < 1e-9 {
+        return None;
+    }
+
+    let mid = p0.lerp(&p2, 0.5);
+    let normal = Point2D::new(-(p2.y - p0.y) / chord, (p2.x - p0.x) / chord);
+    let half2 = chord * chord / 4.0;
+
+    // |p - c|² - r² = |p - m|² - h² - 2s·n·(p - m)，对 s 做最小二乘
+    let mut sum_ab = 0.0;
+    let mut sum_bb = 0.0;
+    let mut side = 0.0;
+    for p in points {
+        let dx = p.x - mid.x;
+        let dy = p.y - mid.y;
+        let b = 2.0 * (normal.x * dx + normal.y * dy);
+        let a = dx * dx + dy * dy - half2;
+        sum_ab += a * b;
+        sum_bb += b * b;
+        side += b;
+    }
+    if sum_bb < 1e-9 * chord * chord * n as f64 {
+        return None;
+    }
+
+    let s = sum_ab / sum_bb;
+    let center = Point2D::new(mid.x + s * normal.x, mid.y + s * normal.y);
+    let radius = (half2 + s * s).sqrt();
+
+    // 取逆时针圆弧，若其中点与数据点不在弦的同侧则改走另一侧
+    let a0 = (p0.y - center.y).atan2(p0.x - center.x);
+    let a1 = (p2.y - center.y).atan2(p2.x - center.x);
+    let ccw = (a1 - a0).rem_euclid(TAU);
+    let ccw_mid = a0 + ccw / 2.0;
+    let ccw_side = normal.x * (center.x + radius * ccw_mid.cos() - mid.x)
+        + normal.y * (center.y + radius * ccw_mid.sin() - mid.y);
+    let sweep = if ccw_side * side >= 0.0 {
+        ccw
+    } else {
+        ccw - TAU
+    };
+    if sweep.abs() >= PI {
+        return None;
+    }
+
+    let arc = CircularArc {
+        start: p0,
+        end: p2,
+        center,
+        radius,
+        sweep,
+    };
+    let error = points
+        .iter()
+        .map(|p| arc.distance_to_point(p).powi(2))
+        .sum::<f64>()
+        / n as f64;
+
+    Some(FitError {
+        bezier: arc.to_quadratic(),
+        error,
+        arc: Some(arc),
+    })
+}
@@ -1,93 +1,340 @@
-﻿use crate::geometry::{Point2D, QuadraticBezier};
+﻿use crate::geometry::{
+    CircularArc, Point2, Point2D, Point3D, QuadBezier, QuadraticBezier, QuadraticBezier3D, Scalar,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use alloc::{vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use crate::math::FloatMath;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FitError {
     pub bezier: QuadraticBezier,
     pub error: f64,
+    /// 该区间选用圆弧时的结果，`bezier` 为其二次近似
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub arc: Option<CircularArc>,
 }
 
 pub struct BezierFitter;
 
 impl BezierFitter {
-    pub(crate) fn compute_bezier(points: &[Point2D]) -> QuadraticBezier {
+    pub(crate) fn compute_bezier<T: Scalar>(points: &[Point2<T>]) -> QuadBezier<T> {
         let n = points.len();
 
         if n == 0 {
-            let p = Point2D::new(0.0, 0.0);
-            return QuadraticBezier::new(p, p, p);
+            let p = Point2::new(T::default(), T::default());
+            return QuadBezier::new(p, p, p);
         }
 
         if n == 1 {
             let p = points[0];
-            return QuadraticBezier::new(p, p, p);
+            return QuadBezier::new(p, p, p);
         }
 
         if n == 2 {
             let p0 = points[0];
             let p2 = points[1];
-            let p1 = p0.lerp(&p2, 0.5);
-            return QuadraticBezier::new(p0, p1, p2);
+            let p1 = p0.lerp(&p2, T::from_f64(0.5));
+            return QuadBezier::new(p0, p1, p2);
         }
 
-        let p0 = points[0];
-        let p2 = points[n - 1];
         let t_values = Self::compute_t_values(points);
+        Self::solve_control_point(points, &t_values, |_| T::from_f64(1.0))
+    }
 
-        let mut sum_x = 0.0;
-        let mut sum_y = 0.0;
-        let mut sum_weight = 0.0;
+    /// 固定端点，按加权最小二乘求解中间控制点
+    pub(crate) fn solve_control_point<T: Scalar>(
+        points: &[Point2<T>],
+        t_values: &[T],
+        point_weight: impl Fn(usize) -> T,
+    ) -> QuadBezier<T> {
+        let n = points.len();
+        Self::solve_control_point_between(points, t_values, points[0], points[n - 1], point_weight)
+    }
+
+    /// 给定端点 p0、p2（不必是首末采样点）求解中间控制点
+    pub(crate) fn solve_control_point_between<T: Scalar>(
+        points: &[Point2<T>],
+        t_values: &[T],
+        p0: Point2<T>,
+        p2: Point2<T>,
+        point_weight: impl Fn(usize) -> T,
+    ) -> QuadBezier<T> {
+        let n = points.len();
+        let eps = T::from_f64(1e-10);
+
+        let mut sum_x = T::default();
+        let mut sum_y = T::default();
+        let mut sum_weight = T::default();
 
         for i in 0..n {
             let t = t_values[i];
-            let mt = 1.0 - t;
-            let weight = 2.0 * mt * t;
+            let mt = T::from_f64(1.0) - t;
+            let weight = T::from_f64(2.0) * mt * t;
 
-            if weight.abs() < 1e-10 {
+            if weight.abs() < eps {
                 continue;
             }
 
+            let w = point_weight(i);
             let target_x = points[i].x - mt * mt * p0.x - t * t * p2.x;
             let target_y = points[i].y - mt * mt * p0.y - t * t * p2.y;
 
-            sum_x += weight * target_x;
-            sum_y += weight * target_y;
-            sum_weight += weight * weight;
+            sum_x = sum_x + w * weight * target_x;
+            sum_y = sum_y + w * weight * target_y;
+            sum_weight = sum_weight + w * weight * weight;
         }
 
-        let p1 = if sum_weight > 1e-10 {
-            Point2D::new(sum_x / sum_weight, sum_y / sum_weight)
+        let p1 = if sum_weight > eps {
+            Point2::new(sum_x / sum_weight, sum_y / sum_weight)
         } else {
-            p0.lerp(&p2, 0.5)
+            p0.lerp(&p2, T::from_f64(0.5))
         };
 
-        QuadraticBezier::new(p0, p1, p2)
+        QuadBezier::new(p0, p1, p2)
     }
 
     pub fn fit_segment(points: &[Point2D]) -> FitError {
         let bezier = Self::compute_bezier(points);
         let error = Self::compute_error(&bezier, points);
-        FitError { bezier, error }
+        FitError {
+            bezier,
+            error,
+            arc: None,
+        }
     }
 
     pub fn fit_segment_with_limit(points: &[Point2D], max_error: f64) -> FitError {
         let bezier = Self::compute_bezier(points);
         let error = Self::compute_error_with_limit(&bezier, points, max_error);
-        FitError { bezier, error }
+        FitError {
+            bezier,
+            error,
+            arc: None,
+        }
     }
 
-    fn compute_t_values(points: &[Point2D]) -> Vec<f64> {
+    /// 端点也参与求解的最小二乘：弦长参数化后对三个控制点解 3×3 正规方程，
+    /// 首末采样点有噪声时不会把误差全部压到中间；方程奇异时退回固定端点
+    pub fn fit_segment_free(points: &[Point2D]) -> FitError {
         let n = points.len();
-        let mut t_values = vec![0.0; n];
-        let mut distances = vec![0.0; n];
+        if n < 3 {
+            return Self::fit_segment(points);
+        }
+        let t_values = Self::compute_t_values(points);
+
+        let mut ata = [[0.0f64; 3]; 3];
+        let mut atx = [0.0f64; 3];
+        let mut aty = [0.0f64; 3];
+        for (p, &t) in points.iter().zip(&t_values) {
+            let mt = 1.0 - t;
+            let basis = [mt * mt, 2.0 * mt * t, t * t];
+            for r in 0..3 {
+                for c in 0..3 {
+                    ata[r][c] += basis[r] * basis[c];
+                }
+                atx[r] += basis[r] * p.x;
+                aty[r] += basis[r] * p.y;
+            }
+        }
+
+        let (Some(xs), Some(ys)) = (solve3(&ata, &atx), solve3(&ata, &aty)) else {
+            return Self::fit_segment(points);
+        };
+        let bezier = QuadraticBezier::new(
+            Point2D::new(xs[0], ys[0]),
+            Point2D::new(xs[1], ys[1]),
+            Point2D::new(xs[2], ys[2]),
+        );
+        let error = Self::compute_error(&bezier, points);
+        FitError {
+            bezier,
+            error,
+            arc: None,
+        }
+    }
+
+    /// 端点取给定位置（如断点附近的局部平均），只求解中间控制点
+    pub fn fit_segment_with_endpoints(points: &[Point2D], p0: Point2D, p2: Point2D) -> FitError {
+        if points.len() < 3 {
+            let bezier = QuadraticBezier::new(p0, p0.lerp(&p2, 0.5), p2);
+            let error = Self::compute_error(&bezier, points);
+            return FitError {
+                bezier,
+                error,
+                arc: None,
+            };
+        }
+        let t_values = Self::compute_t_values(points);
+        let bezier = Self::solve_control_point_between(points, &t_values, p0, p2, |_| 1.0);
+        let error = Self::compute_error(&bezier, points);
+        FitError {
+            bezier,
+            error,
+            arc: None,
+        }
+    }
+
+    /// 约束端点切向的单段拟合，`t_in` / `t_out` 为起点、终点处的切线方向（不必归一化）。
+    /// 控制点取无约束解在 `p0 + a·t_in` 或 `p2 - b·t_out` 上的投影，两者都给出时取两条切线的交点。
+    /// 约束无法满足时返回 None：投影落在切线反方向（a 或 b 不为正，控制点与端点重合时切向无定义），
+    /// 或两条切线不相交于各自的正方向上
+    pub fn fit_segment_with_tangents(
+        points: &[Point2D],
+        t_in: Option<Point2D>,
+        t_out: Option<Point2D>,
+    ) -> Option<FitError> {
+        let free = Self::compute_bezier(points);
+        let (p0, p2) = (free.p0, free.p2);
+        let usable = |d: Option<Point2D>| d.filter(|d| d.x * d.x + d.y * d.y > 1e-20);
+
+        // 控制点沿 origin + s·dir 移动时的最优 s，目标函数在 p1 上各向同性，因此即为投影
+        let along = |origin: Point2D, dir: Point2D, sign: f64| {
+            let len2 = dir.x * dir.x + dir.y * dir.y;
+            let s = ((free.p1.x - origin.x) * dir.x + (free.p1.y - origin.y) * dir.y) / len2 * sign;
+            (s > 0.0)
+                .then(|| Point2D::new(origin.x + sign * s * dir.x, origin.y + sign * s * dir.y))
+        };
+
+        let p1 = match (usable(t_in), usable(t_out)) {
+            (None, None) => free.p1,
+            (Some(d_in), None) => along(p0, d_in, 1.0)?,
+            (None, Some(d_out)) => along(p2, d_out, -1.0)?,
+            (Some(d_in), Some(d_out)) => {
+                // p0 + a·d_in = p2 - b·d_out
+                let cross = d_in.x * d_out.y - d_in.y * d_out.x;
+                let (ex, ey) = (p2.x - p0.x, p2.y - p0.y);
+                let a = (ex * d_out.y - ey * d_out.x) / cross;
+                let b = (d_in.x * ey - d_in.y * ex) / cross;
+                if !(cross.abs() > 1e-12 && a > 0.0 && b > 0.0) {
+                    return None;
+                }
+                Point2D::new(p0.x + a * d_in.x, p0.y + a * d_in.y)
+            }
+        };
+
+        let bezier = QuadraticBezier::new(p0, p1, p2);
+        let error = Self::compute_error(&bezier, points);
+        Some(FitError {
+            bezier,
+            error,
+            arc: None,
+        })
+    }
+
+    /// 误差取单点最大距离的平方
+    pub fn fit_segment_max(points: &[Point2D], max_error: f64) -> FitError {
+        let bezier = Self::compute_bezier(points);
+        let error = Self::compute_max_error(&bezier, points, max_error);
+        FitError {
+            bezier,
+            error,
+            arc: None,
+        }
+    }
+
+    /// 按时间戳参数化：t 取时间在区间内的比例，误差为 B(t_i) 到 p_i 距离平方的均值；
+    /// 点数少于 3、`times` 与 `points` 长度不同或时间跨度为零时退回弦长参数化
+    pub fn fit_segment_timed(points: &[Point2D], times: &[f64]) -> FitError {
+        let n = points.len();
+        if n < 3 || times.len() != n {
+            return Self::fit_segment(points);
+        }
+        let (first, last) = (times[0], times[n - 1]);
+        if last - first <= 0.0 {
+            return Self::fit_segment(points);
+        }
+
+        let t_values: Vec<f64> = times.iter().map(|&t| (t - first) / (last - first)).collect();
+        let bezier = Self::solve_control_point(points, &t_values, |_| 1.0);
+        let error = points
+            .iter()
+            .zip(&t_values)
+            .map(|(p, &t)| bezier.evaluate(t).distance_squared(p))
+            .sum::<f64>()
+            / n as f64;
+        FitError {
+            bezier,
+            error,
+            arc: None,
+        }
+    }
+
+    /// 函数图像拟合：控制点 x 取端点中点，使曲线 x(t) 线性、y 为 x 的二次函数，
+    /// 只对 y 求最小二乘，误差为竖直残差的均方；x 不严格递增的区间不是函数图像，误差记为正无穷
+    pub fn fit_segment_graph(points: &[Point2D]) -> FitError {
+        let n = points.len();
+        if points.windows(2).any(|w| w[1].x <= w[0].x) {
+            return FitError {
+                bezier: Self::compute_bezier(points),
+                error: f64::INFINITY,
+                arc: None,
+            };
+        }
+        if n < 3 {
+            return Self::fit_segment(points);
+        }
+        let (p0, p2) = (points[0], points[n - 1]);
+        let width = p2.x - p0.x;
+
+        let t_of = |p: &Point2D| ((p.x - p0.x) / width).clamp(0.0, 1.0);
+        let mut sum_y = 0.0;
+        let mut sum_weight = 0.0;
+        for p in points {
+            let t = t_of(p);
+            let mt = 1.0 - t;
+            let weight = 2.0 * mt * t;
+            if weight.abs() < 1e-10 {
+                continue;
+            }
+            sum_y += weight * (p.y - mt * mt * p0.y - t * t * p2.y);
+            sum_weight += weight * weight;
+        }
+        let y1 = if sum_weight > 1e-10 {
+            sum_y / sum_weight
+        } else {
+            (p0.y + p2.y) * 0.5
+        };
+
+        let bezier = QuadraticBezier::new(p0, Point2D::new((p0.x + p2.x) * 0.5, y1), p2);
+        let error = points
+            .iter()
+            .map(|p| (p.y - bezier.evaluate(t_of(p)).y).powi(2))
+            .sum::<f64>()
+            / n as f64;
+        FitError {
+            bezier,
+            error,
+            arc: None,
+        }
+    }
+
+    /// 误差按单精度计算，`max_error` 为无穷时不提前终止
+    pub fn fit_segment_f32(points: &[Point2D], max_error: f64) -> FitError {
+        let bezier = Self::compute_bezier(points);
+        let error = Self::compute_error_f32(&bezier, points, max_error);
+        FitError {
+            bezier,
+            error,
+            arc: None,
+        }
+    }
+
+    pub(crate) fn compute_t_values<T: Scalar>(points: &[Point2<T>]) -> Vec<T> {
+        let n = points.len();
+        let mut t_values = vec![T::default(); n];
+        let mut distances = vec![T::default(); n];
 
         for i in 1..n {
             distances[i] = distances[i - 1] + points[i].distance_to(&points[i - 1]);
         }
 
         let total_length = distances[n - 1];
-        if total_length < 1e-10 {
+        if total_length < T::from_f64(1e-10) {
             return (0..n)
-                .map(|i| i as f64 / (n - 1).max(1) as f64)
+                .map(|i| T::from_f64(i as f64 / (n - 1).max(1) as f64))
                 .collect();
         }
 
@@ -98,18 +345,39 @@ impl BezierFitter {
         t_values
     }
 
-    pub fn compute_error(bezier: &QuadraticBezier, points: &[Point2D]) -> f64 {
+    pub fn compute_error<T: Scalar>(bezier: &QuadBezier<T>, points: &[Point2<T>]) -> T {
         if points.is_empty() {
-            return 0.0;
+            return T::default();
         }
 
-        points
+        let sum = points
             .iter()
-            .map(|p| bezier.distance_to_point(p).powi(2))
-            .sum::<f64>()
-            / points.len() as f64
+            .map(|p| {
+                let d = bezier.distance_to_point(p);
+                d * d
+            })
+            .fold(T::default(), |acc, d2| acc + d2);
+        sum / T::from_f64(points.len() as f64)
+    }
+
+    /// 任意标量类型的单段拟合，返回曲线和均方误差；f32 输入全程按单精度计算
+    pub fn fit_quad<T: Scalar>(points: &[Point2<T>]) -> (QuadBezier<T>, T) {
+        let bezier = Self::compute_bezier(points);
+        let error = Self::compute_error(&bezier, points);
+        (bezier, error)
+    }
+
+    /// 带上限的误差计算共用的约定：超过 `limit` 的误差一律记为正无穷，不保留提前终止时的部分和，
+    /// 因此 CPU 和 GPU 后端对同一区间的取舍一致
+    pub fn apply_limit(error: f64, limit: f64) -> f64 {
+        if error > limit {
+            f64::INFINITY
+        } else {
+            error
+        }
     }
 
+    /// 均方误差，超过 `max_error` 后提前返回正无穷
     pub fn compute_error_with_limit(
         bezier: &QuadraticBezier,
         points: &[Point2D],
@@ -126,10 +394,113 @@ impl BezierFitter {
         for p in points {
             sum += bezier.distance_to_point(p).powi(2);
             if sum > max_sum {
-                return sum / n;
+                return f64::INFINITY;
+            }
+        }
+
+        Self::apply_limit(sum / n, max_error)
+    }
+
+    /// 最大距离的平方，超过 `max_error` 后提前返回正无穷
+    pub fn compute_max_error(bezier: &QuadraticBezier, points: &[Point2D], max_error: f64) -> f64 {
+        let mut worst = 0.0f64;
+        for p in points {
+            worst = worst.max(bezier.distance_to_point(p).powi(2));
+            if worst > max_error {
+                return f64::INFINITY;
             }
         }
+        worst
+    }
 
-        sum / n
+    pub fn compute_error_f32(bezier: &QuadraticBezier, points: &[Point2D], max_error: f64) -> f64 {
+        if points.is_empty() {
+            return 0.0;
+        }
+
+        let n = points.len() as f32;
+        let max_sum = max_error as f32 * n;
+        let mut sum = 0.0f32;
+
+        for p in points {
+            sum += bezier.distance_squared_to_point_f32(p);
+            if sum > max_sum {
+                return f64::INFINITY;
+            }
+        }
+
+        Self::apply_limit((sum / n) as f64, max_error)
+    }
+
+    /// 空间曲线的单段拟合：弦长参数化后按坐标分别求最小二乘，返回曲线和均方误差
+    pub fn fit_segment_3d(points: &[Point3D]) -> (QuadraticBezier3D, f64) {
+        let n = points.len();
+        if n == 0 {
+            let p = Point3D::new(0.0, 0.0, 0.0);
+            return (QuadraticBezier3D::new(p, p, p), 0.0);
+        }
+        let p0 = points[0];
+        let p2 = points[n - 1];
+
+        let mut distances = vec![0.0; n];
+        for i in 1..n {
+            distances[i] = distances[i - 1] + points[i].distance_to(&points[i - 1]);
+        }
+        let total_length = distances[n - 1];
+
+        let mut sum = Point3D::new(0.0, 0.0, 0.0);
+        let mut sum_weight = 0.0;
+        for (i, p) in points.iter().enumerate() {
+            let t = if total_length < 1e-10 {
+                i as f64 / (n - 1).max(1) as f64
+            } else {
+                distances[i] / total_length
+            };
+            let mt = 1.0 - t;
+            let weight = 2.0 * mt * t;
+            if weight.abs() < 1e-10 {
+                continue;
+            }
+            sum.x += weight * (p.x - mt * mt * p0.x - t * t * p2.x);
+            sum.y += weight * (p.y - mt * mt * p0.y - t * t * p2.y);
+            sum.z += weight * (p.z - mt * mt * p0.z - t * t * p2.z);
+            sum_weight += weight * weight;
+        }
+
+        let p1 = if sum_weight > 1e-10 {
+            Point3D::new(sum.x / sum_weight, sum.y / sum_weight, sum.z / sum_weight)
+        } else {
+            p0.lerp(&p2, 0.5)
+        };
+
+        let bezier = QuadraticBezier3D::new(p0, p1, p2);
+        let error = points
+            .iter()
+            .map(|p| bezier.distance_to_point(p).powi(2))
+            .sum::<f64>()
+            / n as f64;
+        (bezier, error)
+    }
+}
+
+/// Cramer 法则解 3×3 线性方程组，矩阵接近奇异时返回 None
+fn solve3(m: &[[f64; 3]; 3], rhs: &[f64; 3]) -> Option<[f64; 3]> {
+    let det = |a: &[[f64; 3]; 3]| {
+        a[0][0] * (a[1][1] * a[2][2] - a[1][2] * a[2][1])
+            - a[0][1] * (a[1][0] * a[2][2] - a[1][2] * a[2][0])
+            + a[0][2] * (a[1][0] * a[2][1] - a[1][1] * a[2][0])
+    };
+    let d = det(m);
+    if d.abs() < 1e-12 {
+        return None;
+    }
+    let mut out = [0.0; 3];
+    for (col, value) in out.iter_mut().enumerate() {
+        let mut replaced = *m;
+        for row in 0..3 {
+            replaced[row][col] = rhs[row];
+        }
+        *value = det(&replaced) / d;
     }
+    Some(out)
 }
@@ -1,3 +1,10 @@
+pub mod arc;
 pub mod fitter;
+pub mod orthogonal;
+pub mod ransac;
+pub mod robust;
 
-pub use fitter::{BezierFitter, FitError};
\ No newline at end of file
+pub use arc::fit_arc_segment;
+pub use fitter::{BezierFitter, FitError};
+pub use ransac::RansacParams;
+pub use robust::RobustLoss;
\ No newline at end of file
@@ -0,0 +1,33 @@
+use crate::geometry::Point2D;
+
+use super::fitter::{BezierFitter, FitError};
+use alloc::vec::Vec;
+
+impl BezierFitter {
+    /// 正交距离拟合：交替把每个点投影到当前曲线上取参数、再按新参数求解控制点，
+    /// 最小化点到曲线的垂直距离，宽分段上不会像弦长参数化那样低估曲率。
+    /// 控制点变化足够小时提前结束
+    pub fn fit_segment_orthogonal(points: &[Point2D], iterations: usize) -> FitError {
+        let mut bezier = Self::compute_bezier(points);
+        if points.len() >= 3 {
+            let scale = bezier.p0.distance_to(&bezier.p2).max(1.0);
+            for _ in 0..iterations {
+                let t_values: Vec<f64> =
+                    points.iter().map(|p| bezier.closest_parameter(p)).collect();
+                let next = Self::solve_control_point(points, &t_values, |_| 1.0);
+                let moved = next.p1.distance_to(&bezier.p1);
+                bezier = next;
+                if moved < 1e-9 * scale {
+                    break;
+                }
+            }
+        }
+
+        let error = Self::compute_error(&bezier, points);
+        FitError {
+            bezier,
+            error,
+            arc: None,
+        }
+    }
+}
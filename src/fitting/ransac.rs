@@ -0,0 +1,114 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::fitter::{BezierFitter, FitError};
+use crate::geometry::{Point2D, QuadraticBezier};
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use crate::math::FloatMath;
+
+/// 每个假设使用的内部采样点数
+const SAMPLE_SIZE: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RansacParams {
+    /// 随机假设的数量
+    pub iterations: usize,
+    /// 到曲线距离不超过该值的点视为内点
+    pub inlier_threshold: f64,
+}
+
+impl Default for RansacParams {
+    fn default() -> Self {
+        Self {
+            iterations: 32,
+            inlier_threshold: 2.0,
+        }
+    }
+}
+
+impl BezierFitter {
+    /// RANSAC 拟合：由随机子集估计控制点，保留内点最多的假设后在内点上重新拟合。
+    /// 误差为截断平方误差的均值（MSAC），离群点最多贡献 threshold²
+    pub fn fit_segment_ransac(points: &[Point2D], params: &RansacParams) -> FitError {
+        let n = points.len();
+        let threshold2 = params.inlier_threshold * params.inlier_threshold;
+        let score = |bezier: &QuadraticBezier| -> f64 {
+            points
+                .iter()
+                .map(|p| bezier.distance_to_point(p).powi(2).min(threshold2))
+                .sum::<f64>()
+        };
+
+        let mut best = Self::compute_bezier(points);
+        if n <= SAMPLE_SIZE + 2 {
+            let error = if n == 0 { 0.0 } else { score(&best) / n as f64 };
+            return FitError {
+                bezier: best,
+                error,
+                arc: None,
+            };
+        }
+
+        let t_values = Self::compute_t_values(points);
+        let mut best_score = score(&best);
+
+        // 以区间首尾坐标为种子，结果可复现
+        let mut rng = XorShift::new(points[0].x.to_bits() ^ points[n - 1].y.to_bits() ^ n as u64);
+        let mut sample = [0usize; SAMPLE_SIZE];
+        for _ in 0..params.iterations {
+            for s in sample.iter_mut() {
+                *s = 1 + rng.next_below(n - 2);
+            }
+            let hypothesis = Self::solve_control_point(points, &t_values, |i| {
+                if sample.contains(&i) {
+                    1.0
+                } else {
+                    0.0
+                }
+            });
+            let hypothesis_score = score(&hypothesis);
+            if hypothesis_score < best_score {
+                best = hypothesis;
+                best_score = hypothesis_score;
+            }
+        }
+
+        // 在共识内点上重新拟合，弦长参数化也只沿内点累计
+        let inliers: Vec<bool> = points
+            .iter()
+            .map(|p| best.distance_to_point(p).powi(2) <= threshold2)
+            .collect();
+        let inlier_t = Self::inlier_t_values(points, &inliers);
+        let refined =
+            Self::solve_control_point(points, &inlier_t, |i| if inliers[i] { 1.0 } else { 0.0 });
+        let refined_score = score(&refined);
+        if refined_score < best_score {
+            best = refined;
+            best_score = refined_score;
+        }
+
+        FitError {
+            bezier: best,
+            error: best_score / n as f64,
+            arc: None,
+        }
+    }
+}
+
+/// 简单的 xorshift64 随机数，避免引入额外依赖
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        Self(seed | 1)
+    }
+
+    fn next_below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
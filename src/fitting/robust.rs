@@ -0,0 +1,147 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::fitter::{BezierFitter, FitError};
+use crate::geometry::{Point2D, QuadraticBezier};
+use alloc::{vec, vec::Vec};
+
+/// IRLS 迭代次数，通常 3~5 次即可收敛
+const IRLS_ITERATIONS: usize = 5;
+
+/// 鲁棒损失函数，残差较小时与平方误差一致，离群点的影响被削弱
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RobustLoss {
+    /// 超过 delta 后按线性增长
+    Huber { delta: f64 },
+    /// 超过 c 后损失饱和，离群点权重为零
+    Tukey { c: f64 },
+}
+
+impl RobustLoss {
+    /// 残差 r 的损失 ρ(r)，在 r 较小时 ρ(r) ≈ r²
+    pub fn loss(&self, r: f64) -> f64 {
+        let r = r.abs();
+        match *self {
+            RobustLoss::Huber { delta } => {
+                if r <= delta {
+                    r * r
+                } else {
+                    2.0 * delta * r - delta * delta
+                }
+            }
+            RobustLoss::Tukey { c } => {
+                if r <= c {
+                    let u = 1.0 - (r / c) * (r / c);
+                    c * c / 3.0 * (1.0 - u * u * u)
+                } else {
+                    c * c / 3.0
+                }
+            }
+        }
+    }
+
+    /// IRLS 权重 w(r) = ρ'(r) / 2r
+    pub fn weight(&self, r: f64) -> f64 {
+        let r = r.abs();
+        match *self {
+            RobustLoss::Huber { delta } => {
+                if r <= delta {
+                    1.0
+                } else {
+                    delta / r
+                }
+            }
+            RobustLoss::Tukey { c } => {
+                if r <= c {
+                    let u = 1.0 - (r / c) * (r / c);
+                    u * u
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+impl BezierFitter {
+    /// 迭代加权最小二乘拟合，误差为各点鲁棒损失的均值
+    pub fn fit_segment_robust(points: &[Point2D], loss: &RobustLoss) -> FitError {
+        if points.len() < 3 {
+            let bezier = Self::compute_bezier(points);
+            let error = Self::compute_error_robust(&bezier, points, loss);
+            return FitError {
+                bezier,
+                error,
+                arc: None,
+            };
+        }
+
+        let mut t_values = Self::compute_t_values(points);
+        let mut bezier = Self::solve_control_point(points, &t_values, |_| 1.0);
+        for _ in 0..IRLS_ITERATIONS {
+            let weights: Vec<f64> = points
+                .iter()
+                .map(|p| loss.weight(bezier.distance_to_point(p)))
+                .collect();
+            // 离群点会拉长弦长参数化，只沿内点累计弦长
+            let inliers: Vec<bool> = weights.iter().map(|&w| w >= 0.5).collect();
+            t_values = Self::inlier_t_values(points, &inliers);
+            bezier = Self::solve_control_point(points, &t_values, |i| weights[i]);
+        }
+
+        let error = Self::compute_error_robust(&bezier, points, loss);
+        FitError {
+            bezier,
+            error,
+            arc: None,
+        }
+    }
+
+    /// 只沿内点累计弦长的参数化，离群点的 t 按下标在相邻内点之间插值
+    pub(crate) fn inlier_t_values(points: &[Point2D], inliers: &[bool]) -> Vec<f64> {
+        let idx: Vec<usize> = (0..points.len()).filter(|&i| inliers[i]).collect();
+        if idx.len() < 2 {
+            return Self::compute_t_values(points);
+        }
+
+        let mut dist = vec![0.0; points.len()];
+        for w in idx.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            dist[b] = dist[a] + points[b].distance_to(&points[a]);
+            for i in a + 1..b {
+                dist[i] = dist[a] + (dist[b] - dist[a]) * (i - a) as f64 / (b - a) as f64;
+            }
+        }
+        let (first, last) = (idx[0], idx[idx.len() - 1]);
+        let total = dist[last];
+        for d in dist.iter_mut().skip(last + 1) {
+            *d = total;
+        }
+        if total < 1e-10 {
+            return Self::compute_t_values(points);
+        }
+
+        // 首个内点之前的点 t 为 0
+        dist.iter()
+            .enumerate()
+            .map(|(i, d)| if i < first { 0.0 } else { d / total })
+            .collect()
+    }
+
+    pub fn compute_error_robust(
+        bezier: &QuadraticBezier,
+        points: &[Point2D],
+        loss: &RobustLoss,
+    ) -> f64 {
+        if points.is_empty() {
+            return 0.0;
+        }
+
+        points
+            .iter()
+            .map(|p| loss.loss(bezier.distance_to_point(p)))
+            .sum::<f64>()
+            / points.len() as f64
+    }
+}
@@ -0,0 +1,80 @@
+use core::f64::consts::{PI, TAU};
+
+use super::bezier::QuadraticBezier;
+use super::point::Point2D;
+use super::transform::Transform2D;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "std"))]
+use crate::math::FloatMath;
+
+/// 圆弧：从 start 出发绕 center 转过 sweep 弧度到达 end，sweep 为正表示角度增大的方向
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CircularArc {
+    pub start: Point2D,
+    pub end: Point2D,
+    pub center: Point2D,
+    pub radius: f64,
+    pub sweep: f64,
+}
+
+impl CircularArc {
+    pub fn start_angle(&self) -> f64 {
+        (self.start.y - self.center.y).atan2(self.start.x - self.center.x)
+    }
+
+    pub fn evaluate(&self, t: f64) -> Point2D {
+        let angle = self.start_angle() + t * self.sweep;
+        Point2D::new(
+            self.center.x + self.radius * angle.cos(),
+            self.center.y + self.radius * angle.sin(),
+        )
+    }
+
+    /// 点到圆弧的最短距离
+    pub fn distance_to_point(&self, p: &Point2D) -> f64 {
+        let angle = (p.y - self.center.y).atan2(p.x - self.center.x);
+        let offset = ((angle - self.start_angle()) * self.sweep.signum()).rem_euclid(TAU);
+        if offset <= self.sweep.abs() {
+            (p.distance_to(&self.center) - self.radius).abs()
+        } else {
+            p.distance_to(&self.start).min(p.distance_to(&self.end))
+        }
+    }
+
+    /// SVG 的 large-arc-flag
+    pub fn large_arc(&self) -> bool {
+        self.sweep.abs() > PI
+    }
+
+    /// 以两端切线交点为控制点的二次近似，要求圆心角小于 π
+    pub fn to_quadratic(&self) -> QuadraticBezier {
+        let half = self.sweep / 2.0;
+        let mid = self.start_angle() + half;
+        let dist = self.radius / half.cos();
+        let p1 = Point2D::new(
+            self.center.x + dist * mid.cos(),
+            self.center.y + dist * mid.sin(),
+        );
+        QuadraticBezier::new(self.start, p1, self.end)
+    }
+
+    /// 相似变换下仍为圆弧；一般仿射变换会变成椭圆弧，此时返回 None
+    pub fn transform(&self, m: &Transform2D) -> Option<CircularArc> {
+        let col0 = m.a * m.a + m.b * m.b;
+        let col1 = m.c * m.c + m.d * m.d;
+        let tol = 1e-9 * col0.max(col1).max(1.0);
+        if (m.a * m.c + m.b * m.d).abs() > tol || (col0 - col1).abs() > tol {
+            return None;
+        }
+        let det = m.determinant();
+        Some(CircularArc {
+            start: m.apply(&self.start),
+            end: m.apply(&self.end),
+            center: m.apply(&self.center),
+            radius: self.radius * det.abs().sqrt(),
+            sweep: self.sweep * det.signum(),
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use super::point::Point2D;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// 轴对齐包围盒
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoundingBox {
+    pub min: Point2D,
+    pub max: Point2D,
+}
+
+impl BoundingBox {
+    pub fn new(min: Point2D, max: Point2D) -> Self {
+        Self { min, max }
+    }
+
+    /// 点集的包围盒，点集为空时返回 None
+    pub fn from_points(points: &[Point2D]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+        let mut bbox = Self::new(*first, *first);
+        for p in rest {
+            bbox.include(p);
+        }
+        Some(bbox)
+    }
+
+    /// 扩展包围盒以包含该点
+    pub fn include(&mut self, p: &Point2D) {
+        self.min.x = self.min.x.min(p.x);
+        self.min.y = self.min.y.min(p.y);
+        self.max.x = self.max.x.max(p.x);
+        self.max.y = self.max.y.max(p.y);
+    }
+
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        let mut bbox = *self;
+        bbox.include(&other.min);
+        bbox.include(&other.max);
+        bbox
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    pub fn diagonal(&self) -> f64 {
+        self.min.distance_to(&self.max)
+    }
+
+    pub fn center(&self) -> Point2D {
+        self.min.lerp(&self.max, 0.5)
+    }
+
+    pub fn contains(&self, p: &Point2D) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+    }
+}
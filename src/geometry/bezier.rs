@@ -1,44 +1,80 @@
-use super::point::Point2D;
+use super::bbox::BoundingBox;
+use super::cubic::CubicBezier;
+use super::point::{Point2, Point2D};
+use super::scalar::Scalar;
+use super::transform::Transform2D;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use alloc::{format, string::String, vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use crate::math::FloatMath;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub struct QuadraticBezier {
-    pub p0: Point2D,  // 起点
-    pub p1: Point2D,  // 控制点
-    pub p2: Point2D,  // 终点
+/// 二次贝塞尔曲线，标量类型默认为 f64
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuadBezier<T = f64> {
+    pub p0: Point2<T>,  // 起点
+    pub p1: Point2<T>,  // 控制点
+    pub p2: Point2<T>,  // 终点
 }
 
-impl QuadraticBezier {
-    pub fn new(p0: Point2D, p1: Point2D, p2: Point2D) -> Self {
+/// 双精度二次贝塞尔曲线，库中其余部分均使用该类型
+pub type QuadraticBezier = QuadBezier<f64>;
+
+impl<T: Scalar> QuadBezier<T> {
+    pub fn new(p0: Point2<T>, p1: Point2<T>, p2: Point2<T>) -> Self {
         Self { p0, p1, p2 }
     }
 
     /// 计算贝塞尔曲线上参数为 t 的点 (t ∈ [0, 1])
-    pub fn evaluate(&self, t: f64) -> Point2D {
+    pub fn evaluate(&self, t: T) -> Point2<T> {
+        let two = T::from_f64(2.0);
         let t2 = t * t;
-        let mt = 1.0 - t;
+        let mt = T::from_f64(1.0) - t;
         let mt2 = mt * mt;
 
-        Point2D {
-            x: mt2 * self.p0.x + 2.0 * mt * t * self.p1.x + t2 * self.p2.x,
-            y: mt2 * self.p0.y + 2.0 * mt * t * self.p1.y + t2 * self.p2.y,
+        Point2 {
+            x: mt2 * self.p0.x + two * mt * t * self.p1.x + t2 * self.p2.x,
+            y: mt2 * self.p0.y + two * mt * t * self.p1.y + t2 * self.p2.y,
         }
     }
 
     /// 采样曲线上的点
-    pub fn sample(&self, num_points: usize) -> Vec<Point2D> {
+    pub fn sample(&self, num_points: usize) -> Vec<Point2<T>> {
         if num_points == 0 {
             return Vec::new();
         }
         let denom = (num_points - 1).max(1) as f64;
         (0..num_points)
             .map(|i| {
-                let t = i as f64 / denom;
+                let t = T::from_f64(i as f64 / denom);
                 self.evaluate(t)
             })
             .collect()
     }
 
+    /// 计算点到曲线的最近距离（近似）
+    pub fn distance_to_point(&self, point: &Point2<T>) -> T {
+        // 根据曲线长度自适应采样
+        let curve_length = (self.p0.distance_to(&self.p1) + self.p1.distance_to(&self.p2)).to_f64();
+        let samples = (curve_length / 2.0).clamp(50.0, 200.0) as usize;
+
+        (0..samples)
+            .map(|i| {
+                let t = T::from_f64(i as f64 / (samples - 1) as f64);
+                let p = self.evaluate(t);
+                p.distance_to(point)
+            })
+            .fold(T::from_f64(f64::INFINITY), |best, d| if d < best { d } else { best })
+    }
+
+    /// 转换标量类型，如 f64 与 f32 互转
+    pub fn cast<U: Scalar>(&self) -> QuadBezier<U> {
+        QuadBezier::new(self.p0.cast(), self.p1.cast(), self.p2.cast())
+    }
+}
+
+impl QuadraticBezier {
     /// 转换为 SVG 路径的 Q 指令
     pub fn to_svg_command(&self) -> String {
         format!(
@@ -52,19 +88,325 @@ impl QuadraticBezier {
         [self.p0.into(), self.p1.into(), self.p2.into()]
     }
 
-    /// 计算点到曲线的最近距离（近似）
-    pub fn distance_to_point(&self, point: &Point2D) -> f64 {
-        // 根据曲线长度自适应采样
+    /// 精确包围盒：端点加上导数为零处的极值点
+    pub fn bounding_box(&self) -> BoundingBox {
+        let mut bbox = BoundingBox::new(self.p0, self.p0);
+        bbox.include(&self.p2);
+
+        let denom_x = self.p0.x - 2.0 * self.p1.x + self.p2.x;
+        let denom_y = self.p0.y - 2.0 * self.p1.y + self.p2.y;
+        for (num, denom) in [
+            (self.p0.x - self.p1.x, denom_x),
+            (self.p0.y - self.p1.y, denom_y),
+        ] {
+            if denom.abs() > 1e-12 {
+                let t = num / denom;
+                if t > 0.0 && t < 1.0 {
+                    bbox.include(&self.evaluate(t));
+                }
+            }
+        }
+        bbox
+    }
+
+    /// 控制点到弦 p0-p2 的距离，可衡量曲线偏离直线的程度
+    pub fn chord_deviation(&self) -> f64 {
+        let dx = self.p2.x - self.p0.x;
+        let dy = self.p2.y - self.p0.y;
+        let len2 = dx * dx + dy * dy;
+        if len2 < 1e-20 {
+            return self.p1.distance_to(&self.p0);
+        }
+        let t = (((self.p1.x - self.p0.x) * dx + (self.p1.y - self.p0.y) * dy) / len2).clamp(0.0, 1.0);
+        self.p1.distance_to(&self.p0.lerp(&self.p2, t))
+    }
+
+    /// 弧长
+    pub fn arc_length(&self) -> f64 {
+        self.arc_length_to(1.0)
+    }
+
+    /// 参数区间 [0, t] 上的弧长：速度 |B'(t)| 在 8 个子区间上做 5 点 Gauss-Legendre 积分
+    pub fn arc_length_to(&self, t: f64) -> f64 {
+        const NODES: [(f64, f64); 5] = [
+            (0.0, 0.568_888_888_888_888_9),
+            (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+            (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+            (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+            (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+        ];
+        const PIECES: usize = 8;
+        let h = t.clamp(0.0, 1.0) / PIECES as f64;
+        let mut length = 0.0;
+        for k in 0..PIECES {
+            let mid = (k as f64 + 0.5) * h;
+            for (x, w) in NODES {
+                length += 0.5 * h * w * self.speed(mid + 0.5 * h * x);
+            }
+        }
+        length
+    }
+
+    /// 从起点走过弧长 s 处的参数 t，s 超出 [0, 弧长] 时取端点；牛顿迭代，失败时退回二分
+    pub fn parameter_at_length(&self, s: f64) -> f64 {
+        let total = self.arc_length();
+        if total <= 0.0 || s <= 0.0 {
+            return 0.0;
+        }
+        if s >= total {
+            return 1.0;
+        }
+        let (mut lo, mut hi) = (0.0, 1.0);
+        let mut t = s / total;
+        for _ in 0..20 {
+            let f = self.arc_length_to(t) - s;
+            if f.abs() < 1e-12 * total {
+                break;
+            }
+            if f > 0.0 {
+                hi = t;
+            } else {
+                lo = t;
+            }
+            let speed = self.speed(t);
+            let next = t - f / speed;
+            t = if speed > 0.0 && next > lo && next < hi {
+                next
+            } else {
+                0.5 * (lo + hi)
+            };
+        }
+        t
+    }
+
+    /// 一阶导数 B'(t)，以 `Point2D` 表示向量
+    pub fn derivative(&self, t: f64) -> Point2D {
+        Point2D::new(
+            2.0 * ((1.0 - t) * (self.p1.x - self.p0.x) + t * (self.p2.x - self.p1.x)),
+            2.0 * ((1.0 - t) * (self.p1.y - self.p0.y) + t * (self.p2.y - self.p1.y)),
+        )
+    }
+
+    /// 单位切向量；控制点与端点重合导致导数为零时取弦 p0→p2 的方向，曲线退化为一点时为零向量
+    pub fn tangent(&self, t: f64) -> Point2D {
+        let d = self.derivative(t);
+        let len = d.x.hypot(d.y);
+        if len > 1e-12 {
+            return Point2D::new(d.x / len, d.y / len);
+        }
+        let (dx, dy) = (self.p2.x - self.p0.x, self.p2.y - self.p0.y);
+        let len = dx.hypot(dy);
+        if len > 0.0 {
+            Point2D::new(dx / len, dy / len)
+        } else {
+            Point2D::new(0.0, 0.0)
+        }
+    }
+
+    /// 单位法向量，为切向量逆时针旋转 90°（y 轴向上时指向行进方向左侧）
+    pub fn normal(&self, t: f64) -> Point2D {
+        let tangent = self.tangent(t);
+        Point2D::new(-tangent.y, tangent.x)
+    }
+
+    /// |B'(t)|
+    fn speed(&self, t: f64) -> f64 {
+        let d = self.derivative(t);
+        d.x.hypot(d.y)
+    }
+
+    /// 仿射变换作用在控制点上即可得到精确结果
+    pub fn transform(&self, m: &Transform2D) -> QuadraticBezier {
+        QuadraticBezier::new(m.apply(&self.p0), m.apply(&self.p1), m.apply(&self.p2))
+    }
+
+    /// 升阶为形状完全相同的三次贝塞尔曲线
+    pub fn to_cubic(&self) -> CubicBezier {
+        CubicBezier::new(
+            self.p0,
+            self.p0.lerp(&self.p1, 2.0 / 3.0),
+            self.p2.lerp(&self.p1, 2.0 / 3.0),
+            self.p2,
+        )
+    }
+
+    /// 用 de Casteljau 算法在参数 t 处将曲线一分为二
+    pub fn split(&self, t: f64) -> (QuadraticBezier, QuadraticBezier) {
+        let a = self.p0.lerp(&self.p1, t);
+        let b = self.p1.lerp(&self.p2, t);
+        let mid = a.lerp(&b, t);
+        (
+            QuadraticBezier::new(self.p0, a, mid),
+            QuadraticBezier::new(mid, b, self.p2),
+        )
+    }
+
+    /// 按参数等分为 n 段（n 为 0 时视为 1）
+    pub fn subdivide(&self, n: usize) -> Vec<QuadraticBezier> {
+        let n = n.max(1);
+        let mut pieces = Vec::with_capacity(n);
+        let mut rest = *self;
+        for i in 0..n - 1 {
+            // 剩余部分上的相对参数
+            let (left, right) = rest.split(1.0 / (n - i) as f64);
+            pieces.push(left);
+            rest = right;
+        }
+        pieces.push(rest);
+        pieces
+    }
+
+    /// 自适应细分为折线，与曲线的偏差不超过 tolerance；返回的点包含两个端点。
+    /// 只在弯曲处细分，比按固定点数采样的点更少
+    pub fn flatten(&self, tolerance: f64) -> Vec<Point2D> {
+        let mut points = vec![self.p0];
+        self.flatten_into(tolerance.max(1e-12), 0, &mut points);
+        points
+    }
+
+    fn flatten_into(&self, tolerance: f64, depth: u32, out: &mut Vec<Point2D>) {
+        const MAX_DEPTH: u32 = 24;
+        // p1 在弦上的投影落在弦内时曲线是弦的单值函数，到弦的最大距离为 p1 到弦距离的一半
+        let (dx, dy) = (self.p2.x - self.p0.x, self.p2.y - self.p0.y);
+        let len2 = dx * dx + dy * dy;
+        let along = (self.p1.x - self.p0.x) * dx + (self.p1.y - self.p0.y) * dy;
+        let monotone = len2 < 1e-20 || (0.0..=len2).contains(&along);
+        if (monotone && 0.5 * self.chord_deviation() <= tolerance) || depth >= MAX_DEPTH {
+            out.push(self.p2);
+            return;
+        }
+        let (left, right) = self.split(0.5);
+        left.flatten_into(tolerance, depth + 1, out);
+        right.flatten_into(tolerance, depth + 1, out);
+    }
+
+    /// 到曲线距离为 `distance` 的平行曲线（正值在 `normal` 一侧），用若干二次曲线近似，
+    /// 每段与精确偏移曲线的偏差不超过 tolerance；偏移距离大于曲率半径时产生的尖点和自交不做裁剪
+    pub fn offset(&self, distance: f64, tolerance: f64) -> Vec<QuadraticBezier> {
+        let mut pieces = Vec::new();
+        self.offset_into(distance, tolerance.max(1e-9), 0, &mut pieces);
+        pieces
+    }
+
+    fn offset_into(
+        &self,
+        distance: f64,
+        tolerance: f64,
+        depth: u32,
+        out: &mut Vec<QuadraticBezier>,
+    ) {
+        const MAX_DEPTH: u32 = 12;
+        let shifted = |t: f64| {
+            let p = self.evaluate(t);
+            let n = self.normal(t);
+            Point2D::new(p.x + distance * n.x, p.y + distance * n.y)
+        };
+        let (start, end) = (shifted(0.0), shifted(1.0));
+        let (t0, t1) = (self.tangent(0.0), self.tangent(1.0));
+
+        // 控制点取两端切线的交点，切线平行时只有直线才能用一段表示
+        let cross = t0.x * t1.y - t0.y * t1.x;
+        let candidate = if cross.abs() > 1e-9 {
+            let (ex, ey) = (end.x - start.x, end.y - start.y);
+            let a = (ex * t1.y - ey * t1.x) / cross;
+            let b = (t0.x * ey - t0.y * ex) / cross;
+            (a > 0.0 && b > 0.0).then(|| {
+                QuadraticBezier::new(
+                    start,
+                    Point2D::new(start.x + a * t0.x, start.y + a * t0.y),
+                    end,
+                )
+            })
+        } else if self.chord_deviation() < 1e-12 {
+            Some(QuadraticBezier::new(start, start.lerp(&end, 0.5), end))
+        } else {
+            None
+        };
+
+        if let Some(curve) = candidate {
+            let fits = depth >= MAX_DEPTH
+                || (1..8).all(|i| {
+                    let target = shifted(i as f64 / 8.0);
+                    curve
+                        .evaluate(curve.closest_parameter(&target))
+                        .distance_to(&target)
+                        <= tolerance
+                });
+            if fits {
+                out.push(curve);
+                return;
+            }
+        }
+        if depth >= MAX_DEPTH {
+            out.push(QuadraticBezier::new(start, start.lerp(&end, 0.5), end));
+            return;
+        }
+        let (left, right) = self.split(0.5);
+        left.offset_into(distance, tolerance, depth + 1, out);
+        right.offset_into(distance, tolerance, depth + 1, out);
+    }
+
+    /// 曲线上离 `point` 最近的参数 t：先按 `distance_to_point` 的密度采样，再做几步牛顿迭代
+    pub fn closest_parameter(&self, point: &Point2D) -> f64 {
+        let curve_length = self.p0.distance_to(&self.p1) + self.p1.distance_to(&self.p2);
+        let samples = (curve_length / 2.0).clamp(50.0, 200.0) as usize;
+        let denom = (samples - 1) as f64;
+        let mut t = (0..samples)
+            .map(|i| i as f64 / denom)
+            .min_by(|&a, &b| {
+                let da = self.evaluate(a).distance_squared(point);
+                let db = self.evaluate(b).distance_squared(point);
+                da.total_cmp(&db)
+            })
+            .unwrap_or(0.0);
+
+        // f(t) = (B(t) - p)·B'(t)，B'' 为常向量
+        let a = Point2D::new(
+            self.p0.x - 2.0 * self.p1.x + self.p2.x,
+            self.p0.y - 2.0 * self.p1.y + self.p2.y,
+        );
+        for _ in 0..4 {
+            let b = self.evaluate(t);
+            let d = Point2D::new(
+                2.0 * ((1.0 - t) * (self.p1.x - self.p0.x) + t * (self.p2.x - self.p1.x)),
+                2.0 * ((1.0 - t) * (self.p1.y - self.p0.y) + t * (self.p2.y - self.p1.y)),
+            );
+            let (rx, ry) = (b.x - point.x, b.y - point.y);
+            let f = rx * d.x + ry * d.y;
+            let df = d.x * d.x + d.y * d.y + 2.0 * (rx * a.x + ry * a.y);
+            if df.abs() < 1e-12 {
+                break;
+            }
+            t = (t - f / df).clamp(0.0, 1.0);
+        }
+        t
+    }
+
+    /// 单精度版本的最近距离平方，采样方式与 `distance_to_point` 相同；
+    /// 坐标先平移到以 p0 为原点再转换，减少大坐标下的精度损失
+    pub fn distance_squared_to_point_f32(&self, point: &Point2D) -> f32 {
         let curve_length = self.p0.distance_to(&self.p1) + self.p1.distance_to(&self.p2);
-        let samples = (curve_length / 2.0).max(50.0).min(200.0) as usize;
-        
+        let samples = (curve_length / 2.0).clamp(50.0, 200.0) as usize;
+        let o = self.p0;
+        let [p1x, p1y, p2x, p2y, px, py] = [
+            self.p1.x - o.x,
+            self.p1.y - o.y,
+            self.p2.x - o.x,
+            self.p2.y - o.y,
+            point.x - o.x,
+            point.y - o.y,
+        ]
+        .map(|v| v as f32);
+        let denom = (samples - 1) as f32;
+
         (0..samples)
             .map(|i| {
-                let t = i as f64 / (samples - 1) as f64;
-                let p = self.evaluate(t);
-                p.distance_to(point)
+                let t = i as f32 / denom;
+                let mt = 1.0 - t;
+                let bx = 2.0 * mt * t * p1x + t * t * p2x;
+                let by = 2.0 * mt * t * p1y + t * t * p2y;
+                (bx - px) * (bx - px) + (by - py) * (by - py)
             })
-            .min_by(|a, b| a.total_cmp(b))
-            .unwrap_or(f64::INFINITY)
+            .fold(f32::INFINITY, f32::min)
     }
 }
@@ -0,0 +1,59 @@
+use super::point3d::Point3D;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use alloc::vec::Vec;
+
+/// 空间二次贝塞尔曲线
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuadraticBezier3D {
+    pub p0: Point3D,
+    pub p1: Point3D,
+    pub p2: Point3D,
+}
+
+impl QuadraticBezier3D {
+    pub fn new(p0: Point3D, p1: Point3D, p2: Point3D) -> Self {
+        Self { p0, p1, p2 }
+    }
+
+    /// 计算曲线上参数为 t 的点 (t ∈ [0, 1])
+    pub fn evaluate(&self, t: f64) -> Point3D {
+        let t2 = t * t;
+        let mt = 1.0 - t;
+        let mt2 = mt * mt;
+
+        Point3D {
+            x: mt2 * self.p0.x + 2.0 * mt * t * self.p1.x + t2 * self.p2.x,
+            y: mt2 * self.p0.y + 2.0 * mt * t * self.p1.y + t2 * self.p2.y,
+            z: mt2 * self.p0.z + 2.0 * mt * t * self.p1.z + t2 * self.p2.z,
+        }
+    }
+
+    pub fn sample(&self, num_points: usize) -> Vec<Point3D> {
+        if num_points == 0 {
+            return Vec::new();
+        }
+        let denom = (num_points - 1).max(1) as f64;
+        (0..num_points)
+            .map(|i| self.evaluate(i as f64 / denom))
+            .collect()
+    }
+
+    /// 点到曲线的最近距离（近似），采样方式与二维曲线一致
+    pub fn distance_to_point(&self, point: &Point3D) -> f64 {
+        let curve_length = self.p0.distance_to(&self.p1) + self.p1.distance_to(&self.p2);
+        let samples = (curve_length / 2.0).clamp(50.0, 200.0) as usize;
+
+        (0..samples)
+            .map(|i| {
+                let t = i as f64 / (samples - 1) as f64;
+                self.evaluate(t).distance_to(point)
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    pub fn control_points(&self) -> [(f64, f64, f64); 3] {
+        [self.p0.into(), self.p1.into(), self.p2.into()]
+    }
+}
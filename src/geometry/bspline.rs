@@ -0,0 +1,44 @@
+use super::point::Point2D;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// 二次 B 样条，节点向量首末各重复 3 次（clamped），参数范围 [0, 1]；
+/// `knots.len() == control_points.len() + 3`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuadraticBSpline {
+    pub control_points: Vec<Point2D>,
+    pub knots: Vec<f64>,
+}
+
+impl QuadraticBSpline {
+    pub const DEGREE: usize = 2;
+
+    /// de Boor 算法求值，u 超出 [0, 1] 时取端点
+    pub fn evaluate(&self, u: f64) -> Point2D {
+        const P: usize = QuadraticBSpline::DEGREE;
+        let n = self.control_points.len();
+        let u = u.clamp(self.knots[P], self.knots[n]);
+        // 满足 knots[i] <= u < knots[i + 1] 的区间，u 为末端时取最后一个非空区间
+        let mut i = P;
+        while i + 1 < n && self.knots[i + 1] <= u {
+            i += 1;
+        }
+
+        let mut d = [
+            self.control_points[i - 2],
+            self.control_points[i - 1],
+            self.control_points[i],
+        ];
+        for r in 1..=P {
+            for j in (r..=P).rev() {
+                let lo = self.knots[j + i - P];
+                let hi = self.knots[j + 1 + i - r];
+                let alpha = if hi > lo { (u - lo) / (hi - lo) } else { 0.0 };
+                d[j] = d[j - 1].lerp(&d[j], alpha);
+            }
+        }
+        d[P]
+    }
+}
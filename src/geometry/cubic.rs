@@ -0,0 +1,56 @@
+use super::point::Point2D;
+use super::transform::Transform2D;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use alloc::{format, string::String};
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CubicBezier {
+    pub p0: Point2D,  // 起点
+    pub p1: Point2D,  // 第一控制点
+    pub p2: Point2D,  // 第二控制点
+    pub p3: Point2D,  // 终点
+}
+
+impl CubicBezier {
+    pub fn new(p0: Point2D, p1: Point2D, p2: Point2D, p3: Point2D) -> Self {
+        Self { p0, p1, p2, p3 }
+    }
+
+    /// 计算贝塞尔曲线上参数为 t 的点 (t ∈ [0, 1])
+    pub fn evaluate(&self, t: f64) -> Point2D {
+        let mt = 1.0 - t;
+        let a = mt * mt * mt;
+        let b = 3.0 * mt * mt * t;
+        let c = 3.0 * mt * t * t;
+        let d = t * t * t;
+
+        Point2D {
+            x: a * self.p0.x + b * self.p1.x + c * self.p2.x + d * self.p3.x,
+            y: a * self.p0.y + b * self.p1.y + c * self.p2.y + d * self.p3.y,
+        }
+    }
+
+    pub fn transform(&self, m: &Transform2D) -> CubicBezier {
+        CubicBezier::new(
+            m.apply(&self.p0),
+            m.apply(&self.p1),
+            m.apply(&self.p2),
+            m.apply(&self.p3),
+        )
+    }
+
+    /// 转换为 SVG 路径的 C 指令
+    pub fn to_svg_command(&self) -> String {
+        format!(
+            "C {:.2} {:.2}, {:.2} {:.2}, {:.2} {:.2}",
+            self.p1.x, self.p1.y, self.p2.x, self.p2.y, self.p3.x, self.p3.y
+        )
+    }
+
+    /// 获取控制点数组
+    pub fn control_points(&self) -> [(f64, f64); 4] {
+        [self.p0.into(), self.p1.into(), self.p2.into(), self.p3.into()]
+    }
+}
@@ -0,0 +1,122 @@
+use super::bbox::BoundingBox;
+use super::bezier::QuadraticBezier;
+use alloc::{vec, vec::Vec};
+
+/// 每对曲线最多递归的次数，防止重合曲线段导致细分无限展开
+const BUDGET: usize = 1 << 14;
+
+impl QuadraticBezier {
+    /// 与另一条二次曲线的交点参数 `(t_self, t_other)`，按 `t_self` 排序；
+    /// 按控制点包围盒递归细分到近似直线后求交，再用牛顿法精化。重合的曲线段不保证完整报告
+    pub fn intersections(&self, other: &QuadraticBezier) -> Vec<(f64, f64)> {
+        let scale = hull(self).union(&hull(other)).diagonal().max(1e-12);
+        let mut candidates = Vec::new();
+        let mut budget = BUDGET;
+        subdivide_pair(
+            self,
+            (0.0, 1.0),
+            other,
+            (0.0, 1.0),
+            scale * 1e-7,
+            &mut budget,
+            &mut candidates,
+        );
+
+        let mut found: Vec<(f64, f64)> = Vec::new();
+        for (s, u) in candidates {
+            let (s, u) = refine(self, other, s, u);
+            let duplicate = found
+                .iter()
+                .any(|&(a, b)| (a - s).abs() < 1e-7 && (b - u).abs() < 1e-7);
+            if !duplicate {
+                found.push((s, u));
+            }
+        }
+        found.sort_by(|a, b| a.0.total_cmp(&b.0));
+        found
+    }
+}
+
+fn hull(c: &QuadraticBezier) -> BoundingBox {
+    BoundingBox::from_points(&[c.p0, c.p1, c.p2]).expect("three control points")
+}
+
+fn subdivide_pair(
+    a: &QuadraticBezier,
+    ra: (f64, f64),
+    b: &QuadraticBezier,
+    rb: (f64, f64),
+    flatness: f64,
+    budget: &mut usize,
+    out: &mut Vec<(f64, f64)>,
+) {
+    if *budget == 0 || !hull(a).intersects(&hull(b)) {
+        return;
+    }
+    *budget -= 1;
+
+    let a_flat = a.chord_deviation() <= flatness;
+    let b_flat = b.chord_deviation() <= flatness;
+    if a_flat && b_flat {
+        if let Some((s, u)) = chord_intersection(a, b) {
+            out.push((ra.0 + s * (ra.1 - ra.0), rb.0 + u * (rb.1 - rb.0)));
+        }
+        return;
+    }
+
+    let halves = |c: &QuadraticBezier, r: (f64, f64), flat: bool| {
+        if flat {
+            vec![(*c, r)]
+        } else {
+            let (left, right) = c.split(0.5);
+            let mid = 0.5 * (r.0 + r.1);
+            vec![(left, (r.0, mid)), (right, (mid, r.1))]
+        }
+    };
+    for (a_part, ra_part) in halves(a, ra, a_flat) {
+        for &(b_part, rb_part) in &halves(b, rb, b_flat) {
+            subdivide_pair(&a_part, ra_part, &b_part, rb_part, flatness, budget, out);
+        }
+    }
+}
+
+/// 两条弦 p0-p2 的交点参数，平行时返回 None
+fn chord_intersection(a: &QuadraticBezier, b: &QuadraticBezier) -> Option<(f64, f64)> {
+    let (d1x, d1y) = (a.p2.x - a.p0.x, a.p2.y - a.p0.y);
+    let (d2x, d2y) = (b.p2.x - b.p0.x, b.p2.y - b.p0.y);
+    let denom = d1x * d2y - d1y * d2x;
+    if denom.abs() < 1e-300 {
+        return None;
+    }
+    let (ex, ey) = (b.p0.x - a.p0.x, b.p0.y - a.p0.y);
+    let s = (ex * d2y - ey * d2x) / denom;
+    let u = (ex * d1y - ey * d1x) / denom;
+    const SLACK: f64 = 1e-9;
+    let inside = |v: f64| (-SLACK..=1.0 + SLACK).contains(&v);
+    (inside(s) && inside(u)).then(|| (s.clamp(0.0, 1.0), u.clamp(0.0, 1.0)))
+}
+
+/// 对 A(s) - B(u) = 0 做几步牛顿迭代，保留残差最小的参数
+fn refine(a: &QuadraticBezier, b: &QuadraticBezier, mut s: f64, mut u: f64) -> (f64, f64) {
+    let residual = |s: f64, u: f64| a.evaluate(s).distance_to(&b.evaluate(u));
+    let mut best = (s, u, residual(s, u));
+    for _ in 0..6 {
+        let (pa, pb) = (a.evaluate(s), b.evaluate(u));
+        let (fx, fy) = (pa.x - pb.x, pa.y - pb.y);
+        let (da, db) = (a.derivative(s), b.derivative(u));
+        // J = [A'(s), -B'(u)]
+        let det = -da.x * db.y + da.y * db.x;
+        if det.abs() < 1e-300 {
+            break;
+        }
+        let ds = (-fx * db.y + fy * db.x) / det;
+        let du = (da.x * fy - da.y * fx) / det;
+        s = (s - ds).clamp(0.0, 1.0);
+        u = (u - du).clamp(0.0, 1.0);
+        let r = residual(s, u);
+        if r < best.2 {
+            best = (s, u, r);
+        }
+    }
+    (best.0, best.1)
+}
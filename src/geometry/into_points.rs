@@ -0,0 +1,53 @@
+use alloc::borrow::Cow;
+
+use super::point::Point2D;
+use alloc::vec::Vec;
+
+/// 可作为拟合输入的点序列；`Point2D` 切片直接借用，其他布局转换为新的缓冲区
+pub trait IntoPoints {
+    fn to_points(&self) -> Cow<'_, [Point2D]>;
+}
+
+impl IntoPoints for [Point2D] {
+    fn to_points(&self) -> Cow<'_, [Point2D]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl IntoPoints for [[f64; 2]] {
+    fn to_points(&self) -> Cow<'_, [Point2D]> {
+        Cow::Owned(self.iter().map(|&[x, y]| Point2D::new(x, y)).collect())
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl IntoPoints for [nalgebra::Point2<f64>] {
+    fn to_points(&self) -> Cow<'_, [Point2D]> {
+        Cow::Owned(self.iter().map(|p| Point2D::new(p.x, p.y)).collect())
+    }
+}
+
+#[cfg(feature = "glam")]
+impl IntoPoints for [glam::DVec2] {
+    fn to_points(&self) -> Cow<'_, [Point2D]> {
+        Cow::Owned(self.iter().map(|p| Point2D::new(p.x, p.y)).collect())
+    }
+}
+
+impl<T> IntoPoints for Vec<T>
+where
+    [T]: IntoPoints,
+{
+    fn to_points(&self) -> Cow<'_, [Point2D]> {
+        self.as_slice().to_points()
+    }
+}
+
+impl<T, const N: usize> IntoPoints for [T; N]
+where
+    [T]: IntoPoints,
+{
+    fn to_points(&self) -> Cow<'_, [Point2D]> {
+        self.as_slice().to_points()
+    }
+}
@@ -1,5 +1,26 @@
 pub mod point;
+pub mod point3d;
+pub mod arc;
 pub mod bezier;
+pub mod bezier3d;
+pub mod bbox;
+pub mod bspline;
+pub mod cubic;
+mod intersect;
+pub mod into_points;
+pub mod scalar;
+pub mod segment;
+pub mod transform;
 
-pub use point::Point2D;
-pub use bezier::QuadraticBezier;
\ No newline at end of file
+pub use point::{Point2, Point2D};
+pub use point3d::Point3D;
+pub use arc::CircularArc;
+pub use bezier::{QuadBezier, QuadraticBezier};
+pub use bezier3d::QuadraticBezier3D;
+pub use bbox::BoundingBox;
+pub use bspline::QuadraticBSpline;
+pub use cubic::CubicBezier;
+pub use into_points::IntoPoints;
+pub use scalar::Scalar;
+pub use segment::Segment;
+pub use transform::Transform2D;
\ No newline at end of file
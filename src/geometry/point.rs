@@ -1,44 +1,65 @@
+use super::scalar::Scalar;
+use super::transform::Transform2D;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
-pub struct Point2D {
-    pub x: f64,
-    pub y: f64,
+/// 二维点，标量类型默认为 f64
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Point2<T = f64> {
+    pub x: T,
+    pub y: T,
 }
 
-impl Point2D {
-    pub fn new(x: f64, y: f64) -> Self {
+/// 双精度点，库中其余部分均使用该类型
+pub type Point2D = Point2<f64>;
+
+impl<T: Scalar> Point2<T> {
+    pub fn new(x: T, y: T) -> Self {
         Self { x, y }
     }
 
-    pub fn distance_to(&self, other: &Point2D) -> f64 {
-        let dx = self.x - other.x;
-        let dy = self.y - other.y;
-        (dx * dx + dy * dy).sqrt()
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+
+    pub fn distance_to(&self, other: &Point2<T>) -> T {
+        self.distance_squared(other).sqrt()
     }
 
-    pub fn distance_squared(&self, other: &Point2D) -> f64 {
+    pub fn distance_squared(&self, other: &Point2<T>) -> T {
         let dx = self.x - other.x;
         let dy = self.y - other.y;
         dx * dx + dy * dy
     }
 
-    pub fn lerp(&self, other: &Point2D, t: f64) -> Point2D {
-        Point2D {
+    pub fn lerp(&self, other: &Point2<T>, t: T) -> Point2<T> {
+        Point2 {
             x: self.x + (other.x - self.x) * t,
             y: self.y + (other.y - self.y) * t,
         }
     }
+
+    /// 转换标量类型，如 f64 与 f32 互转
+    pub fn cast<U: Scalar>(&self) -> Point2<U> {
+        Point2::new(U::from_f64(self.x.to_f64()), U::from_f64(self.y.to_f64()))
+    }
+}
+
+impl Point2D {
+    pub fn transform(&self, m: &Transform2D) -> Point2D {
+        m.apply(self)
+    }
 }
 
-impl From<(f64, f64)> for Point2D {
-    fn from((x, y): (f64, f64)) -> Self {
-        Point2D::new(x, y)
+impl<T: Scalar> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2::new(x, y)
     }
 }
 
-impl From<Point2D> for (f64, f64) {
-    fn from(p: Point2D) -> Self {
+impl<T: Scalar> From<Point2<T>> for (T, T) {
+    fn from(p: Point2<T>) -> Self {
         (p.x, p.y)
     }
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "std"))]
+use crate::math::FloatMath;
+
+/// 三维点，用于空间轨迹拟合
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Point3D {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Point3D {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+
+    pub fn distance_to(&self, other: &Point3D) -> f64 {
+        self.distance_squared(other).sqrt()
+    }
+
+    pub fn distance_squared(&self, other: &Point3D) -> f64 {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        let dz = self.z - other.z;
+        dx * dx + dy * dy + dz * dz
+    }
+
+    pub fn lerp(&self, other: &Point3D, t: f64) -> Point3D {
+        Point3D {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+            z: self.z + (other.z - self.z) * t,
+        }
+    }
+}
+
+impl From<(f64, f64, f64)> for Point3D {
+    fn from((x, y, z): (f64, f64, f64)) -> Self {
+        Point3D::new(x, y, z)
+    }
+}
+
+impl From<Point3D> for (f64, f64, f64) {
+    fn from(p: Point3D) -> Self {
+        (p.x, p.y, p.z)
+    }
+}
@@ -0,0 +1,59 @@
+use core::fmt::Debug;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// 几何类型和单段拟合使用的浮点标量，已为 f32 和 f64 实现
+pub trait Scalar:
+    Copy
+    + Debug
+    + Default
+    + PartialOrd
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn from_f64(v: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! impl_scalar {
+    ($t:ty) => {
+        impl Scalar for $t {
+            fn from_f64(v: f64) -> Self {
+                v as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            #[cfg(feature = "std")]
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+
+            #[cfg(not(feature = "std"))]
+            fn sqrt(self) -> Self {
+                crate::math::FloatMath::sqrt(self)
+            }
+
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            fn is_finite(self) -> bool {
+                <$t>::is_finite(self)
+            }
+        }
+    };
+}
+
+impl_scalar!(f32);
+impl_scalar!(f64);
@@ -0,0 +1,94 @@
+use super::arc::CircularArc;
+use super::bezier::QuadraticBezier;
+use super::point::Point2D;
+use super::transform::Transform2D;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// 输出路径中的一段图元
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Segment {
+    Line { start: Point2D, end: Point2D },
+    Quad(QuadraticBezier),
+    Arc(CircularArc),
+}
+
+impl Segment {
+    /// 控制点到弦的距离不超过 tolerance 时视为直线
+    pub fn from_quad(curve: &QuadraticBezier, line_tolerance: Option<f64>) -> Segment {
+        match line_tolerance {
+            Some(tol) if curve.chord_deviation() <= tol => Segment::Line {
+                start: curve.p0,
+                end: curve.p2,
+            },
+            _ => Segment::Quad(*curve),
+        }
+    }
+
+    /// 图元的弧长
+    pub fn length(&self) -> f64 {
+        match self {
+            Segment::Line { start, end } => start.distance_to(end),
+            Segment::Quad(c) => c.arc_length(),
+            Segment::Arc(a) => a.radius * a.sweep.abs(),
+        }
+    }
+
+    /// 点到图元的最短距离
+    pub fn distance_to_point(&self, p: &Point2D) -> f64 {
+        match self {
+            Segment::Line { start, end } => {
+                let (dx, dy) = (end.x - start.x, end.y - start.y);
+                let len2 = dx * dx + dy * dy;
+                let t = if len2 > 0.0 {
+                    (((p.x - start.x) * dx + (p.y - start.y) * dy) / len2).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                p.distance_to(&start.lerp(end, t))
+            }
+            Segment::Quad(c) => c.distance_to_point(p),
+            Segment::Arc(a) => a.distance_to_point(p),
+        }
+    }
+
+    pub fn start(&self) -> Point2D {
+        match self {
+            Segment::Line { start, .. } => *start,
+            Segment::Quad(c) => c.p0,
+            Segment::Arc(a) => a.start,
+        }
+    }
+
+    pub fn end(&self) -> Point2D {
+        match self {
+            Segment::Line { end, .. } => *end,
+            Segment::Quad(c) => c.p2,
+            Segment::Arc(a) => a.end,
+        }
+    }
+
+    pub fn is_line(&self) -> bool {
+        matches!(self, Segment::Line { .. })
+    }
+
+    pub fn is_arc(&self) -> bool {
+        matches!(self, Segment::Arc(_))
+    }
+
+    /// 非相似变换会把圆弧变成椭圆弧，此时改用其二次近似
+    pub fn transform(&self, m: &Transform2D) -> Segment {
+        match self {
+            Segment::Line { start, end } => Segment::Line {
+                start: m.apply(start),
+                end: m.apply(end),
+            },
+            Segment::Quad(c) => Segment::Quad(c.transform(m)),
+            Segment::Arc(a) => a
+                .transform(m)
+                .map(Segment::Arc)
+                .unwrap_or_else(|| Segment::Quad(a.to_quadratic().transform(m))),
+        }
+    }
+}
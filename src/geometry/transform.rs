@@ -0,0 +1,92 @@
+use super::point::Point2D;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "std"))]
+use crate::math::FloatMath;
+
+/// 二维仿射变换，矩阵约定与 SVG 的 `matrix(a b c d e f)` 一致：
+/// x' = a·x + c·y + e，y' = b·x + d·y + f
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transform2D {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform2D {
+    pub fn new(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+    }
+
+    pub fn translate(tx: f64, ty: f64) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, tx, ty)
+    }
+
+    pub fn scale(sx: f64, sy: f64) -> Self {
+        Self::new(sx, 0.0, 0.0, sy, 0.0, 0.0)
+    }
+
+    /// 绕原点旋转，角度为弧度
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    /// 错切，角度为弧度
+    pub fn skew(angle_x: f64, angle_y: f64) -> Self {
+        Self::new(1.0, angle_y.tan(), angle_x.tan(), 1.0, 0.0, 0.0)
+    }
+
+    /// 先应用 self 再应用 other
+    pub fn then(&self, other: &Transform2D) -> Transform2D {
+        Transform2D {
+            a: other.a * self.a + other.c * self.b,
+            b: other.b * self.a + other.d * self.b,
+            c: other.a * self.c + other.c * self.d,
+            d: other.b * self.c + other.d * self.d,
+            e: other.a * self.e + other.c * self.f + other.e,
+            f: other.b * self.e + other.d * self.f + other.f,
+        }
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// 逆变换，矩阵奇异时返回 None
+    pub fn inverse(&self) -> Option<Transform2D> {
+        let det = self.determinant();
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        Some(Transform2D {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            e: (self.c * self.f - self.d * self.e) / det,
+            f: (self.b * self.e - self.a * self.f) / det,
+        })
+    }
+
+    pub fn apply(&self, p: &Point2D) -> Point2D {
+        Point2D {
+            x: self.a * p.x + self.c * p.y + self.e,
+            y: self.b * p.x + self.d * p.y + self.f,
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+use crate::geometry::Point2D;
+use crate::optimizer::FitResult;
+
+use super::wkt::Sampling;
+
+/// CSV 读写选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// 分隔符，None 时逗号、分号和空白都视为分隔符
+    pub delimiter: Option<char>,
+    /// x、y 所在的列（从 0 开始）
+    pub x_column: usize,
+    pub y_column: usize,
+    /// 写出时首行输出 `x,y` 表头
+    pub header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            x_column: 0,
+            y_column: 1,
+            header: false,
+        }
+    }
+}
+
+/// CSV 读写错误，`line` 为出错的行号（从 1 开始），读写失败时为 None
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    pub line: Option<usize>,
+    pub reason: String,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.reason),
+            None => write!(f, "csv: {}", self.reason),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<std::io::Error> for CsvError {
+    fn from(e: std::io::Error) -> Self {
+        CsvError {
+            line: None,
+            reason: e.to_string(),
+        }
+    }
+}
+
+/// 解析 CSV 文本；跳过空行、`#` 注释和首个数据行之前无法解析的表头
+pub fn parse_points(text: &str, options: &CsvOptions) -> Result<Vec<Point2D>, CsvError> {
+    read_points(text.as_bytes(), options)
+}
+
+/// 从任意 reader 逐行读取点
+pub fn read_points(reader: impl Read, options: &CsvOptions) -> Result<Vec<Point2D>, CsvError> {
+    let columns = options.x_column.max(options.y_column) + 1;
+    let mut points = Vec::new();
+    for (lineno, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = match options.delimiter {
+            Some(d) => line.split(d).map(str::trim).collect(),
+            None => line
+                .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                .filter(|f| !f.is_empty())
+                .collect(),
+        };
+        let invalid = |reason: String| CsvError {
+            line: Some(lineno + 1),
+            reason,
+        };
+        if fields.len() < columns {
+            return Err(invalid(format!("expected at least {columns} columns")));
+        }
+        match (
+            fields[options.x_column].parse::<f64>(),
+            fields[options.y_column].parse::<f64>(),
+        ) {
+            (Ok(x), Ok(y)) => points.push(Point2D::new(x, y)),
+            _ if points.is_empty() => continue, // 表头
+            _ => return Err(invalid("invalid number".to_string())),
+        }
+    }
+    Ok(points)
+}
+
+/// 从文件读取点
+pub fn read_points_file(
+    path: impl AsRef<Path>,
+    options: &CsvOptions,
+) -> Result<Vec<Point2D>, CsvError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| CsvError {
+        line: None,
+        reason: format!("{}: {e}", path.display()),
+    })?;
+    read_points(file, options)
+}
+
+/// 把点逐行写为 `x,y`，分隔符未指定时用逗号
+pub fn write_points(
+    mut writer: impl Write,
+    points: &[Point2D],
+    options: &CsvOptions,
+) -> Result<(), CsvError> {
+    let d = options.delimiter.unwrap_or(',');
+    if options.header {
+        writeln!(writer, "x{d}y")?;
+    }
+    for p in points {
+        writeln!(writer, "{}{d}{}", p.x, p.y)?;
+    }
+    Ok(())
+}
+
+/// 采样拟合结果并写为 CSV，相邻分段的公共端点只写一次
+pub fn write_samples(
+    writer: impl Write,
+    result: &FitResult,
+    sampling: impl Into<Sampling>,
+    options: &CsvOptions,
+) -> Result<(), CsvError> {
+    write_points(writer, &result.sampled_polyline(sampling.into()), options)
+}
@@ -0,0 +1,150 @@
+use std::fmt::Write;
+
+use crate::geometry::QuadraticBezier;
+use crate::optimizer::{FitDocument, FitResult};
+
+/// DXF 中曲线的实体类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DxfEntity {
+    /// 二次 B 样条：相邻分段共用端点，内部节点重复两次，与分段二次贝塞尔完全等价
+    Spline,
+    /// 每段采样若干点后输出为折线，供不支持 SPLINE 的老旧软件使用
+    Polyline { samples_per_segment: usize },
+}
+
+/// DXF 输出选项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DxfOptions {
+    pub entity: DxfEntity,
+    /// 实体所在图层
+    pub layer: String,
+}
+
+impl Default for DxfOptions {
+    fn default() -> Self {
+        Self {
+            entity: DxfEntity::Spline,
+            layer: "0".to_string(),
+        }
+    }
+}
+
+/// 把多个拟合结果写入同一个 DXF（R2000）文档；端点不相连的分段拆成独立实体
+pub fn write_dxf(results: &[FitResult], options: &DxfOptions) -> String {
+    let mut out = String::new();
+    push_pair(&mut out, 0, "SECTION");
+    push_pair(&mut out, 2, "HEADER");
+    push_pair(&mut out, 9, "$ACADVER");
+    push_pair(&mut out, 1, "AC1015");
+    push_pair(&mut out, 0, "ENDSEC");
+    push_pair(&mut out, 0, "SECTION");
+    push_pair(&mut out, 2, "ENTITIES");
+
+    for result in results {
+        for run in connected_runs(&result.curves) {
+            match options.entity {
+                DxfEntity::Spline => write_spline(&mut out, run, &options.layer),
+                DxfEntity::Polyline {
+                    samples_per_segment,
+                } => write_polyline(&mut out, run, samples_per_segment, &options.layer),
+            }
+        }
+    }
+
+    push_pair(&mut out, 0, "ENDSEC");
+    push_pair(&mut out, 0, "EOF");
+    out
+}
+
+/// 按端点是否相连切分曲线序列
+fn connected_runs(curves: &[QuadraticBezier]) -> Vec<&[QuadraticBezier]> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for i in 1..curves.len() {
+        if curves[i].p0 != curves[i - 1].p2 {
+            runs.push(&curves[start..i]);
+            start = i;
+        }
+    }
+    if start < curves.len() {
+        runs.push(&curves[start..]);
+    }
+    runs
+}
+
+fn push_pair(out: &mut String, code: u16, value: impl std::fmt::Display) {
+    let _ = write!(out, "{code}\n{value}\n");
+}
+
+fn push_point(out: &mut String, x: f64, y: f64) {
+    push_pair(out, 10, x);
+    push_pair(out, 20, y);
+    push_pair(out, 30, 0.0);
+}
+
+fn write_spline(out: &mut String, curves: &[QuadraticBezier], layer: &str) {
+    let k = curves.len();
+    push_pair(out, 0, "SPLINE");
+    push_pair(out, 100, "AcDbEntity");
+    push_pair(out, 8, layer);
+    push_pair(out, 100, "AcDbSpline");
+    push_pair(out, 70, 8); // 平面曲线
+    push_pair(out, 71, 2);
+    push_pair(out, 72, 2 * k + 4);
+    push_pair(out, 73, 2 * k + 1);
+    push_pair(out, 74, 0);
+
+    // 节点向量 [0,0,0, 1,1, …, k-1,k-1, k,k,k]
+    for _ in 0..3 {
+        push_pair(out, 40, 0);
+    }
+    for i in 1..k {
+        push_pair(out, 40, i);
+        push_pair(out, 40, i);
+    }
+    for _ in 0..3 {
+        push_pair(out, 40, k);
+    }
+
+    push_point(out, curves[0].p0.x, curves[0].p0.y);
+    for curve in curves {
+        push_point(out, curve.p1.x, curve.p1.y);
+        push_point(out, curve.p2.x, curve.p2.y);
+    }
+}
+
+fn write_polyline(out: &mut String, curves: &[QuadraticBezier], samples: usize, layer: &str) {
+    push_pair(out, 0, "POLYLINE");
+    push_pair(out, 8, layer);
+    push_pair(out, 66, 1);
+    push_pair(out, 70, 0);
+    push_point(out, 0.0, 0.0);
+    for (k, curve) in curves.iter().enumerate() {
+        // 相邻分段共享端点，只输出一次
+        for p in curve
+            .sample(samples.max(2))
+            .into_iter()
+            .skip(usize::from(k > 0))
+        {
+            push_pair(out, 0, "VERTEX");
+            push_pair(out, 8, layer);
+            push_point(out, p.x, p.y);
+        }
+    }
+    push_pair(out, 0, "SEQEND");
+    push_pair(out, 8, layer);
+}
+
+impl FitResult {
+    /// 导出为 DXF 文档，供 CAM / 激光切割流程使用
+    pub fn to_dxf(&self, options: &DxfOptions) -> String {
+        write_dxf(std::slice::from_ref(self), options)
+    }
+}
+
+impl FitDocument {
+    /// 所有路径写入同一个 DXF 文档
+    pub fn to_dxf(&self, options: &DxfOptions) -> String {
+        write_dxf(&self.paths, options)
+    }
+}
@@ -0,0 +1,115 @@
+use serde_json::{json, Value};
+
+use crate::geometry::Point2D;
+use crate::optimizer::FitResult;
+
+/// 解析 GeoJSON 中的所有 LineString / MultiLineString，每条线返回一组点。
+/// 支持 FeatureCollection、Feature、GeometryCollection 以及裸 Geometry
+pub fn from_geojson(text: &str) -> Result<Vec<Vec<Point2D>>, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("geojson: {e}"))?;
+    let mut lines = Vec::new();
+    collect_lines(&value, &mut lines)?;
+    Ok(lines)
+}
+
+fn collect_lines(value: &Value, lines: &mut Vec<Vec<Point2D>>) -> Result<(), String> {
+    let kind = value
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or("geojson: object without \"type\"")?;
+
+    match kind {
+        "FeatureCollection" => {
+            let features = value
+                .get("features")
+                .and_then(Value::as_array)
+                .ok_or("geojson: FeatureCollection without \"features\"")?;
+            for feature in features {
+                collect_lines(feature, lines)?;
+            }
+        }
+        "Feature" => {
+            // geometry 为 null 的要素直接跳过
+            if let Some(geometry) = value.get("geometry").filter(|g| !g.is_null()) {
+                collect_lines(geometry, lines)?;
+            }
+        }
+        "GeometryCollection" => {
+            let geometries = value
+                .get("geometries")
+                .and_then(Value::as_array)
+                .ok_or("geojson: GeometryCollection without \"geometries\"")?;
+            for geometry in geometries {
+                collect_lines(geometry, lines)?;
+            }
+        }
+        "LineString" => {
+            lines.push(parse_positions(coordinates(value)?)?);
+        }
+        "MultiLineString" => {
+            let parts = coordinates(value)?
+                .as_array()
+                .ok_or("geojson: MultiLineString coordinates must be an array")?;
+            for part in parts {
+                lines.push(parse_positions(part)?);
+            }
+        }
+        // 点、面等几何与曲线拟合无关，忽略
+        _ => {}
+    }
+    Ok(())
+}
+
+fn coordinates(geometry: &Value) -> Result<&Value, String> {
+    geometry
+        .get("coordinates")
+        .ok_or_else(|| "geojson: geometry without \"coordinates\"".to_string())
+}
+
+fn parse_positions(value: &Value) -> Result<Vec<Point2D>, String> {
+    let positions = value
+        .as_array()
+        .ok_or("geojson: LineString coordinates must be an array")?;
+    positions
+        .iter()
+        .map(|pos| {
+            let pos = pos.as_array().ok_or("geojson: position must be an array")?;
+            match (
+                pos.first().and_then(Value::as_f64),
+                pos.get(1).and_then(Value::as_f64),
+            ) {
+                (Some(x), Some(y)) => Ok(Point2D::new(x, y)),
+                _ => Err("geojson: position needs numeric x and y".to_string()),
+            }
+        })
+        .collect()
+}
+
+impl FitResult {
+    /// 采样后导出为 GeoJSON LineString Feature，properties 中附带误差统计
+    pub fn to_geojson_linestring(&self, samples_per_segment: usize) -> String {
+        let mut coords: Vec<[f64; 2]> = Vec::new();
+        for (k, curve) in self.curves.iter().enumerate() {
+            let samples = curve.sample(samples_per_segment.max(2));
+            // 相邻分段共享端点，只保留一次
+            let skip = usize::from(k > 0);
+            coords.extend(samples.into_iter().skip(skip).map(|p| [p.x, p.y]));
+        }
+
+        let feature = json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "LineString",
+                "coordinates": coords,
+            },
+            "properties": {
+                "num_segments": self.num_segments,
+                "total_error": self.total_error,
+                "max_error": self.config.max_error,
+                "min_segment_len": self.config.min_segment_len,
+                "max_segment_len": self.config.max_segment_len,
+            },
+        });
+        feature.to_string()
+    }
+}
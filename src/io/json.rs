@@ -0,0 +1,521 @@
+//! `FitResult` 的版本化 JSON schema，与内部的 serde 结构解耦：字段改名或新增不会改变已发布版本的形状。
+//!
+//! 当前为 `schema_version: 1`，点统一写成 `{"x": .., "y": ..}` 对象，区间写成 `{"start": .., "end": ..}`，
+//! 图元和状态用 `type` / `kind` 字段区分；`total_error` 和 `segment_errors` 中的 `null` 表示误差为无穷大。
+//! 完整定义见 `schema/fit_result.v1.schema.json`
+
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::geometry::{CircularArc, Point2D, QuadraticBezier, Segment};
+use crate::optimizer::{
+    Backend, ErrorMetric, FitConfig, FitResult, FitStatus, Objective, Precision, SegmentInfo,
+};
+
+/// `FitResult::to_json` 输出的 schema 版本
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// 与 schema 第 1 版一一对应的类型
+pub mod v1 {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    pub struct Point {
+        pub x: f64,
+        pub y: f64,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    pub struct QuadraticBezier {
+        pub p0: Point,
+        pub p1: Point,
+        pub p2: Point,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "type", rename_all = "lowercase")]
+    pub enum Segment {
+        Line {
+            start: Point,
+            end: Point,
+        },
+        Quad {
+            p0: Point,
+            p1: Point,
+            p2: Point,
+        },
+        Arc {
+            start: Point,
+            end: Point,
+            center: Point,
+            radius: f64,
+            sweep: f64,
+        },
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "kind", rename_all = "lowercase")]
+    pub enum Status {
+        Optimal,
+        Relaxed { achieved_error: f64 },
+        Infeasible,
+        Degraded,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct Interval {
+        pub start: usize,
+        pub end: usize,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    pub struct TimeRange {
+        pub start: f64,
+        pub end: f64,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    pub struct SegmentInfo {
+        pub start_index: usize,
+        pub end_index: usize,
+        pub arc_length: f64,
+        pub max_deviation: f64,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Metric {
+        Mse,
+        Max,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Backend {
+        Auto,
+        Cpu,
+        Cuda,
+        Wgpu,
+        #[serde(rename = "cuda-verified")]
+        CudaVerified,
+        OpenCl,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Precision {
+        F32,
+        F64,
+    }
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "kind", rename_all = "snake_case")]
+    pub enum Objective {
+        #[default]
+        MinSegments,
+        Penalized { lambda: f64 },
+    }
+
+    /// `FitConfig` 中与结果解读相关的字段，其余字段读回时取默认值
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct FitConfig {
+        pub min_segment_len: usize,
+        pub max_segment_len: usize,
+        /// null 表示无穷大
+        pub max_error: Option<f64>,
+        pub metric: Metric,
+        pub backend: Backend,
+        pub precision: Precision,
+        pub normalize: bool,
+        pub line_tolerance: Option<f64>,
+        pub dedup_distance: Option<f64>,
+        pub required_breakpoints: Vec<usize>,
+        pub timestamps: Vec<f64>,
+        pub function_graph: bool,
+        /// 缺省为 `min_segments`
+        #[serde(default)]
+        pub objective: Objective,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct FitResult {
+        pub schema_version: u64,
+        pub status: Status,
+        pub total_error: Option<f64>,
+        pub num_segments: usize,
+        pub curves: Vec<QuadraticBezier>,
+        pub segments: Vec<Segment>,
+        pub breakpoints: Vec<usize>,
+        pub segment_errors: Vec<Option<f64>>,
+        pub blocking_intervals: Vec<Interval>,
+        pub segment_times: Vec<TimeRange>,
+        pub segment_info: Vec<SegmentInfo>,
+        pub config: FitConfig,
+    }
+}
+
+/// `FitResult::from_json` 的错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonSchemaError {
+    /// 不是合法的 JSON，或字段缺失、类型不符；`message` 来自 serde_json
+    Syntax { message: String },
+    /// `schema_version` 缺失或不是本版本支持的值
+    UnsupportedVersion { found: Option<u64> },
+    /// 字段值违反 schema 的约束，`field` 为出错字段的路径，如 `breakpoints[3]`
+    InvalidField { field: String, reason: String },
+}
+
+impl fmt::Display for JsonSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonSchemaError::Syntax { message } => write!(f, "json: {}", message),
+            JsonSchemaError::UnsupportedVersion {
+                found: Some(version),
+            } => write!(
+                f,
+                "unsupported schema_version {} (expected {})",
+                version, SCHEMA_VERSION
+            ),
+            JsonSchemaError::UnsupportedVersion { found: None } => {
+                write!(f, "missing schema_version (expected {})", SCHEMA_VERSION)
+            }
+            JsonSchemaError::InvalidField { field, reason } => write!(f, "{}: {}", field, reason),
+        }
+    }
+}
+
+impl std::error::Error for JsonSchemaError {}
+
+impl From<serde_json::Error> for JsonSchemaError {
+    fn from(e: serde_json::Error) -> Self {
+        JsonSchemaError::Syntax {
+            message: e.to_string(),
+        }
+    }
+}
+
+impl From<Point2D> for v1::Point {
+    fn from(p: Point2D) -> Self {
+        v1::Point { x: p.x, y: p.y }
+    }
+}
+
+impl From<v1::Point> for Point2D {
+    fn from(p: v1::Point) -> Self {
+        Point2D::new(p.x, p.y)
+    }
+}
+
+impl From<&QuadraticBezier> for v1::QuadraticBezier {
+    fn from(c: &QuadraticBezier) -> Self {
+        v1::QuadraticBezier {
+            p0: c.p0.into(),
+            p1: c.p1.into(),
+            p2: c.p2.into(),
+        }
+    }
+}
+
+impl From<v1::QuadraticBezier> for QuadraticBezier {
+    fn from(c: v1::QuadraticBezier) -> Self {
+        QuadraticBezier::new(c.p0.into(), c.p1.into(), c.p2.into())
+    }
+}
+
+impl From<&Segment> for v1::Segment {
+    fn from(segment: &Segment) -> Self {
+        match segment {
+            Segment::Line { start, end } => v1::Segment::Line {
+                start: (*start).into(),
+                end: (*end).into(),
+            },
+            Segment::Quad(c) => v1::Segment::Quad {
+                p0: c.p0.into(),
+                p1: c.p1.into(),
+                p2: c.p2.into(),
+            },
+            Segment::Arc(a) => v1::Segment::Arc {
+                start: a.start.into(),
+                end: a.end.into(),
+                center: a.center.into(),
+                radius: a.radius,
+                sweep: a.sweep,
+            },
+        }
+    }
+}
+
+impl From<v1::Segment> for Segment {
+    fn from(segment: v1::Segment) -> Self {
+        match segment {
+            v1::Segment::Line { start, end } => Segment::Line {
+                start: start.into(),
+                end: end.into(),
+            },
+            v1::Segment::Quad { p0, p1, p2 } => {
+                Segment::Quad(QuadraticBezier::new(p0.into(), p1.into(), p2.into()))
+            }
+            v1::Segment::Arc {
+                start,
+                end,
+                center,
+                radius,
+                sweep,
+            } => Segment::Arc(CircularArc {
+                start: start.into(),
+                end: end.into(),
+                center: center.into(),
+                radius,
+                sweep,
+            }),
+        }
+    }
+}
+
+impl From<&FitConfig> for v1::FitConfig {
+    fn from(config: &FitConfig) -> Self {
+        v1::FitConfig {
+            min_segment_len: config.min_segment_len,
+            max_segment_len: config.max_segment_len,
+            max_error: finite(config.max_error),
+            metric: match config.metric {
+                ErrorMetric::Mse => v1::Metric::Mse,
+                ErrorMetric::Max => v1::Metric::Max,
+            },
+            backend: match config.backend {
+                Backend::Auto => v1::Backend::Auto,
+                Backend::Cpu => v1::Backend::Cpu,
+                Backend::Cuda => v1::Backend::Cuda,
+                Backend::Wgpu => v1::Backend::Wgpu,
+                Backend::CudaVerified => v1::Backend::CudaVerified,
+                Backend::OpenCl => v1::Backend::OpenCl,
+            },
+            precision: match config.precision {
+                Precision::F32 => v1::Precision::F32,
+                Precision::F64 => v1::Precision::F64,
+            },
+            normalize: config.normalize,
+            line_tolerance: config.line_tolerance,
+            dedup_distance: config.dedup_distance,
+            required_breakpoints: config.required_breakpoints.clone(),
+            timestamps: config.timestamps.clone(),
+            function_graph: config.function_graph,
+            objective: match config.objective {
+                Objective::MinSegments => v1::Objective::MinSegments,
+                Objective::Penalized { lambda } => v1::Objective::Penalized { lambda },
+            },
+        }
+    }
+}
+
+impl From<v1::FitConfig> for FitConfig {
+    /// schema 中没有的字段取 `FitConfig::default()` 的值
+    fn from(config: v1::FitConfig) -> Self {
+        FitConfig {
+            min_segment_len: config.min_segment_len,
+            max_segment_len: config.max_segment_len,
+            max_error: config.max_error.unwrap_or(f64::INFINITY),
+            metric: match config.metric {
+                v1::Metric::Mse => ErrorMetric::Mse,
+                v1::Metric::Max => ErrorMetric::Max,
+            },
+            backend: match config.backend {
+                v1::Backend::Auto => Backend::Auto,
+                v1::Backend::Cpu => Backend::Cpu,
+                v1::Backend::Cuda => Backend::Cuda,
+                v1::Backend::Wgpu => Backend::Wgpu,
+                v1::Backend::CudaVerified => Backend::CudaVerified,
+                v1::Backend::OpenCl => Backend::OpenCl,
+            },
+            precision: match config.precision {
+                v1::Precision::F32 => Precision::F32,
+                v1::Precision::F64 => Precision::F64,
+            },
+            normalize: config.normalize,
+            line_tolerance: config.line_tolerance,
+            dedup_distance: config.dedup_distance,
+            required_breakpoints: config.required_breakpoints,
+            timestamps: config.timestamps,
+            function_graph: config.function_graph,
+            objective: match config.objective {
+                v1::Objective::MinSegments => Objective::MinSegments,
+                v1::Objective::Penalized { lambda } => Objective::Penalized { lambda },
+            },
+            ..FitConfig::default()
+        }
+    }
+}
+
+/// JSON 没有无穷大，非有限的误差写成 null
+fn finite(error: f64) -> Option<f64> {
+    error.is_finite().then_some(error)
+}
+
+impl From<&FitResult> for v1::FitResult {
+    fn from(result: &FitResult) -> Self {
+        v1::FitResult {
+            schema_version: SCHEMA_VERSION,
+            status: match result.status {
+                FitStatus::Optimal => v1::Status::Optimal,
+                FitStatus::Relaxed { achieved_error } => v1::Status::Relaxed { achieved_error },
+                FitStatus::Infeasible => v1::Status::Infeasible,
+                FitStatus::Degraded => v1::Status::Degraded,
+            },
+            total_error: finite(result.total_error),
+            num_segments: result.num_segments,
+            curves: result.curves.iter().map(Into::into).collect(),
+            segments: result.segments.iter().map(Into::into).collect(),
+            breakpoints: result.breakpoints.clone(),
+            segment_errors: result.segment_errors.iter().map(|&e| finite(e)).collect(),
+            blocking_intervals: result
+                .blocking_intervals
+                .iter()
+                .map(|&(start, end)| v1::Interval { start, end })
+                .collect(),
+            segment_times: result
+                .segment_times
+                .iter()
+                .map(|&(start, end)| v1::TimeRange { start, end })
+                .collect(),
+            segment_info: result
+                .segment_info
+                .iter()
+                .map(|info| v1::SegmentInfo {
+                    start_index: info.start_index,
+                    end_index: info.end_index,
+                    arc_length: info.arc_length,
+                    max_deviation: info.max_deviation,
+                })
+                .collect(),
+            config: (&result.config).into(),
+        }
+    }
+}
+
+impl TryFrom<v1::FitResult> for FitResult {
+    type Error = JsonSchemaError;
+
+    /// 检查各数组长度与 `curves` 一致、分段下标有序以及配置有效
+    fn try_from(result: v1::FitResult) -> Result<Self, Self::Error> {
+        let invalid =
+            |field: String, reason: String| Err(JsonSchemaError::InvalidField { field, reason });
+        let n = result.curves.len();
+        if result.num_segments != n {
+            return invalid(
+                "num_segments".to_string(),
+                format!("{} does not match {} curves", result.num_segments, n),
+            );
+        }
+        // segment_times、segment_info 在部分结果（如点数过少时）中为空
+        let per_segment = [
+            ("segments", result.segments.len(), false),
+            ("segment_errors", result.segment_errors.len(), false),
+            ("segment_times", result.segment_times.len(), true),
+            ("segment_info", result.segment_info.len(), true),
+        ];
+        for (field, len, optional) in per_segment {
+            if len != n && !(optional && len == 0) {
+                return invalid(
+                    field.to_string(),
+                    format!("expected {} entries, found {}", n, len),
+                );
+            }
+        }
+        if !(result.breakpoints.is_empty() && n == 0) && result.breakpoints.len() != n + 1 {
+            return invalid(
+                "breakpoints".to_string(),
+                format!(
+                    "expected {} entries, found {}",
+                    n + 1,
+                    result.breakpoints.len()
+                ),
+            );
+        }
+        if let Some(k) = result.breakpoints.windows(2).position(|w| w[1] < w[0]) {
+            return invalid(
+                format!("breakpoints[{}]", k + 1),
+                "breakpoints must be non-decreasing".to_string(),
+            );
+        }
+        if let Some(k) = result
+            .blocking_intervals
+            .iter()
+            .position(|i| i.end < i.start)
+        {
+            return invalid(
+                format!("blocking_intervals[{}]", k),
+                "end precedes start".to_string(),
+            );
+        }
+        let config = FitConfig::from(result.config);
+        if let Err(e) = config.validate() {
+            return invalid("config".to_string(), e.reason);
+        }
+
+        Ok(FitResult {
+            curves: result.curves.into_iter().map(Into::into).collect(),
+            total_error: result.total_error.unwrap_or(f64::INFINITY),
+            num_segments: n,
+            config,
+            breakpoints: result.breakpoints,
+            segments: result.segments.into_iter().map(Into::into).collect(),
+            segment_errors: result
+                .segment_errors
+                .iter()
+                .map(|e| e.unwrap_or(f64::INFINITY))
+                .collect(),
+            status: match result.status {
+                v1::Status::Optimal => FitStatus::Optimal,
+                v1::Status::Relaxed { achieved_error } => FitStatus::Relaxed { achieved_error },
+                v1::Status::Infeasible => FitStatus::Infeasible,
+                v1::Status::Degraded => FitStatus::Degraded,
+            },
+            blocking_intervals: result
+                .blocking_intervals
+                .iter()
+                .map(|i| (i.start, i.end))
+                .collect(),
+            segment_times: result
+                .segment_times
+                .iter()
+                .map(|t| (t.start, t.end))
+                .collect(),
+            segment_info: result
+                .segment_info
+                .iter()
+                .map(|info| SegmentInfo {
+                    start_index: info.start_index,
+                    end_index: info.end_index,
+                    arc_length: info.arc_length,
+                    max_deviation: info.max_deviation,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// 只读出 `schema_version`，先于完整解析检查版本，使旧版本的输入得到明确的错误
+#[derive(Deserialize)]
+struct VersionProbe {
+    schema_version: Option<u64>,
+}
+
+impl FitResult {
+    /// 按版本化 schema 导出为 JSON，见 `io::json`
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&v1::FitResult::from(self))
+    }
+
+    /// 解析 `to_json` 的输出，检查 `schema_version` 和字段间的一致性
+    pub fn from_json(text: &str) -> Result<Self, JsonSchemaError> {
+        let probe: VersionProbe = serde_json::from_str(text)?;
+        if probe.schema_version != Some(SCHEMA_VERSION) {
+            return Err(JsonSchemaError::UnsupportedVersion {
+                found: probe.schema_version,
+            });
+        }
+        let result: v1::FitResult = serde_json::from_str(text)?;
+        result.try_into()
+    }
+}
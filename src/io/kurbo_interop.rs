@@ -0,0 +1,44 @@
+#![cfg(feature = "kurbo")]
+
+use kurbo::BezPath;
+
+use crate::geometry::{Point2D, QuadraticBezier};
+use crate::optimizer::FitResult;
+
+impl From<Point2D> for kurbo::Point {
+    fn from(p: Point2D) -> Self {
+        kurbo::Point::new(p.x, p.y)
+    }
+}
+
+impl From<kurbo::Point> for Point2D {
+    fn from(p: kurbo::Point) -> Self {
+        Point2D::new(p.x, p.y)
+    }
+}
+
+impl From<QuadraticBezier> for kurbo::QuadBez {
+    fn from(c: QuadraticBezier) -> Self {
+        kurbo::QuadBez::new(c.p0, c.p1, c.p2)
+    }
+}
+
+impl From<kurbo::QuadBez> for QuadraticBezier {
+    fn from(c: kurbo::QuadBez) -> Self {
+        QuadraticBezier::new(c.p0.into(), c.p1.into(), c.p2.into())
+    }
+}
+
+impl FitResult {
+    /// 转换为 kurbo 路径，每段输出为一条二次曲线
+    pub fn to_kurbo_bezpath(&self) -> BezPath {
+        let mut path = BezPath::new();
+        if let Some(first) = self.curves.first() {
+            path.move_to(first.p0);
+            for curve in &self.curves {
+                path.quad_to(curve.p1, curve.p2);
+            }
+        }
+        path
+    }
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "lyon")]
+
+use lyon_path::math::point;
+use lyon_path::Path;
+
+use crate::optimizer::FitResult;
+
+impl FitResult {
+    /// 构建由二次曲线组成的 lyon 路径（开放子路径），坐标转为 f32，可直接交给 lyon 的曲面细分器
+    pub fn to_lyon_path(&self) -> Path {
+        let mut builder = Path::builder();
+        if let Some(first) = self.curves.first() {
+            builder.begin(point(first.p0.x as f32, first.p0.y as f32));
+            for curve in &self.curves {
+                builder.quadratic_bezier_to(
+                    point(curve.p1.x as f32, curve.p1.y as f32),
+                    point(curve.p2.x as f32, curve.p2.y as f32),
+                );
+            }
+            builder.end(false);
+        }
+        builder.build()
+    }
+}
@@ -0,0 +1,22 @@
+pub mod csv;
+pub mod dxf;
+pub mod geojson;
+pub mod json;
+#[cfg(feature = "kurbo")]
+pub mod kurbo_interop;
+#[cfg(feature = "lyon")]
+pub mod lyon_interop;
+pub mod npy;
+pub mod polyline;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+pub mod svg;
+pub mod truetype;
+pub mod wkt;
+
+pub use csv::{CsvError, CsvOptions};
+pub use dxf::{DxfEntity, DxfOptions};
+pub use json::JsonSchemaError;
+pub use svg::{SvgDocumentOptions, SvgOptions};
+pub use truetype::TrueTypePoint;
+pub use wkt::Sampling;
\ No newline at end of file
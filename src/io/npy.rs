@@ -0,0 +1,106 @@
+use crate::geometry::Point2D;
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// 读取 NumPy `.npy` 格式的 (N, 2) 或 (2, N) 浮点数组，(2, 2) 视为两个点；
+/// 支持 f8 / f4、大小端和 Fortran 顺序
+pub fn from_npy(bytes: &[u8]) -> Result<Vec<Point2D>, String> {
+    if bytes.len() < 10 || &bytes[..6] != MAGIC {
+        return Err("npy: missing magic string".to_string());
+    }
+    let (header_len, offset) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (
+            u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+            12,
+        ),
+        v => return Err(format!("npy: unsupported format version {v}")),
+    };
+    let header = bytes
+        .get(offset..offset + header_len)
+        .and_then(|h| std::str::from_utf8(h).ok())
+        .ok_or("npy: truncated header")?;
+    let data = &bytes[offset + header_len..];
+
+    let descr = header_value(header, "descr")
+        .and_then(|v| v.strip_prefix('\''))
+        .and_then(|v| v.split('\'').next())
+        .ok_or("npy: missing descr")?;
+    let fortran_order = header_value(header, "fortran_order")
+        .ok_or("npy: missing fortran_order")?
+        .starts_with("True");
+    let shape: Vec<usize> = header_value(header, "shape")
+        .and_then(|v| v.strip_prefix('('))
+        .and_then(|v| v.split(')').next())
+        .ok_or("npy: missing shape")?
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| {
+            d.parse()
+                .map_err(|_| format!("npy: invalid shape dimension {d}"))
+        })
+        .collect::<Result<_, _>>()?;
+    let (rows, cols) = match shape[..] {
+        [rows, cols] if cols == 2 || rows == 2 => (rows, cols),
+        _ => {
+            return Err(format!(
+                "npy: expected shape (N, 2) or (2, N), got {shape:?}"
+            ))
+        }
+    };
+
+    let values: Vec<f64> = match descr {
+        "<f8" => data
+            .chunks_exact(8)
+            .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+            .collect(),
+        ">f8" => data
+            .chunks_exact(8)
+            .map(|c| f64::from_be_bytes(c.try_into().unwrap()))
+            .collect(),
+        "<f4" => data
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes(c.try_into().unwrap()) as f64)
+            .collect(),
+        ">f4" => data
+            .chunks_exact(4)
+            .map(|c| f32::from_be_bytes(c.try_into().unwrap()) as f64)
+            .collect(),
+        _ => {
+            return Err(format!(
+                "npy: unsupported dtype {descr} (expected f8 or f4)"
+            ))
+        }
+    };
+    let count = rows.checked_mul(cols).ok_or("npy: shape too large")?;
+    if values.len() < count {
+        return Err(format!(
+            "npy: expected {count} values, got {}",
+            values.len()
+        ));
+    }
+
+    let at = |i: usize, j: usize| {
+        if fortran_order {
+            values[j * rows + i]
+        } else {
+            values[i * cols + j]
+        }
+    };
+    Ok(if cols == 2 {
+        (0..rows)
+            .map(|i| Point2D::new(at(i, 0), at(i, 1)))
+            .collect()
+    } else {
+        (0..cols)
+            .map(|j| Point2D::new(at(0, j), at(1, j)))
+            .collect()
+    })
+}
+
+/// header 是 Python 字典字面量，取 `'key':` 之后的原始文本
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header.find(&format!("'{key}':"))? + key.len() + 3;
+    Some(header[start..].trim_start())
+}
@@ -0,0 +1,88 @@
+//! Google encoded polyline 格式。点的 x 为经度、y 为纬度，与 GeoJSON 约定一致；
+//! 标准精度为 5 位小数，OSRM / Valhalla 等使用 6 位
+
+use crate::geometry::Point2D;
+use crate::optimizer::FitResult;
+
+/// 解码为点集
+pub fn decode(encoded: &str, precision: u32) -> Result<Vec<Point2D>, String> {
+    let factor = 10f64.powi(precision as i32);
+    let bytes = encoded.as_bytes();
+    let mut points = Vec::new();
+    let (mut lat, mut lng) = (0i64, 0i64);
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        lat += decode_value(bytes, &mut pos)?;
+        if pos >= bytes.len() {
+            return Err("polyline: truncated coordinate pair".to_string());
+        }
+        lng += decode_value(bytes, &mut pos)?;
+        points.push(Point2D::new(lng as f64 / factor, lat as f64 / factor));
+    }
+    Ok(points)
+}
+
+fn decode_value(bytes: &[u8], pos: &mut usize) -> Result<i64, String> {
+    let mut result = 0i64;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*pos).ok_or("polyline: unexpected end of input")?;
+        if !(63..=126).contains(&byte) {
+            return Err(format!("polyline: invalid character at {}", *pos));
+        }
+        *pos += 1;
+        let chunk = (byte - 63) as i64;
+        if shift > 60 {
+            return Err("polyline: value overflow".to_string());
+        }
+        result |= (chunk & 0x1f) << shift;
+        shift += 5;
+        if chunk < 0x20 {
+            break;
+        }
+    }
+    Ok(if result & 1 != 0 {
+        !(result >> 1)
+    } else {
+        result >> 1
+    })
+}
+
+/// 编码点集
+pub fn encode(points: &[Point2D], precision: u32) -> String {
+    let factor = 10f64.powi(precision as i32);
+    let mut out = String::new();
+    let (mut prev_lat, mut prev_lng) = (0i64, 0i64);
+
+    for p in points {
+        let lat = (p.y * factor).round() as i64;
+        let lng = (p.x * factor).round() as i64;
+        encode_value(lat - prev_lat, &mut out);
+        encode_value(lng - prev_lng, &mut out);
+        prev_lat = lat;
+        prev_lng = lng;
+    }
+    out
+}
+
+fn encode_value(value: i64, out: &mut String) {
+    let mut v = if value < 0 { !(value << 1) } else { value << 1 };
+    while v >= 0x20 {
+        out.push((((v & 0x1f) | 0x20) as u8 + 63) as char);
+        v >>= 5;
+    }
+    out.push((v as u8 + 63) as char);
+}
+
+impl FitResult {
+    /// 采样拟合结果并编码为 polyline，相邻分段的公共端点只输出一次
+    pub fn to_encoded_polyline(&self, samples_per_segment: usize, precision: u32) -> String {
+        let mut points = Vec::new();
+        for (k, curve) in self.curves.iter().enumerate() {
+            let samples = curve.sample(samples_per_segment.max(2));
+            points.extend(samples.into_iter().skip(usize::from(k > 0)));
+        }
+        encode(&points, precision)
+    }
+}
@@ -0,0 +1,482 @@
+#![cfg(feature = "protobuf")]
+
+//! `proto/bezier_dp_fit.proto` 的 prost 实现，供非 Rust 服务读取拟合结果
+
+use prost::{DecodeError, Message};
+
+use crate::geometry::{CircularArc, Point2D, QuadraticBezier, Segment};
+use crate::optimizer::{
+    Backend, ErrorMetric, FitConfig, FitResult, FitStatus, Precision, SegmentInfo,
+};
+
+/// 与 `.proto` 文件一一对应的消息类型
+pub mod pb {
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct Point {
+        #[prost(double, tag = "1")]
+        pub x: f64,
+        #[prost(double, tag = "2")]
+        pub y: f64,
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct QuadraticBezier {
+        #[prost(message, optional, tag = "1")]
+        pub p0: Option<Point>,
+        #[prost(message, optional, tag = "2")]
+        pub p1: Option<Point>,
+        #[prost(message, optional, tag = "3")]
+        pub p2: Option<Point>,
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct LineSegment {
+        #[prost(message, optional, tag = "1")]
+        pub start: Option<Point>,
+        #[prost(message, optional, tag = "2")]
+        pub end: Option<Point>,
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct CircularArc {
+        #[prost(message, optional, tag = "1")]
+        pub start: Option<Point>,
+        #[prost(message, optional, tag = "2")]
+        pub end: Option<Point>,
+        #[prost(message, optional, tag = "3")]
+        pub center: Option<Point>,
+        #[prost(double, tag = "4")]
+        pub radius: f64,
+        #[prost(double, tag = "5")]
+        pub sweep: f64,
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct Segment {
+        #[prost(oneof = "segment::Kind", tags = "1, 2, 3")]
+        pub kind: Option<segment::Kind>,
+    }
+
+    pub mod segment {
+        #[derive(Clone, Copy, PartialEq, prost::Oneof)]
+        pub enum Kind {
+            #[prost(message, tag = "1")]
+            Line(super::LineSegment),
+            #[prost(message, tag = "2")]
+            Quad(super::QuadraticBezier),
+            #[prost(message, tag = "3")]
+            Arc(super::CircularArc),
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum ErrorMetric {
+        Mse = 0,
+        Max = 1,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Backend {
+        Auto = 0,
+        Cpu = 1,
+        Cuda = 2,
+        Wgpu = 3,
+        CudaVerified = 4,
+        OpenCl = 5,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Precision {
+        F64 = 0,
+        F32 = 1,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FitConfig {
+        #[prost(uint64, tag = "1")]
+        pub min_segment_len: u64,
+        #[prost(uint64, tag = "2")]
+        pub max_segment_len: u64,
+        #[prost(double, tag = "3")]
+        pub max_error: f64,
+        #[prost(enumeration = "ErrorMetric", tag = "4")]
+        pub metric: i32,
+        #[prost(enumeration = "Backend", tag = "5")]
+        pub backend: i32,
+        #[prost(enumeration = "Precision", tag = "6")]
+        pub precision: i32,
+        #[prost(bool, tag = "7")]
+        pub normalize: bool,
+        #[prost(double, optional, tag = "8")]
+        pub line_tolerance: Option<f64>,
+        #[prost(double, optional, tag = "9")]
+        pub dedup_distance: Option<f64>,
+        #[prost(uint64, repeated, tag = "10")]
+        pub required_breakpoints: Vec<u64>,
+        #[prost(double, repeated, tag = "11")]
+        pub timestamps: Vec<f64>,
+        #[prost(bool, tag = "12")]
+        pub function_graph: bool,
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct FitStatus {
+        #[prost(enumeration = "fit_status::Kind", tag = "1")]
+        pub kind: i32,
+        #[prost(double, tag = "2")]
+        pub achieved_error: f64,
+    }
+
+    pub mod fit_status {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+        #[repr(i32)]
+        pub enum Kind {
+            Optimal = 0,
+            Relaxed = 1,
+            Infeasible = 2,
+            Degraded = 3,
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct Interval {
+        #[prost(uint64, tag = "1")]
+        pub start: u64,
+        #[prost(uint64, tag = "2")]
+        pub end: u64,
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct TimeRange {
+        #[prost(double, tag = "1")]
+        pub start: f64,
+        #[prost(double, tag = "2")]
+        pub end: f64,
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct SegmentInfo {
+        #[prost(uint64, tag = "1")]
+        pub start_index: u64,
+        #[prost(uint64, tag = "2")]
+        pub end_index: u64,
+        #[prost(double, tag = "3")]
+        pub arc_length: f64,
+        #[prost(double, tag = "4")]
+        pub max_deviation: f64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FitResult {
+        #[prost(message, repeated, tag = "1")]
+        pub curves: Vec<QuadraticBezier>,
+        #[prost(double, tag = "2")]
+        pub total_error: f64,
+        #[prost(uint64, tag = "3")]
+        pub num_segments: u64,
+        #[prost(message, optional, tag = "4")]
+        pub config: Option<FitConfig>,
+        #[prost(uint64, repeated, tag = "5")]
+        pub breakpoints: Vec<u64>,
+        #[prost(message, repeated, tag = "6")]
+        pub segments: Vec<Segment>,
+        #[prost(double, repeated, tag = "7")]
+        pub segment_errors: Vec<f64>,
+        #[prost(message, optional, tag = "8")]
+        pub status: Option<FitStatus>,
+        #[prost(message, repeated, tag = "9")]
+        pub blocking_intervals: Vec<Interval>,
+        #[prost(message, repeated, tag = "10")]
+        pub segment_times: Vec<TimeRange>,
+        #[prost(message, repeated, tag = "11")]
+        pub segment_info: Vec<SegmentInfo>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FitBatch {
+        #[prost(message, repeated, tag = "1")]
+        pub results: Vec<FitResult>,
+    }
+}
+
+// proto3 中缺失的消息字段按默认值处理，因此转换不会失败
+
+impl From<Point2D> for pb::Point {
+    fn from(p: Point2D) -> Self {
+        pb::Point { x: p.x, y: p.y }
+    }
+}
+
+fn point(p: Option<pb::Point>) -> Point2D {
+    let p = p.unwrap_or_default();
+    Point2D::new(p.x, p.y)
+}
+
+impl From<&QuadraticBezier> for pb::QuadraticBezier {
+    fn from(c: &QuadraticBezier) -> Self {
+        pb::QuadraticBezier {
+            p0: Some(c.p0.into()),
+            p1: Some(c.p1.into()),
+            p2: Some(c.p2.into()),
+        }
+    }
+}
+
+impl From<pb::QuadraticBezier> for QuadraticBezier {
+    fn from(c: pb::QuadraticBezier) -> Self {
+        QuadraticBezier::new(point(c.p0), point(c.p1), point(c.p2))
+    }
+}
+
+impl From<&Segment> for pb::Segment {
+    fn from(segment: &Segment) -> Self {
+        use pb::segment::Kind;
+        let kind = match segment {
+            Segment::Line { start, end } => Kind::Line(pb::LineSegment {
+                start: Some((*start).into()),
+                end: Some((*end).into()),
+            }),
+            Segment::Quad(c) => Kind::Quad(c.into()),
+            Segment::Arc(a) => Kind::Arc(pb::CircularArc {
+                start: Some(a.start.into()),
+                end: Some(a.end.into()),
+                center: Some(a.center.into()),
+                radius: a.radius,
+                sweep: a.sweep,
+            }),
+        };
+        pb::Segment { kind: Some(kind) }
+    }
+}
+
+impl From<pb::Segment> for Segment {
+    fn from(segment: pb::Segment) -> Self {
+        use pb::segment::Kind;
+        match segment.kind {
+            Some(Kind::Line(l)) => Segment::Line {
+                start: point(l.start),
+                end: point(l.end),
+            },
+            Some(Kind::Quad(c)) => Segment::Quad(c.into()),
+            Some(Kind::Arc(a)) => Segment::Arc(CircularArc {
+                start: point(a.start),
+                end: point(a.end),
+                center: point(a.center),
+                radius: a.radius,
+                sweep: a.sweep,
+            }),
+            None => Segment::Quad(pb::QuadraticBezier::default().into()),
+        }
+    }
+}
+
+impl From<&FitConfig> for pb::FitConfig {
+    fn from(config: &FitConfig) -> Self {
+        pb::FitConfig {
+            min_segment_len: config.min_segment_len as u64,
+            max_segment_len: config.max_segment_len as u64,
+            max_error: config.max_error,
+            metric: match config.metric {
+                ErrorMetric::Mse => pb::ErrorMetric::Mse,
+                ErrorMetric::Max => pb::ErrorMetric::Max,
+            } as i32,
+            backend: match config.backend {
+                Backend::Auto => pb::Backend::Auto,
+                Backend::Cpu => pb::Backend::Cpu,
+                Backend::Cuda => pb::Backend::Cuda,
+                Backend::Wgpu => pb::Backend::Wgpu,
+                Backend::CudaVerified => pb::Backend::CudaVerified,
+                Backend::OpenCl => pb::Backend::OpenCl,
+            } as i32,
+            precision: match config.precision {
+                Precision::F64 => pb::Precision::F64,
+                Precision::F32 => pb::Precision::F32,
+            } as i32,
+            normalize: config.normalize,
+            line_tolerance: config.line_tolerance,
+            dedup_distance: config.dedup_distance,
+            required_breakpoints: config
+                .required_breakpoints
+                .iter()
+                .map(|&i| i as u64)
+                .collect(),
+            timestamps: config.timestamps.clone(),
+            function_graph: config.function_graph,
+        }
+    }
+}
+
+impl From<pb::FitConfig> for FitConfig {
+    /// schema 中没有的字段取 `FitConfig::default()` 的值
+    fn from(config: pb::FitConfig) -> Self {
+        FitConfig {
+            min_segment_len: config.min_segment_len as usize,
+            max_segment_len: config.max_segment_len as usize,
+            max_error: config.max_error,
+            metric: match config.metric() {
+                pb::ErrorMetric::Mse => ErrorMetric::Mse,
+                pb::ErrorMetric::Max => ErrorMetric::Max,
+            },
+            backend: match config.backend() {
+                pb::Backend::Auto => Backend::Auto,
+                pb::Backend::Cpu => Backend::Cpu,
+                pb::Backend::Cuda => Backend::Cuda,
+                pb::Backend::Wgpu => Backend::Wgpu,
+                pb::Backend::CudaVerified => Backend::CudaVerified,
+                pb::Backend::OpenCl => Backend::OpenCl,
+            },
+            precision: match config.precision() {
+                pb::Precision::F64 => Precision::F64,
+                pb::Precision::F32 => Precision::F32,
+            },
+            normalize: config.normalize,
+            line_tolerance: config.line_tolerance,
+            dedup_distance: config.dedup_distance,
+            required_breakpoints: config
+                .required_breakpoints
+                .iter()
+                .map(|&i| i as usize)
+                .collect(),
+            timestamps: config.timestamps,
+            function_graph: config.function_graph,
+            ..FitConfig::default()
+        }
+    }
+}
+
+impl From<&FitResult> for pb::FitResult {
+    fn from(result: &FitResult) -> Self {
+        let status = match result.status {
+            FitStatus::Optimal => pb::FitStatus::default(),
+            FitStatus::Relaxed { achieved_error } => pb::FitStatus {
+                kind: pb::fit_status::Kind::Relaxed as i32,
+                achieved_error,
+            },
+            FitStatus::Infeasible => pb::FitStatus {
+                kind: pb::fit_status::Kind::Infeasible as i32,
+                achieved_error: 0.0,
+            },
+            FitStatus::Degraded => pb::FitStatus {
+                kind: pb::fit_status::Kind::Degraded as i32,
+                achieved_error: 0.0,
+            },
+        };
+        pb::FitResult {
+            curves: result.curves.iter().map(Into::into).collect(),
+            total_error: result.total_error,
+            num_segments: result.num_segments as u64,
+            config: Some((&result.config).into()),
+            breakpoints: result.breakpoints.iter().map(|&i| i as u64).collect(),
+            segments: result.segments.iter().map(Into::into).collect(),
+            segment_errors: result.segment_errors.clone(),
+            status: Some(status),
+            blocking_intervals: result
+                .blocking_intervals
+                .iter()
+                .map(|&(start, end)| pb::Interval {
+                    start: start as u64,
+                    end: end as u64,
+                })
+                .collect(),
+            segment_times: result
+                .segment_times
+                .iter()
+                .map(|&(start, end)| pb::TimeRange { start, end })
+                .collect(),
+            segment_info: result
+                .segment_info
+                .iter()
+                .map(|info| pb::SegmentInfo {
+                    start_index: info.start_index as u64,
+                    end_index: info.end_index as u64,
+                    arc_length: info.arc_length,
+                    max_deviation: info.max_deviation,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<pb::FitResult> for FitResult {
+    fn from(result: pb::FitResult) -> Self {
+        let status = result.status.unwrap_or_default();
+        FitResult {
+            curves: result.curves.into_iter().map(Into::into).collect(),
+            total_error: result.total_error,
+            num_segments: result.num_segments as usize,
+            config: result.config.map(Into::into).unwrap_or_default(),
+            breakpoints: result.breakpoints.iter().map(|&i| i as usize).collect(),
+            segments: result.segments.into_iter().map(Into::into).collect(),
+            segment_errors: result.segment_errors,
+            status: match status.kind() {
+                pb::fit_status::Kind::Optimal => FitStatus::Optimal,
+                pb::fit_status::Kind::Relaxed => FitStatus::Relaxed {
+                    achieved_error: status.achieved_error,
+                },
+                pb::fit_status::Kind::Infeasible => FitStatus::Infeasible,
+                pb::fit_status::Kind::Degraded => FitStatus::Degraded,
+            },
+            blocking_intervals: result
+                .blocking_intervals
+                .iter()
+                .map(|i| (i.start as usize, i.end as usize))
+                .collect(),
+            segment_times: result
+                .segment_times
+                .iter()
+                .map(|t| (t.start, t.end))
+                .collect(),
+            segment_info: result
+                .segment_info
+                .iter()
+                .map(|info| SegmentInfo {
+                    start_index: info.start_index as usize,
+                    end_index: info.end_index as usize,
+                    arc_length: info.arc_length,
+                    max_deviation: info.max_deviation,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl FitConfig {
+    /// 按 `proto/bezier_dp_fit.proto` 的 `FitConfig` 消息编码
+    pub fn to_protobuf(&self) -> Vec<u8> {
+        pb::FitConfig::from(self).encode_to_vec()
+    }
+
+    /// 从 `to_protobuf` 的输出恢复，schema 之外的字段取默认值
+    pub fn from_protobuf(bytes: &[u8]) -> Result<Self, DecodeError> {
+        pb::FitConfig::decode(bytes).map(Into::into)
+    }
+}
+
+impl FitResult {
+    /// 按 `proto/bezier_dp_fit.proto` 的 `FitResult` 消息编码
+    pub fn to_protobuf(&self) -> Vec<u8> {
+        pb::FitResult::from(self).encode_to_vec()
+    }
+
+    /// 从 `to_protobuf` 的输出恢复
+    pub fn from_protobuf(bytes: &[u8]) -> Result<Self, DecodeError> {
+        pb::FitResult::decode(bytes).map(Into::into)
+    }
+}
+
+/// 把一批结果编码为 `FitBatch` 消息
+pub fn encode_batch(results: &[FitResult]) -> Vec<u8> {
+    pb::FitBatch {
+        results: results.iter().map(Into::into).collect(),
+    }
+    .encode_to_vec()
+}
+
+/// 解码 `encode_batch` 的输出
+pub fn decode_batch(bytes: &[u8]) -> Result<Vec<FitResult>, DecodeError> {
+    let batch = pb::FitBatch::decode(bytes)?;
+    Ok(batch.results.into_iter().map(Into::into).collect())
+}
@@ -0,0 +1,217 @@
+use std::fmt::Write;
+
+use crate::geometry::{BoundingBox, Point2D, Segment};
+use crate::optimizer::FitResult;
+
+/// SVG 路径输出选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// 坐标保留的小数位数
+    pub precision: usize,
+    /// 使用相对指令 `m`/`q`
+    pub relative: bool,
+    /// 末尾追加 `Z` 闭合路径
+    pub close_path: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            precision: 2,
+            relative: false,
+            close_path: false,
+        }
+    }
+}
+
+/// 完整 SVG 文档的样式选项
+#[derive(Debug, Clone)]
+pub struct SvgDocumentOptions<'a> {
+    pub path: SvgOptions,
+    pub stroke: String,
+    pub stroke_width: f64,
+    pub fill: String,
+    /// viewBox 四周留白
+    pub padding: f64,
+    /// 叠加显示原始输入点，便于目视检查
+    pub overlay_points: Option<&'a [Point2D]>,
+    pub point_radius: f64,
+    pub point_color: String,
+}
+
+impl Default for SvgDocumentOptions<'_> {
+    fn default() -> Self {
+        Self {
+            path: SvgOptions::default(),
+            stroke: "black".to_string(),
+            stroke_width: 1.0,
+            fill: "none".to_string(),
+            padding: 2.0,
+            overlay_points: None,
+            point_radius: 0.5,
+            point_color: "red".to_string(),
+        }
+    }
+}
+
+impl FitResult {
+    /// 旧版本序列化的结果没有 `segments` 字段，此时全部按二次曲线输出
+    fn output_segments(&self) -> Vec<Segment> {
+        if self.segments.len() == self.curves.len() {
+            self.segments.clone()
+        } else {
+            self.curves.iter().map(|c| Segment::Quad(*c)).collect()
+        }
+    }
+
+    /// 按选项生成 SVG 路径字符串
+    pub fn to_svg_path_with(&self, options: &SvgOptions) -> String {
+        let Some(first) = self.curves.first() else {
+            return String::new();
+        };
+
+        let prec = options.precision;
+        let mut path = if options.relative {
+            format!("m {:.*} {:.*}", prec, first.p0.x, prec, first.p0.y)
+        } else {
+            format!("M {:.*} {:.*}", prec, first.p0.x, prec, first.p0.y)
+        };
+
+        // 相对坐标基于已舍入的当前点计算，避免误差沿路径累积
+        let scale = 10f64.powi(prec as i32);
+        let round = |v: f64| (v * scale).round() / scale;
+        let (mut cx, mut cy) = (round(first.p0.x), round(first.p0.y));
+        for segment in self.output_segments() {
+            match (segment, options.relative) {
+                (Segment::Line { end, .. }, true) => {
+                    let (x2, y2) = (round(end.x), round(end.y));
+                    let _ = write!(path, " l {:.*} {:.*}", prec, x2 - cx, prec, y2 - cy);
+                    (cx, cy) = (x2, y2);
+                }
+                (Segment::Line { end, .. }, false) => {
+                    let _ = write!(path, " L {:.*} {:.*}", prec, end.x, prec, end.y);
+                }
+                (Segment::Quad(curve), true) => {
+                    let (x2, y2) = (round(curve.p2.x), round(curve.p2.y));
+                    let _ = write!(
+                        path,
+                        " q {:.*} {:.*}, {:.*} {:.*}",
+                        prec,
+                        round(curve.p1.x) - cx,
+                        prec,
+                        round(curve.p1.y) - cy,
+                        prec,
+                        x2 - cx,
+                        prec,
+                        y2 - cy
+                    );
+                    (cx, cy) = (x2, y2);
+                }
+                (Segment::Arc(arc), true) => {
+                    let (x2, y2) = (round(arc.end.x), round(arc.end.y));
+                    let _ = write!(
+                        path,
+                        " a {:.*} {:.*} 0 {} {} {:.*} {:.*}",
+                        prec,
+                        arc.radius,
+                        prec,
+                        arc.radius,
+                        arc.large_arc() as u8,
+                        (arc.sweep > 0.0) as u8,
+                        prec,
+                        x2 - cx,
+                        prec,
+                        y2 - cy
+                    );
+                    (cx, cy) = (x2, y2);
+                }
+                (Segment::Arc(arc), false) => {
+                    let _ = write!(
+                        path,
+                        " A {:.*} {:.*} 0 {} {} {:.*} {:.*}",
+                        prec,
+                        arc.radius,
+                        prec,
+                        arc.radius,
+                        arc.large_arc() as u8,
+                        (arc.sweep > 0.0) as u8,
+                        prec,
+                        arc.end.x,
+                        prec,
+                        arc.end.y
+                    );
+                }
+                (Segment::Quad(curve), false) => {
+                    let _ = write!(
+                        path,
+                        " Q {:.*} {:.*}, {:.*} {:.*}",
+                        prec, curve.p1.x, prec, curve.p1.y, prec, curve.p2.x, prec, curve.p2.y
+                    );
+                }
+            }
+        }
+
+        if options.close_path {
+            path.push_str(if options.relative { " z" } else { " Z" });
+        }
+        path
+    }
+
+    /// 以三次贝塞尔 `C` 指令输出路径，供偏好三次曲线的设计工具导入
+    pub fn to_svg_path_cubic(&self) -> String {
+        let Some(first) = self.curves.first() else {
+            return String::new();
+        };
+
+        let mut path = format!("M {:.2} {:.2}", first.p0.x, first.p0.y);
+        for cubic in self.to_cubic_curves() {
+            path.push(' ');
+            path.push_str(&cubic.to_svg_command());
+        }
+        path
+    }
+
+    /// 生成独立的 `<svg>` 文档，viewBox 由曲线的精确包围盒计算
+    pub fn to_svg_document(&self, options: &SvgDocumentOptions) -> String {
+        let mut bbox = self.bounding_box();
+        if let Some(points) = options.overlay_points.and_then(BoundingBox::from_points) {
+            bbox = Some(bbox.map_or(points, |b| b.union(&points)));
+        }
+        let bbox = bbox.unwrap_or(BoundingBox::new(
+            Point2D::new(0.0, 0.0),
+            Point2D::new(0.0, 0.0),
+        ));
+
+        let pad = options.padding;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{:.2} {:.2} {:.2} {:.2}\">\n",
+            bbox.min.x - pad,
+            bbox.min.y - pad,
+            bbox.width() + 2.0 * pad,
+            bbox.height() + 2.0 * pad
+        );
+        let _ = writeln!(
+            svg,
+            "  <path d=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+            self.to_svg_path_with(&options.path),
+            options.fill,
+            options.stroke,
+            options.stroke_width
+        );
+
+        if let Some(points) = options.overlay_points {
+            let _ = writeln!(svg, "  <g fill=\"{}\">", options.point_color);
+            for p in points {
+                let _ = writeln!(
+                    svg,
+                    "    <circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{}\"/>",
+                    p.x, p.y, options.point_radius
+                );
+            }
+            svg.push_str("  </g>\n");
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
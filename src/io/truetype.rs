@@ -0,0 +1,71 @@
+use crate::geometry::{Point2D, Segment};
+use crate::optimizer::FitResult;
+
+/// glyf 轮廓中的一个点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrueTypePoint {
+    pub x: f64,
+    pub y: f64,
+    pub on_curve: bool,
+}
+
+impl TrueTypePoint {
+    fn on(p: Point2D) -> Self {
+        Self {
+            x: p.x,
+            y: p.y,
+            on_curve: true,
+        }
+    }
+
+    fn off(p: Point2D) -> Self {
+        Self {
+            x: p.x,
+            y: p.y,
+            on_curve: false,
+        }
+    }
+}
+
+impl FitResult {
+    /// 按 glyf 轮廓格式输出交替的 on-curve / off-curve 点；
+    /// 恰好位于两个相邻 off-curve 点中点的 on-curve 点省略（TrueType 会隐式补出）
+    pub fn to_truetype_points(&self) -> Vec<TrueTypePoint> {
+        self.to_truetype_points_with_tolerance(1e-9)
+    }
+
+    /// 同 `to_truetype_points`，与中点的距离不超过 `tolerance` 即省略，
+    /// 坐标随后要取整到字体单位时可取 0.5。直线段只输出端点；
+    /// 首尾重合的闭合轮廓不重复输出起点
+    pub fn to_truetype_points_with_tolerance(&self, tolerance: f64) -> Vec<TrueTypePoint> {
+        let Some(first) = self.curves.first() else {
+            return Vec::new();
+        };
+
+        let mut points = vec![TrueTypePoint::on(first.p0)];
+        for (k, curve) in self.curves.iter().enumerate() {
+            let is_line = matches!(self.segments.get(k), Some(Segment::Line { .. }));
+            if !is_line {
+                // 上一个 on-curve 点夹在两个 off-curve 点之间且正好是中点时可以省略
+                if let [.., before, last] = points.as_slice() {
+                    let mid =
+                        Point2D::new((before.x + curve.p1.x) * 0.5, (before.y + curve.p1.y) * 0.5);
+                    if last.on_curve
+                        && !before.on_curve
+                        && Point2D::new(last.x, last.y).distance_to(&mid) <= tolerance
+                    {
+                        points.pop();
+                    }
+                }
+                points.push(TrueTypePoint::off(curve.p1));
+            }
+            points.push(TrueTypePoint::on(curve.p2));
+        }
+
+        let last = self.curves[self.curves.len() - 1].p2;
+        if points.len() > 1 && last == first.p0 {
+            points.pop();
+        }
+        points
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt::Write;
+
+use crate::geometry::{Point2D, QuadraticBezier};
+use crate::optimizer::FitResult;
+
+/// 曲线转折线时的采样方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sampling {
+    /// 每段固定采样点数（含端点）
+    PerSegment(usize),
+    /// 每段按曲率均匀细分，保证折线与曲线的偏差不超过该值
+    MaxDeviation(f64),
+}
+
+impl From<usize> for Sampling {
+    fn from(samples_per_segment: usize) -> Self {
+        Sampling::PerSegment(samples_per_segment)
+    }
+}
+
+impl Sampling {
+    /// 单段曲线的采样点数
+    fn samples_for(&self, curve: &QuadraticBezier) -> usize {
+        match *self {
+            Sampling::PerSegment(n) => n.max(2),
+            Sampling::MaxDeviation(tolerance) => {
+                // 步长 h 的弦与二次曲线的最大偏差为 |p0 - 2p1 + p2|·h²/4
+                let ax = curve.p0.x - 2.0 * curve.p1.x + curve.p2.x;
+                let ay = curve.p0.y - 2.0 * curve.p1.y + curve.p2.y;
+                let a = (ax * ax + ay * ay).sqrt();
+                if a <= 0.0 || tolerance.is_nan() || tolerance <= 0.0 {
+                    return 2;
+                }
+                let pieces = (a / (4.0 * tolerance)).sqrt().ceil();
+                (pieces as usize).clamp(1, 1 << 16) + 1
+            }
+        }
+    }
+}
+
+impl FitResult {
+    /// 采样成折线，相邻分段的公共端点只保留一次
+    pub(crate) fn sampled_polyline(&self, sampling: Sampling) -> Vec<Point2D> {
+        let mut points = Vec::new();
+        for (k, curve) in self.curves.iter().enumerate() {
+            let samples = curve.sample(sampling.samples_for(curve));
+            points.extend(samples.into_iter().skip(usize::from(k > 0)));
+        }
+        points
+    }
+
+    /// 导出为 WKT `LINESTRING`，可直接用于 PostGIS 的 `ST_GeomFromText`；
+    /// 传入整数时按每段固定点数采样，也可传 `Sampling::MaxDeviation`
+    pub fn to_wkt_linestring(&self, sampling: impl Into<Sampling>) -> String {
+        let points = self.sampled_polyline(sampling.into());
+        if points.is_empty() {
+            return "LINESTRING EMPTY".to_string();
+        }
+        let mut wkt = String::from("LINESTRING(");
+        for (i, p) in points.iter().enumerate() {
+            if i > 0 {
+                wkt.push_str(", ");
+            }
+            let _ = write!(wkt, "{} {}", p.x, p.y);
+        }
+        wkt.push(')');
+        wkt
+    }
+
+    /// 导出为小端 WKB `LineString`，可用于 `ST_GeomFromWKB`
+    pub fn to_wkb_linestring(&self, sampling: impl Into<Sampling>) -> Vec<u8> {
+        let points = self.sampled_polyline(sampling.into());
+        let mut wkb = Vec::with_capacity(9 + points.len() * 16);
+        wkb.push(1); // 小端
+        wkb.extend_from_slice(&2u32.to_le_bytes()); // LineString
+        wkb.extend_from_slice(&(points.len() as u32).to_le_bytes());
+        for p in &points {
+            wkb.extend_from_slice(&p.x.to_le_bytes());
+            wkb.extend_from_slice(&p.y.to_le_bytes());
+        }
+        wkb
+    }
+}
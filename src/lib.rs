@@ -1,19 +1,46 @@
+//! 关闭默认的 `std` feature 时为 `no_std` + `alloc`：只保留 `geometry`、`fitting` 和单线程的
+//! `optimizer::serial`，超越函数由 libm 提供
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod geometry;
 pub mod fitting;
 pub mod optimizer;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+pub mod preprocess;
+#[cfg(feature = "service")]
+pub mod service;
+#[cfg(feature = "python")]
 mod python;
+mod math;
+#[cfg(feature = "std")]
+mod par;
 
 // 导出主要类型
-pub use geometry::{Point2D, QuadraticBezier};
-pub use fitting::{BezierFitter, FitError};
-pub use optimizer::{FitConfig, FitResult, DPOptimizer, fit_curve};
+pub use geometry::{BoundingBox, CircularArc, CubicBezier, IntoPoints, Point2, Point2D, Point3D, QuadBezier, QuadraticBSpline, QuadraticBezier, QuadraticBezier3D, Scalar, Segment, Transform2D};
+pub use fitting::{BezierFitter, FitError, RansacParams, RobustLoss};
+#[cfg(feature = "std")]
+pub use io::{DxfEntity, DxfOptions, JsonSchemaError, Sampling, SvgDocumentOptions, SvgOptions, TrueTypePoint};
+#[cfg(feature = "std")]
+pub use preprocess::Smoothing;
+#[cfg(feature = "std")]
+pub use optimizer::{AdaptiveWindow, Backend, CacheMode, ConfigError, CudaOptions, EndpointPolicy, ErrorCache, EquivarianceReport, ErrorMetric, FitConfig, FitConfigBuilder, FitDocument, FitterKind, FitCurveError, FitEstimate, FitParams, FitQuality, FitResult, FitResult3D, FitStatus, Multiresolution, Objective, ParityReport, PathMetric, Precision, PrimitiveSet, SegmentInfo, SegmentQuality, StreamingFitter, DPOptimizer, fit_curve, fit_curve_3d, fit_curve_chunked, fit_multistroke, interpolate_catmull_rom, try_fit_curve, try_fit_strokes};
 
-// Python模块入口
+// Python模块入口，需要 `python` feature（maturin 构建时自动启用）
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
+#[cfg(feature = "python")]
 #[pymodule]
 fn bezier_dp_fit(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(python::bindings::fit_curve_py, m)?)?;
+    m.add_function(wrap_pyfunction!(python::bindings::detect_corners_py, m)?)?;
+    m.add_function(wrap_pyfunction!(python::bindings::smooth_py, m)?)?;
+    m.add_function(wrap_pyfunction!(python::bindings::simplify_py, m)?)?;
     m.add_class::<python::bindings::PyFitResult>()?;
+    m.add_class::<python::bindings::PyFitSegment>()?;
     Ok(())
 }
@@ -0,0 +1,65 @@
+// no_std 下 f32 / f64 没有 sqrt、三角函数等固有方法，由 libm 按同名方法提供；
+// 启用 std 时固有方法优先，这里不参与编译
+#![cfg(not(feature = "std"))]
+
+pub(crate) trait FloatMath: Sized {
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+    fn sin_cos(self) -> (Self, Self);
+}
+
+macro_rules! impl_float_math {
+    ($t:ty, $sqrt:ident, $sin:ident, $cos:ident, $tan:ident, $atan2:ident, $hypot:ident, $pow:ident, $fmod:ident) => {
+        impl FloatMath for $t {
+            fn sqrt(self) -> Self {
+                libm::$sqrt(self)
+            }
+
+            fn sin(self) -> Self {
+                libm::$sin(self)
+            }
+
+            fn cos(self) -> Self {
+                libm::$cos(self)
+            }
+
+            fn tan(self) -> Self {
+                libm::$tan(self)
+            }
+
+            fn atan2(self, other: Self) -> Self {
+                libm::$atan2(self, other)
+            }
+
+            fn hypot(self, other: Self) -> Self {
+                libm::$hypot(self, other)
+            }
+
+            fn powi(self, n: i32) -> Self {
+                libm::$pow(self, n as $t)
+            }
+
+            fn rem_euclid(self, rhs: Self) -> Self {
+                let r = libm::$fmod(self, rhs);
+                if r < 0.0 {
+                    r + rhs.abs()
+                } else {
+                    r
+                }
+            }
+
+            fn sin_cos(self) -> (Self, Self) {
+                (libm::$sin(self), libm::$cos(self))
+            }
+        }
+    };
+}
+
+impl_float_math!(f32, sqrtf, sinf, cosf, tanf, atan2f, hypotf, powf, fmodf);
+impl_float_math!(f64, sqrt, sin, cos, tan, atan2, hypot, pow, fmod);
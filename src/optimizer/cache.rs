@@ -0,0 +1,177 @@
+use crate::fitting::{FitError, RobustLoss};
+use crate::geometry::Point2D;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::RangeInclusive;
+
+use super::config::{EndpointPolicy, ErrorMetric, FitConfig, FitterKind, Precision, PrimitiveSet};
+use super::dp::DPOptimizer;
+
+/// 区间误差缓存：键为 (起点下标, 终点下标)，两端均包含
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ErrorCache {
+    #[serde(serialize_with = "serialize_sorted")]
+    entries: HashMap<(usize, usize), FitError>,
+    /// 计算这些区间时的单段拟合方式；手工构造的缓存为 None，复用时不检查
+    #[serde(default)]
+    fit: Option<FitKind>,
+    /// 计算时的误差上限，超过的区间记为正无穷；手工构造的缓存为 0
+    #[serde(default)]
+    max_error: f64,
+}
+
+/// 决定单段拟合结果和误差含义的配置项，不同时缓存中的区间不能混用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct FitKind {
+    fitter: FitterKind,
+    robust: Option<RobustLoss>,
+    metric: ErrorMetric,
+    precision: Precision,
+    primitives: PrimitiveSet,
+    endpoint_policy: EndpointPolicy,
+    function_graph: bool,
+    timestamps: Vec<f64>,
+}
+
+impl FitKind {
+    fn of(config: &FitConfig) -> Self {
+        Self {
+            fitter: config.fitter,
+            robust: config.robust,
+            metric: config.metric,
+            precision: config.precision,
+            primitives: config.primitives,
+            endpoint_policy: config.endpoint_policy,
+            function_graph: config.function_graph,
+            timestamps: config.timestamps.clone(),
+        }
+    }
+}
+
+/// 按键排序序列化，同样的缓存总是得到相同的字节
+fn serialize_sorted<S: Serializer>(
+    entries: &HashMap<(usize, usize), FitError>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(entries.iter().collect::<BTreeMap<_, _>>())
+}
+
+impl ErrorCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 计算段长（点数）在 `bounds` 内所有区间的最小二乘拟合，误差完整计算不提前终止，
+    /// 因此可配合 `DPOptimizer::optimize_with_cache` 在不同 max_error 下反复使用
+    pub fn build(points: &[Point2D], bounds: RangeInclusive<usize>) -> Self {
+        let config = FitConfig {
+            min_segment_len: *bounds.start(),
+            max_segment_len: *bounds.end(),
+            ..FitConfig::default()
+        };
+        Self::build_for(points, &config)
+    }
+
+    /// 同 `build`，但按 `config` 的段长范围和单段拟合方式（拟合器、误差度量、精度、图元等）计算，
+    /// 忽略其 max_error
+    pub fn build_for(points: &[Point2D], config: &FitConfig) -> Self {
+        let config = FitConfig {
+            max_error: f64::INFINITY,
+            error_overrides: Vec::new(),
+            ..config.clone()
+        };
+        DPOptimizer::compute_error_cache(points, &config)
+    }
+
+    /// 记录计算缓存时的配置和误差上限
+    pub(crate) fn mark_source(&mut self, config: &FitConfig, max_error: f64) {
+        self.fit = Some(FitKind::of(config));
+        self.max_error = max_error;
+    }
+
+    /// 让缓存可用于 `config`，之后调用方须按完整误差补算缺失的区间：单段拟合方式不同时清空；
+    /// 原上限比 `config` 的上限严时，丢弃记为正无穷的区间
+    pub(crate) fn prepare_for(&mut self, config: &FitConfig) {
+        let fit = FitKind::of(config);
+        if self.fit.as_ref().is_some_and(|old| *old != fit) {
+            self.entries.clear();
+            self.max_error = f64::INFINITY;
+        } else if self.max_error < config.widest_max_error() {
+            self.entries.retain(|_, fit| fit.error.is_finite());
+            self.max_error = f64::INFINITY;
+        }
+        self.fit = Some(fit);
+    }
+
+    pub fn get(&self, start: usize, end: usize) -> Option<&FitError> {
+        self.entries.get(&(start, end))
+    }
+
+    pub fn contains(&self, start: usize, end: usize) -> bool {
+        self.entries.contains_key(&(start, end))
+    }
+
+    pub fn insert(&mut self, start: usize, end: usize, fit: FitError) {
+        self.entries.insert((start, end), fit);
+    }
+
+    /// 并入另一个缓存，重复的区间以 `other` 为准；误差上限取两者中较严的
+    pub fn merge(&mut self, other: ErrorCache) {
+        self.entries.extend(other.entries);
+        self.max_error = self.max_error.min(other.max_error);
+        if self.fit.is_none() {
+            self.fit = other.fit;
+        }
+    }
+
+    /// 只保留满足条件的区间
+    pub fn retain(&mut self, mut keep: impl FnMut(usize, usize) -> bool) {
+        self.entries.retain(|&(start, end), _| keep(start, end));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 导出为紧凑的二进制格式（bincode）
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// 从 `to_bytes` 的输出恢复缓存
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+}
+
+impl From<HashMap<(usize, usize), FitError>> for ErrorCache {
+    fn from(entries: HashMap<(usize, usize), FitError>) -> Self {
+        Self {
+            entries,
+            ..Self::default()
+        }
+    }
+}
+
+impl IntoIterator for ErrorCache {
+    type Item = ((usize, usize), FitError);
+    type IntoIter = std::collections::hash_map::IntoIter<(usize, usize), FitError>;
+
+    /// 按任意顺序取出所有区间
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl FromIterator<((usize, usize), FitError)> for ErrorCache {
+    fn from_iter<I: IntoIterator<Item = ((usize, usize), FitError)>>(iter: I) -> Self {
+        Self {
+            entries: iter.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+}
@@ -1,10 +1,294 @@
+use crate::fitting::{RansacParams, RobustLoss};
+use crate::geometry::{BoundingBox, Point2D};
+use crate::optimizer::ConfigError;
+use crate::preprocess::Smoothing;
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// 单段拟合算法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum FitterKind {
+    /// 固定端点的最小二乘（配合 `robust` 时为迭代加权最小二乘）
+    #[default]
+    LeastSquares,
+    /// 随机一致性采样，适合含传感器跳点的脏数据
+    Ransac(RansacParams),
+    /// 正交距离（全最小二乘）拟合，交替投影与求解至多 `iterations` 轮；
+    /// 比弦长参数化更贴合宽分段的曲率，代价是每个区间多次投影
+    Orthogonal { iterations: usize },
+}
+
+/// DP 可选用的分段图元
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrimitiveSet {
+    #[default]
+    Bezier,
+    /// 每个区间在二次曲线和圆弧之间取误差较小者，适合 CNC / 激光刀路。圆弧按同一度量
+    /// （均方、最大或鲁棒损失）计分；不能与 RANSAC、时间戳或 `function_graph` 同用
+    BezierAndArc,
+}
+
+/// 误差缓存的计算后端；GPU 不可用或配置不受内核支持时回退到 CPU
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Backend {
+    /// 依次尝试 CUDA、OpenCL、wgpu（仅 `Precision::F32` 时）和 CPU
+    #[default]
+    Auto,
+    Cpu,
+    Cuda,
+    /// wgpu 计算着色器，始终按单精度计算
+    Wgpu,
+    /// 用于验证 CUDA 内核：计算后抽取 `CudaOptions::verify_samples` 个区间在 CPU 上重算。
+    /// 偏差超出 `ParityReport` 的容差时不使用 CUDA 的缓存，改在 CPU 上计算：`fit_curve` 照常返回结果，
+    /// `try_fit_curve` 返回带报告的 `FitCurveError::CacheDivergence`
+    CudaVerified,
+    /// OpenCL 内核，供只有 OpenCL 驱动的环境使用；双精度需要设备支持 `cl_khr_fp64`
+    OpenCl,
+}
+
+impl std::str::FromStr for Backend {
+    type Err = String;
+
+    /// 不区分大小写的 "auto" / "cpu" / "cuda" / "wgpu" / "cuda-verified" / "opencl"
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Backend::Auto),
+            "cpu" => Ok(Backend::Cpu),
+            "cuda" => Ok(Backend::Cuda),
+            "wgpu" => Ok(Backend::Wgpu),
+            "cuda-verified" => Ok(Backend::CudaVerified),
+            "opencl" => Ok(Backend::OpenCl),
+            _ => Err(format!(
+                "unknown backend: {name} (expected auto, cpu, cuda, wgpu, cuda-verified or opencl)"
+            )),
+        }
+    }
+}
+
+/// DP 的优化目标
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Objective {
+    /// 先最少段数，段数相同时取总误差最小
+    #[default]
+    MinSegments,
+    /// 最小化 `total_error + lambda * num_segments`，适合压缩类场景；
+    /// max_error 仍是单段误差上限，设为无穷大即只由 lambda 权衡
+    Penalized { lambda: f64 },
+}
+
+/// 单段误差的度量方式，两者都是距离的平方，max_error 的单位不变
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorMetric {
+    /// 均方距离
+    #[default]
+    Mse,
+    /// 最大距离的平方，保证每个点都在容差内；使用鲁棒损失或 RANSAC 时忽略
+    Max,
+}
+
+impl std::str::FromStr for ErrorMetric {
+    type Err = String;
+
+    /// 不区分大小写的 "mse" / "max"
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "mse" => Ok(ErrorMetric::Mse),
+            "max" => Ok(ErrorMetric::Max),
+            _ => Err(format!("unknown metric: {name} (expected mse or max)")),
+        }
+    }
+}
+
+/// 单段最小二乘中端点的取法；只作用于普通最小二乘，鲁棒拟合、RANSAC、时间戳和函数图像模式仍固定端点
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EndpointPolicy {
+    /// 端点固定为区间首末采样点
+    #[default]
+    Fixed,
+    /// 端点与控制点一起求解；相邻分段在断点处不再保证连续，SVG 等路径输出按各段终点连接
+    Free,
+    /// 端点取断点前后 `radius` 个采样点的平均，相邻分段共用同一端点，仍然连续
+    LocalAverage { radius: usize },
+}
+
+/// 误差缓存的计算精度；控制点求解始终使用 f64
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Precision {
+    /// 单精度，消费级 GPU 上吞吐远高于双精度，容差在像素级时足够
+    F32,
+    #[default]
+    F64,
+}
+
+/// CUDA 后端的设备与启动参数，未启用 `cuda` feature 时忽略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CudaOptions {
+    /// 设备序号，可用 `cuda::list_devices()` 查询
+    pub device_id: usize,
+    /// 每个 block 的线程数，取值 1..=1024
+    pub block_size: u32,
+    /// 单次上传的显存上限（字节），区间数超出时分块计算
+    pub memory_budget: usize,
+    /// `Backend::CudaVerified` 抽查的区间数
+    pub verify_samples: usize,
+    /// 内核中每个点先取 8 个粗采样，再做 3 次牛顿迭代求最近点，代替 50~200 个均匀采样；
+    /// 更快，高曲率处也更准确，但误差略小于 CPU 的采样结果，与 `Backend::CudaVerified` 的容差不兼容
+    pub newton_refine: bool,
+    /// 把区间均分到所有可见设备并行计算，忽略 `device_id`；每块设备各自使用 `memory_budget`
+    pub multi_gpu: bool,
+    /// 流水线使用的 CUDA 流数，一块区间计算时下一块在另一条流上上传；1 表示串行
+    pub streams: usize,
+    /// 误差留在显存中，DP 也在设备上完成，只取回断点，再在 CPU 上重新拟合选中的各段。
+    /// 省去误差回传，但不支持必经点和禁止断点，也不做 `Backend::CudaVerified` 的抽查
+    pub device_dp: bool,
+}
+
+impl Default for CudaOptions {
+    fn default() -> Self {
+        Self {
+            device_id: 0,
+            block_size: 128,
+            memory_budget: 512 << 20,
+            verify_samples: 256,
+            newton_refine: false,
+            multi_gpu: false,
+            streams: 2,
+            device_dp: false,
+        }
+    }
+}
+
+/// 区间误差的计算方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CacheMode {
+    /// 先并行计算所有候选区间的误差再做 DP，可使用 GPU 后端
+    #[default]
+    Full,
+    /// 在 DP 中按需计算，只保留回溯路径上的拟合结果；不可达的起点和段数更多的候选不再计算。
+    /// 结果与 `Full` 相同，适合最大段长较短而点数很多的输入
+    None,
+}
+
+/// 粗到细的近似模式：先在每隔 `factor` 个点取一个的子集上拟合，再在原始点上把每个断点
+/// 在左右 `window` 个点内重新选取；用于百万点级别、精确 DP 不可行的输入，结果接近但不保证最优
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Multiresolution {
+    pub factor: usize,
+    pub window: usize,
+}
+
+/// 按局部曲率收窄候选窗口：以终点 i 结尾的分段最长只到切线累计转角达到 `max_turning` 处，
+/// 平直区域仍可用满 `max_segment_len`，弯曲区域的候选区间大幅减少。二次曲线的切线转角小于 π，
+/// 转角更大的区间本来就拟合不好，因此结果通常不变
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveWindow {
+    /// 一段内切线方向允许转过的总角度（弧度）
+    pub max_turning: f64,
+    /// 估计切线方向时前后各跨过的点数，抑制像素锯齿带来的虚假转角
+    pub stride: usize,
+}
+
+impl Default for AdaptiveWindow {
+    fn default() -> Self {
+        Self {
+            max_turning: std::f64::consts::PI,
+            stride: 2,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FitConfig {
     pub min_segment_len: usize,
     pub max_segment_len: usize,
     pub max_error: f64,
+    /// 拟合前对输入点做平滑，抑制数位板抖动
+    #[serde(default)]
+    pub presmooth: Option<Smoothing>,
+    /// 拟合前去除间距小于该值的重复点
+    #[serde(default)]
+    pub dedup_distance: Option<f64>,
+    /// 使用鲁棒损失做迭代加权拟合，max_error 也按该损失衡量
+    #[serde(default)]
+    pub robust: Option<RobustLoss>,
+    #[serde(default)]
+    pub fitter: FitterKind,
+    /// 控制点到弦的距离不超过该值的分段输出为直线 `L`
+    #[serde(default)]
+    pub line_tolerance: Option<f64>,
+    #[serde(default)]
+    pub primitives: PrimitiveSet,
+    #[serde(default)]
+    pub cuda: CudaOptions,
+    #[serde(default)]
+    pub precision: Precision,
+    #[serde(default)]
+    pub backend: Backend,
+    /// DP 路径必须经过的输入点下标（如落笔点、航点），超出范围的下标被忽略
+    #[serde(default)]
+    pub required_breakpoints: Vec<usize>,
+    /// 按输入点下标标记不允许分段的位置（如字形内部），超出掩码长度的点不受限；
+    /// 与必经点冲突时以必经点为准
+    #[serde(default)]
+    pub forbidden_breakpoints: Vec<bool>,
+    #[serde(default)]
+    pub objective: Objective,
+    /// CPU 误差缓存计算使用的线程数，None 时使用当前 rayon 线程池；
+    /// 在 `ThreadPool::install` 内调用拟合即可复用外部线程池；未启用 `parallel` feature 时忽略
+    #[serde(default)]
+    pub num_threads: Option<usize>,
+    /// 拟合前把点缩放到单位包围盒内，结果再映射回原坐标；改善大坐标（如 UTM）下的数值精度，
+    /// 此时 max_error、line_tolerance 等容差和输出的误差值都按归一化坐标计
+    #[serde(default)]
+    pub normalize: bool,
+    #[serde(default)]
+    pub metric: ErrorMetric,
+    /// 每个输入点的时间戳（非递减），设置后按时间而不是弦长参数化曲线，
+    /// 误差为 B(t_i) 到 p_i 距离平方的均值；长度与输入点数不同时 `fit_curve` 忽略，
+    /// `try_fit_curve` 返回 `FitCurveError::TimestampCount`
+    #[serde(default)]
+    pub timestamps: Vec<f64>,
+    /// 函数图像模式：输入的 x 须严格递增（`try_fit_curve` 检查，`fit_curve` 中不递增的区间不可选），
+    /// 误差按竖直方向 |y - f(x)| 计算，
+    /// 每段曲线在 x 上单调，适合时间序列图表的降采样；优先于 `timestamps`
+    #[serde(default)]
+    pub function_graph: bool,
+    #[serde(default)]
+    pub endpoint_policy: EndpointPolicy,
+    /// 逐位可复现模式：误差缓存总在 CPU 上计算（GPU 的浮点结果随设备和驱动变化），
+    /// 用于需要跨平台比对结果的 CI；DP 中等价的候选总是取起点下标最小者，与此开关无关
+    #[serde(default)]
+    pub deterministic: bool,
+    #[serde(default)]
+    pub multiresolution: Option<Multiresolution>,
+    /// 误差缓存的内存上限（字节，按 `DPOptimizer::estimate` 估计）。超出时按 `CacheMode::None`
+    /// 计算，内存只与点数成正比；结果不变，但不使用 GPU 后端，状态记为 `FitStatus::Degraded`
+    #[serde(default)]
+    pub max_cache_bytes: Option<usize>,
+    #[serde(default)]
+    pub cache_mode: CacheMode,
+    /// 按输入点下标范围覆盖单段误差上限（如在标注的特征点附近收紧）。分段 `start..=end`
+    /// 与若干范围相交时取其中最小的值，不与任何范围相交时使用 `max_error`；
+    /// 设置后不使用 `multiresolution` 和 `CudaOptions::device_dp`
+    #[serde(default)]
+    pub error_overrides: Vec<(Range<usize>, f64)>,
+    /// 按终点下标给出的最大段长（点数），不超过 `max_segment_len`，超出长度的下标使用
+    /// `max_segment_len`；设置后不使用 `CudaOptions::device_dp`
+    #[serde(default)]
+    pub max_segment_lens: Vec<usize>,
+    /// 拟合时按局部曲率生成 `max_segment_lens`，覆盖手动设置的值
+    #[serde(default)]
+    pub adaptive_window: Option<AdaptiveWindow>,
+    /// 断点处的曲线端点固定为原始采样点。设为 false 时 DP 之后在各断点附近局部移动端点
+    /// （相邻两段共用，路径仍连续）以减小两侧误差之和，适合断点恰好落在噪声点上的数据；
+    /// 分段下标不变，只作用于普通最小二乘和 `EndpointPolicy::Fixed`
+    #[serde(default = "snap_endpoints_default")]
+    pub snap_endpoints: bool,
+}
+
+fn snap_endpoints_default() -> bool {
+    true
 }
 
 impl Default for FitConfig {
@@ -13,11 +297,42 @@ impl Default for FitConfig {
             min_segment_len: 30,
             max_segment_len: 200,
             max_error: 2.0,
+            presmooth: None,
+            dedup_distance: None,
+            robust: None,
+            fitter: FitterKind::LeastSquares,
+            line_tolerance: None,
+            primitives: PrimitiveSet::Bezier,
+            cuda: CudaOptions::default(),
+            precision: Precision::F64,
+            backend: Backend::Auto,
+            required_breakpoints: Vec::new(),
+            forbidden_breakpoints: Vec::new(),
+            objective: Objective::MinSegments,
+            num_threads: None,
+            normalize: false,
+            metric: ErrorMetric::Mse,
+            timestamps: Vec::new(),
+            function_graph: false,
+            endpoint_policy: EndpointPolicy::Fixed,
+            deterministic: false,
+            multiresolution: None,
+            max_cache_bytes: None,
+            cache_mode: CacheMode::Full,
+            error_overrides: Vec::new(),
+            max_segment_lens: Vec::new(),
+            adaptive_window: None,
+            snap_endpoints: true,
         }
     }
 }
 
 impl FitConfig {
+    /// 链式构造配置，`build` 时统一校验
+    pub fn builder() -> FitConfigBuilder {
+        FitConfigBuilder::default()
+    }
+
     pub fn new(min_segment_len: usize, max_segment_len: usize, max_error: f64) -> Self {
         // 验证参数
         assert!(min_segment_len >= 3, "min_segment_len must be at least 3");
@@ -29,9 +344,272 @@ impl FitConfig {
             min_segment_len,
             max_segment_len,
             max_error,
+            ..Self::default()
         }
     }
     
+    /// 设置拟合前的平滑方式
+    pub fn with_presmooth(mut self, smoothing: Smoothing) -> Self {
+        self.presmooth = Some(smoothing);
+        self
+    }
+
+    /// 拟合前去除间距小于 `min_distance` 的点
+    pub fn with_dedup(mut self, min_distance: f64) -> Self {
+        self.dedup_distance = Some(min_distance);
+        self
+    }
+
+    /// 使用鲁棒损失抑制离群点
+    pub fn with_robust(mut self, loss: RobustLoss) -> Self {
+        self.robust = Some(loss);
+        self
+    }
+
+    /// 选择单段拟合算法
+    pub fn with_fitter(mut self, fitter: FitterKind) -> Self {
+        self.fitter = fitter;
+        self
+    }
+
+    /// 近似直线的分段输出为 `L` 线段
+    pub fn with_line_tolerance(mut self, tolerance: f64) -> Self {
+        self.line_tolerance = Some(tolerance);
+        self
+    }
+
+    /// 选择 DP 可用的分段图元
+    pub fn with_primitives(mut self, primitives: PrimitiveSet) -> Self {
+        self.primitives = primitives;
+        self
+    }
+
+    /// 指定 CUDA 设备和 block 大小
+    pub fn with_cuda(mut self, options: CudaOptions) -> Self {
+        self.cuda = options;
+        self
+    }
+
+    /// 设置误差缓存的计算精度
+    pub fn with_precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// 选择误差缓存的计算后端
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// 强制在给定下标处分段
+    pub fn with_required_breakpoints(mut self, indices: impl IntoIterator<Item = usize>) -> Self {
+        self.required_breakpoints = indices.into_iter().collect();
+        self
+    }
+
+    /// 禁止在掩码为 true 的下标处分段
+    pub fn with_forbidden_breakpoints(mut self, mask: impl IntoIterator<Item = bool>) -> Self {
+        self.forbidden_breakpoints = mask.into_iter().collect();
+        self
+    }
+
+    /// 设置 DP 的优化目标
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// 限制 CPU 并行计算的线程数
+    pub fn with_num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = Some(num_threads);
+        self
+    }
+
+    /// 设置单段误差的度量方式
+    pub fn with_metric(mut self, metric: ErrorMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// 按时间戳参数化，适合速度变化的轨迹数据
+    pub fn with_timestamps(mut self, timestamps: impl IntoIterator<Item = f64>) -> Self {
+        self.timestamps = timestamps.into_iter().collect();
+        self
+    }
+
+    /// 把输入当作 y = f(x) 的采样拟合
+    pub fn with_function_graph(mut self, function_graph: bool) -> Self {
+        self.function_graph = function_graph;
+        self
+    }
+
+    /// 设置单段拟合时端点的取法，抑制首末采样点噪声的影响
+    pub fn with_endpoint_policy(mut self, policy: EndpointPolicy) -> Self {
+        self.endpoint_policy = policy;
+        self
+    }
+
+    /// 是否把断点处的端点固定在采样点上，见 `snap_endpoints`
+    pub fn with_snap_endpoints(mut self, snap_endpoints: bool) -> Self {
+        self.snap_endpoints = snap_endpoints;
+        self
+    }
+
+    /// 要求结果逐位可复现，忽略 GPU 后端
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// 启用粗到细的近似拟合，`factor` 为降采样间隔，`window` 为细化时断点的移动范围（点数）
+    pub fn with_multiresolution(mut self, factor: usize, window: usize) -> Self {
+        self.multiresolution = Some(Multiresolution { factor, window });
+        self
+    }
+
+    /// 限制误差缓存的内存，超出时改为不缓存的计算方式
+    pub fn with_max_cache_bytes(mut self, max_cache_bytes: usize) -> Self {
+        self.max_cache_bytes = Some(max_cache_bytes);
+        self
+    }
+
+    /// 设置区间误差的计算方式
+    pub fn with_cache_mode(mut self, mode: CacheMode) -> Self {
+        self.cache_mode = mode;
+        self
+    }
+
+    /// 为部分下标范围单独指定误差上限，见 `error_overrides`
+    pub fn with_error_overrides(mut self, overrides: impl IntoIterator<Item = (Range<usize>, f64)>) -> Self {
+        self.error_overrides = overrides.into_iter().collect();
+        self
+    }
+
+    /// 按局部曲率自适应地限制段长，见 `AdaptiveWindow`
+    pub fn with_adaptive_window(mut self, adaptive: AdaptiveWindow) -> Self {
+        self.adaptive_window = Some(adaptive);
+        self
+    }
+
+    /// 以 i 结尾的分段的最大点数
+    pub fn max_segment_len_at(&self, i: usize) -> usize {
+        self.max_segment_lens
+            .get(i)
+            .map_or(self.max_segment_len, |&len| len.min(self.max_segment_len))
+    }
+
+    /// 分段 `start..=end` 的误差上限
+    pub fn max_error_for(&self, start: usize, end: usize) -> f64 {
+        self.error_overrides
+            .iter()
+            .filter(|(range, _)| range.start <= end && start < range.end)
+            .map(|&(_, limit)| limit)
+            .reduce(f64::min)
+            .unwrap_or(self.max_error)
+    }
+
+    /// 所有分段中最宽的误差上限，GPU 缓存按它截断
+    pub(crate) fn widest_max_error(&self) -> f64 {
+        self.error_overrides
+            .iter()
+            .map(|&(_, limit)| limit)
+            .fold(self.max_error, f64::max)
+    }
+
+    /// 检查参数是否有效
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |reason: &str| {
+            Err(ConfigError {
+                reason: reason.to_string(),
+            })
+        };
+        if self.min_segment_len < 3 {
+            return invalid("min_segment_len must be at least 3");
+        }
+        if self.max_segment_len < self.min_segment_len {
+            return invalid("max_segment_len must be >= min_segment_len");
+        }
+        if self.max_error.is_nan() || self.max_error <= 0.0 {
+            return invalid("max_error must be positive");
+        }
+        if self.dedup_distance.is_some_and(|d| d.is_nan() || d < 0.0) {
+            return invalid("dedup_distance must be non-negative");
+        }
+        if let Some(Smoothing::Gaussian { sigma }) = self.presmooth {
+            if !(sigma > 0.0 && sigma.is_finite()) {
+                return invalid("presmooth sigma must be finite and positive");
+            }
+        }
+        if self.line_tolerance.is_some_and(|t| t.is_nan() || t < 0.0) {
+            return invalid("line_tolerance must be non-negative");
+        }
+        if self.error_overrides.iter().any(|(_, limit)| limit.is_nan() || *limit <= 0.0) {
+            return invalid("error override must be positive");
+        }
+        if self.max_segment_lens.iter().any(|&len| len < self.min_segment_len) {
+            return invalid("max_segment_lens must be >= min_segment_len");
+        }
+        if let Some(adaptive) = self.adaptive_window {
+            if adaptive.max_turning.is_nan() || adaptive.max_turning <= 0.0 || adaptive.stride == 0 {
+                return invalid("adaptive_window needs a positive max_turning and stride");
+            }
+        }
+        if self.primitives == PrimitiveSet::BezierAndArc
+            && (matches!(self.fitter, FitterKind::Ransac(_)) || !self.timestamps.is_empty() || self.function_graph)
+        {
+            return invalid("arc primitives cannot be used with ransac, timestamps or function_graph");
+        }
+        if let Objective::Penalized { lambda } = self.objective {
+            if !(lambda >= 0.0 && lambda.is_finite()) {
+                return invalid("objective lambda must be finite and non-negative");
+            }
+        }
+        if self.timestamps.iter().any(|t| !t.is_finite())
+            || self.timestamps.windows(2).any(|w| w[1] < w[0])
+        {
+            return invalid("timestamps must be finite and non-decreasing");
+        }
+        if self.num_threads == Some(0) {
+            return invalid("num_threads must be positive");
+        }
+        if self.multiresolution.is_some_and(|m| m.factor == 0) {
+            return invalid("multiresolution factor must be positive");
+        }
+        if !(1..=1024).contains(&self.cuda.block_size) {
+            return invalid("cuda block_size must be in 1..=1024");
+        }
+        if self.cuda.streams == 0 {
+            return invalid("cuda streams must be positive");
+        }
+        Ok(())
+    }
+
+    /// 在单位包围盒内拟合，容差按相对尺度解释
+    pub fn with_normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// 按数据尺度设置 max_error：允许的距离偏差为包围盒对角线的 `relative_tolerance` 倍，
+    /// 点全部重合时退化为相邻点间距中位数的倍数；max_error 为均方误差，因此取其平方。
+    /// 尺度无法确定（点数不足）时保持原值
+    pub fn auto_error(mut self, points: &[Point2D], relative_tolerance: f64) -> Self {
+        let diagonal = BoundingBox::from_points(points).map_or(0.0, |b| b.diagonal());
+        let scale = if diagonal > 0.0 {
+            diagonal
+        } else {
+            let mut spacing: Vec<f64> = points.windows(2).map(|w| w[0].distance_to(&w[1])).collect();
+            spacing.sort_by(f64::total_cmp);
+            spacing.get(spacing.len() / 2).copied().unwrap_or(0.0)
+        };
+        let tolerance = scale * relative_tolerance;
+        if tolerance > 0.0 && tolerance.is_finite() {
+            self.max_error = tolerance * tolerance;
+        }
+        self
+    }
+
     /// 创建配置，自动修正无效参数
     pub fn new_clamped(min_segment_len: usize, max_segment_len: usize, max_error: f64) -> Self {
         let min_len = min_segment_len.max(3);
@@ -42,6 +620,219 @@ impl FitConfig {
             min_segment_len: min_len,
             max_segment_len: max_len,
             max_error: error,
+            ..Self::default()
+        }
+    }
+
+    /// 手写笔迹：数位板采样密、抖动明显，先平滑去重再用较短的分段
+    pub fn handwriting() -> Self {
+        Self {
+            min_segment_len: 5,
+            max_segment_len: 60,
+            max_error: 1.0,
+            presmooth: Some(Smoothing::Gaussian { sigma: 1.0 }),
+            dedup_distance: Some(0.5),
+            ..Self::default()
         }
     }
+
+    /// GPS 轨迹（米制投影坐标）：容差以米计，Huber 损失压制定位跳点，停留时的重复点去掉
+    pub fn gps_track() -> Self {
+        Self {
+            min_segment_len: 3,
+            max_segment_len: 100,
+            max_error: 5.0,
+            dedup_distance: Some(1.0),
+            robust: Some(RobustLoss::Huber { delta: 5.0 }),
+            ..Self::default()
+        }
+    }
+
+    /// 字体轮廓（字体单位）：点少且精确，不做平滑，近直线的笔画输出为 `L`
+    pub fn font_outline() -> Self {
+        Self {
+            min_segment_len: 3,
+            max_segment_len: 50,
+            max_error: 0.25,
+            line_tolerance: Some(0.05),
+            ..Self::default()
+        }
+    }
+
+    /// 绘图仪 / 激光刀路（毫米）：容差严格，允许圆弧和直线以便输出 G2/G3 与 G1
+    pub fn plotter() -> Self {
+        Self {
+            min_segment_len: 4,
+            max_segment_len: 80,
+            max_error: 0.1,
+            line_tolerance: Some(0.02),
+            primitives: PrimitiveSet::BezierAndArc,
+            ..Self::default()
+        }
+    }
+
+    /// 按名称取预设：`handwriting`、`gps_track`、`font_outline`、`plotter`
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "handwriting" => Some(Self::handwriting()),
+            "gps_track" => Some(Self::gps_track()),
+            "font_outline" => Some(Self::font_outline()),
+            "plotter" => Some(Self::plotter()),
+            _ => None,
+        }
+    }
+}
+
+/// `FitConfig::builder()` 返回的构造器，未设置的参数取 `FitConfig::default()` 的值
+#[derive(Debug, Clone, Default)]
+pub struct FitConfigBuilder {
+    config: FitConfig,
+}
+
+impl FitConfigBuilder {
+    pub fn min_segment_len(mut self, len: usize) -> Self {
+        self.config.min_segment_len = len;
+        self
+    }
+
+    pub fn max_segment_len(mut self, len: usize) -> Self {
+        self.config.max_segment_len = len;
+        self
+    }
+
+    pub fn max_error(mut self, max_error: f64) -> Self {
+        self.config.max_error = max_error;
+        self
+    }
+
+    pub fn presmooth(mut self, smoothing: Smoothing) -> Self {
+        self.config.presmooth = Some(smoothing);
+        self
+    }
+
+    pub fn dedup_distance(mut self, min_distance: f64) -> Self {
+        self.config.dedup_distance = Some(min_distance);
+        self
+    }
+
+    pub fn robust(mut self, loss: RobustLoss) -> Self {
+        self.config.robust = Some(loss);
+        self
+    }
+
+    pub fn fitter(mut self, fitter: FitterKind) -> Self {
+        self.config.fitter = fitter;
+        self
+    }
+
+    pub fn line_tolerance(mut self, tolerance: f64) -> Self {
+        self.config.line_tolerance = Some(tolerance);
+        self
+    }
+
+    pub fn primitives(mut self, primitives: PrimitiveSet) -> Self {
+        self.config.primitives = primitives;
+        self
+    }
+
+    pub fn metric(mut self, metric: ErrorMetric) -> Self {
+        self.config.metric = metric;
+        self
+    }
+
+    pub fn objective(mut self, objective: Objective) -> Self {
+        self.config.objective = objective;
+        self
+    }
+
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.config.backend = backend;
+        self
+    }
+
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.config.precision = precision;
+        self
+    }
+
+    pub fn cuda(mut self, options: CudaOptions) -> Self {
+        self.config.cuda = options;
+        self
+    }
+
+    pub fn required_breakpoints(mut self, indices: impl IntoIterator<Item = usize>) -> Self {
+        self.config.required_breakpoints = indices.into_iter().collect();
+        self
+    }
+
+    pub fn forbidden_breakpoints(mut self, mask: impl IntoIterator<Item = bool>) -> Self {
+        self.config.forbidden_breakpoints = mask.into_iter().collect();
+        self
+    }
+
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.config.num_threads = Some(num_threads);
+        self
+    }
+
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.config.normalize = normalize;
+        self
+    }
+
+    pub fn timestamps(mut self, timestamps: impl IntoIterator<Item = f64>) -> Self {
+        self.config.timestamps = timestamps.into_iter().collect();
+        self
+    }
+
+    pub fn function_graph(mut self, function_graph: bool) -> Self {
+        self.config.function_graph = function_graph;
+        self
+    }
+
+    pub fn endpoint_policy(mut self, policy: EndpointPolicy) -> Self {
+        self.config.endpoint_policy = policy;
+        self
+    }
+
+    pub fn snap_endpoints(mut self, snap_endpoints: bool) -> Self {
+        self.config.snap_endpoints = snap_endpoints;
+        self
+    }
+
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.config.deterministic = deterministic;
+        self
+    }
+
+    pub fn multiresolution(mut self, factor: usize, window: usize) -> Self {
+        self.config.multiresolution = Some(Multiresolution { factor, window });
+        self
+    }
+
+    pub fn max_cache_bytes(mut self, max_cache_bytes: usize) -> Self {
+        self.config.max_cache_bytes = Some(max_cache_bytes);
+        self
+    }
+
+    pub fn cache_mode(mut self, mode: CacheMode) -> Self {
+        self.config.cache_mode = mode;
+        self
+    }
+
+    pub fn error_overrides(mut self, overrides: impl IntoIterator<Item = (Range<usize>, f64)>) -> Self {
+        self.config.error_overrides = overrides.into_iter().collect();
+        self
+    }
+
+    pub fn adaptive_window(mut self, adaptive: AdaptiveWindow) -> Self {
+        self.config.adaptive_window = Some(adaptive);
+        self
+    }
+
+    /// 校验并返回配置
+    pub fn build(self) -> Result<FitConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
 }
\ No newline at end of file
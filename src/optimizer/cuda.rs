@@ -1,227 +1,756 @@
 #![cfg(feature = "cuda")]
 
 use std::collections::HashMap;
+use std::iter::Peekable;
+use std::sync::{Arc, Mutex, OnceLock};
 
-use cudarc::driver::{CudaDevice, DeviceRepr, LaunchAsync, LaunchConfig};
-use cudarc::nvrtc::compile_ptx;
+use cudarc::driver::{
+    result, sys, CudaDevice, CudaFunction, CudaSlice, CudaStream, DevicePtr, DeviceRepr,
+    LaunchAsync, LaunchConfig, ValidAsZeroBits,
+};
+use cudarc::nvrtc::{compile_ptx, Ptx};
 
 use crate::fitting::{BezierFitter, FitError};
-use crate::geometry::Point2D;
-use crate::optimizer::config::FitConfig;
-
-const CUDA_SRC: &str = r#"
-extern "C" __global__ void compute_errors(
-    const double* pts_x,
-    const double* pts_y,
-    int n_points,
-    const double* p0x,
-    const double* p0y,
-    const double* p1x,
-    const double* p1y,
-    const double* p2x,
-    const double* p2y,
-    const int* start_idx,
-    const int* end_idx,
-    double max_error,
-    double* out_err,
-    int n_segments
-) {
-    int idx = (int)(blockIdx.x * blockDim.x + threadIdx.x);
-    if (idx >= n_segments) {
-        return;
-    }
-
-    int start = start_idx[idx];
-    int end = end_idx[idx];
-    int len = end - start + 1;
-    if (len <= 0) {
-        out_err[idx] = 0.0;
-        return;
-    }
-
-    double p0xv = p0x[idx];
-    double p0yv = p0y[idx];
-    double p1xv = p1x[idx];
-    double p1yv = p1y[idx];
-    double p2xv = p2x[idx];
-    double p2yv = p2y[idx];
-
-    double dx01 = p0xv - p1xv;
-    double dy01 = p0yv - p1yv;
-    double dx12 = p1xv - p2xv;
-    double dy12 = p1yv - p2yv;
-    double curve_len = sqrt(dx01 * dx01 + dy01 * dy01)
-                     + sqrt(dx12 * dx12 + dy12 * dy12);
-
-    int samples = (int)(curve_len / 2.0);
-    if (samples < 50) samples = 50;
-    if (samples > 200) samples = 200;
-    double denom = (samples > 1) ? (double)(samples - 1) : 1.0;
-
-    double max_sum = max_error * (double)len;
-    double sum = 0.0;
-
-    for (int i = start; i <= end; ++i) {
-        double px = pts_x[i];
-        double py = pts_y[i];
-        double min_d2 = 1.0e300;
-
-        for (int s = 0; s < samples; ++s) {
-            double t = (double)s / denom;
-            double mt = 1.0 - t;
-            double mt2 = mt * mt;
-            double t2 = t * t;
-
-            double bx = mt2 * p0xv + 2.0 * mt * t * p1xv + t2 * p2xv;
-            double by = mt2 * p0yv + 2.0 * mt * t * p1yv + t2 * p2yv;
-
-            double dx = bx - px;
-            double dy = by - py;
-            double d2 = dx * dx + dy * dy;
-            if (d2 < min_d2) {
-                min_d2 = d2;
-            }
-        }
+use crate::geometry::{Point2D, QuadraticBezier};
+use crate::optimizer::cache::ErrorCache;
+use crate::optimizer::config::{FitConfig, Objective, Precision};
+use crate::optimizer::intervals::{candidate_starts, IntervalIter};
 
-        sum += min_d2;
-        if (sum > max_sum) {
-            break;
-        }
+/// 内核支持的浮点类型
+trait GpuScalar: DeviceRepr + ValidAsZeroBits + Unpin + Copy {
+    fn from_f64(v: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl GpuScalar for f64 {
+    fn from_f64(v: f64) -> Self {
+        v
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl GpuScalar for f32 {
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
     }
+}
+
+/// 误差内核源码；启用 `cuda-ptx` feature 时由 build.rs 预编译为 PTX
+const CUDA_SRC: &str = include_str!("kernels.cu");
+
+/// 构建时嵌入的 PTX；nvcc 不可用时为空，运行时改用 NVRTC 编译
+#[cfg(feature = "cuda-ptx")]
+const EMBEDDED_PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/kernels.ptx"));
+#[cfg(not(feature = "cuda-ptx"))]
+const EMBEDDED_PTX: &str = "";
+
+/// 一块 CUDA 设备的基本属性
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CudaDeviceInfo {
+    pub ordinal: usize,
+    pub name: String,
+    /// 显存总量（字节）
+    pub total_memory: usize,
+    pub compute_capability: (i32, i32),
+    pub multiprocessor_count: i32,
+}
 
-    out_err[idx] = sum / (double)len;
+/// 列出本机所有 CUDA 设备，便于多卡服务器用 `CudaOptions::device_id` 绑定
+pub fn list_devices() -> Result<Vec<CudaDeviceInfo>, String> {
+    let count = CudaDevice::count().map_err(|e| format!("cuda init: {e}"))?;
+    (0..count.max(0) as usize)
+        .map(|ordinal| {
+            let dev =
+                CudaDevice::new(ordinal).map_err(|e| format!("cuda device {ordinal}: {e}"))?;
+            let attr = |a| {
+                dev.attribute(a)
+                    .map_err(|e| format!("cuda device {ordinal}: {e}"))
+            };
+            let total_memory = unsafe { result::device::total_mem(*dev.cu_device()) }
+                .map_err(|e| format!("cuda device {ordinal}: {e}"))?;
+            Ok(CudaDeviceInfo {
+                ordinal,
+                name: dev
+                    .name()
+                    .map_err(|e| format!("cuda device {ordinal}: {e}"))?,
+                total_memory,
+                compute_capability: (
+                    attr(sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_COMPUTE_CAPABILITY_MAJOR)?,
+                    attr(sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_COMPUTE_CAPABILITY_MINOR)?,
+                ),
+                multiprocessor_count: attr(
+                    sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_MULTIPROCESSOR_COUNT,
+                )?,
+            })
+        })
+        .collect()
 }
-"#;
 
+/// 按配置的设备取共享引擎计算误差缓存；`CudaOptions::multi_gpu` 时把区间按顺序均分到
+/// 所有可见设备并行计算后合并，任一设备失败即整体失败
 pub fn compute_error_cache_cuda(
     points: &[Point2D],
     config: &FitConfig,
-) -> Result<HashMap<(usize, usize), FitError>, String> {
-    let n = points.len();
-    if n == 0 {
-        return Ok(HashMap::new());
-    }
-
-    let mut starts: Vec<i32> = Vec::new();
-    let mut ends: Vec<i32> = Vec::new();
-    let mut p0x: Vec<f64> = Vec::new();
-    let mut p0y: Vec<f64> = Vec::new();
-    let mut p1x: Vec<f64> = Vec::new();
-    let mut p1y: Vec<f64> = Vec::new();
-    let mut p2x: Vec<f64> = Vec::new();
-    let mut p2y: Vec<f64> = Vec::new();
-    let mut beziers = Vec::new();
-
-    let max_len = config.max_segment_len.max(1);
-    for i in config.min_segment_len..n {
-        let start = i.saturating_sub(max_len - 1);
-        let end = if config.min_segment_len > 0 {
-            i.saturating_sub(config.min_segment_len - 1)
-        } else {
-            i
+) -> Result<ErrorCache, String> {
+    if !config.cuda.multi_gpu {
+        return CudaEngine::shared(config.cuda.device_id)?.compute_error_cache(points, config);
+    }
+    let count = CudaDevice::count().map_err(|e| format!("cuda init: {e}"))?;
+    let engines = (0..count.max(0) as usize)
+        .map(CudaEngine::shared)
+        .collect::<Result<Vec<_>, _>>()?;
+    if engines.is_empty() {
+        return Err("no cuda devices".to_string());
+    }
+
+    // 连续分片，保持每块内区间的局部性
+    let total = IntervalIter::new(points.len(), config).count();
+    let shards: Vec<Result<ErrorCache, String>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = engines
+            .iter()
+            .enumerate()
+            .map(|(d, engine)| {
+                let from = total * d / engines.len();
+                let to = total * (d + 1) / engines.len();
+                scope.spawn(move || {
+                    let intervals = IntervalIter::new(points.len(), config)
+                        .skip(from)
+                        .take(to - from);
+                    engine.compute_intervals(points, config, intervals)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("cuda shard thread panicked"))
+            .collect()
+    });
+
+    let mut cache = ErrorCache::new();
+    for shard in shards {
+        cache.merge(shard?);
+    }
+    Ok(cache)
+}
+
+/// 多个笔画的误差缓存：所有笔画的点拼接后常驻显存，区间换算为全局下标打包到同一批
+/// 内核启动中，避免数千条短笔画各自启动内核。返回的缓存与 `strokes` 一一对应，下标相对各自笔画
+pub fn compute_error_cache_cuda_batch<S: AsRef<[Point2D]>>(
+    strokes: &[S],
+    config: &FitConfig,
+) -> Result<Vec<ErrorCache>, String> {
+    CudaEngine::shared(config.cuda.device_id)?.compute_error_cache_batch(strokes, config)
+}
+
+/// 在配置的设备上完成误差计算和 DP 前向递推，返回每个下标的上一个断点；忽略 `multi_gpu`
+pub fn solve_dp_cuda(points: &[Point2D], config: &FitConfig) -> Result<Vec<usize>, String> {
+    CudaEngine::shared(config.cuda.device_id)?.solve_dp(points, config)
+}
+
+/// 已编译并加载内核的设备句柄。NVRTC 编译（或加载预编译的 PTX）只在创建时做一次，
+/// 可显式持有，也可通过 `shared` 取按设备缓存的全局实例
+pub struct CudaEngine {
+    dev: Arc<CudaDevice>,
+    func: CudaFunction,
+    func_f32: CudaFunction,
+    func_newton: CudaFunction,
+    func_newton_f32: CudaFunction,
+    dp_func: CudaFunction,
+    dp_func_f32: CudaFunction,
+}
+
+impl CudaEngine {
+    pub fn new(device_id: usize) -> Result<Self, String> {
+        let dev =
+            CudaDevice::new(device_id).map_err(|e| format!("cuda device {device_id}: {e}"))?;
+        let names = [
+            "compute_errors",
+            "compute_errors_f32",
+            "compute_errors_newton",
+            "compute_errors_newton_f32",
+            "dp_forward",
+            "dp_forward_f32",
+        ];
+        // 优先加载预编译的 PTX，为空或驱动无法加载（如架构不符）时回退到 NVRTC
+        let embedded = !EMBEDDED_PTX.is_empty()
+            && dev
+                .load_ptx(Ptx::from_src(EMBEDDED_PTX), "bezier", &names)
+                .is_ok();
+        if !embedded {
+            let ptx = compile_ptx(CUDA_SRC).map_err(|e| format!("nvrtc: {e}"))?;
+            dev.load_ptx(ptx, "bezier", &names)
+                .map_err(|e| format!("load ptx: {e}"))?;
+        }
+        let get_func = |name: &str| {
+            dev.get_func("bezier", name)
+                .ok_or_else(|| format!("get func: {name} not found"))
+        };
+        let func = get_func("compute_errors")?;
+        let func_f32 = get_func("compute_errors_f32")?;
+        let func_newton = get_func("compute_errors_newton")?;
+        let func_newton_f32 = get_func("compute_errors_newton_f32")?;
+        let dp_func = get_func("dp_forward")?;
+        let dp_func_f32 = get_func("dp_forward_f32")?;
+        Ok(Self {
+            dev,
+            func,
+            func_f32,
+            func_newton,
+            func_newton_f32,
+            dp_func,
+            dp_func_f32,
+        })
+    }
+
+    /// 进程内按设备缓存的引擎，首次使用时初始化；初始化失败不缓存，下次调用会重试
+    pub fn shared(device_id: usize) -> Result<Arc<CudaEngine>, String> {
+        static ENGINES: OnceLock<Mutex<HashMap<usize, Arc<CudaEngine>>>> = OnceLock::new();
+        let mut engines = ENGINES
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(engine) = engines.get(&device_id) {
+            return Ok(engine.clone());
+        }
+        let engine = Arc::new(Self::new(device_id)?);
+        engines.insert(device_id, engine.clone());
+        Ok(engine)
+    }
+
+    pub fn device_id(&self) -> usize {
+        self.dev.ordinal()
+    }
+
+    pub fn compute_error_cache(
+        &self,
+        points: &[Point2D],
+        config: &FitConfig,
+    ) -> Result<ErrorCache, String> {
+        self.compute_intervals(points, config, IntervalIter::new(points.len(), config))
+    }
+
+    pub fn compute_error_cache_batch<S: AsRef<[Point2D]>>(
+        &self,
+        strokes: &[S],
+        config: &FitConfig,
+    ) -> Result<Vec<ErrorCache>, String> {
+        // offsets[k] 为第 k 个笔画在拼接点集中的起始下标，末尾为总点数
+        let mut offsets = Vec::with_capacity(strokes.len() + 1);
+        let mut points = Vec::new();
+        for stroke in strokes {
+            offsets.push(points.len());
+            points.extend_from_slice(stroke.as_ref());
+        }
+        offsets.push(points.len());
+
+        let intervals = strokes.iter().zip(&offsets).flat_map(|(stroke, &offset)| {
+            IntervalIter::new(stroke.as_ref().len(), config)
+                .map(move |(start, end)| (start + offset, end + offset))
+        });
+        let merged = self.compute_intervals(&points, config, intervals)?;
+
+        let mut caches = vec![ErrorCache::new(); strokes.len()];
+        for ((start, end), fit) in merged {
+            let k = offsets.partition_point(|&offset| offset <= start) - 1;
+            caches[k].insert(start - offsets[k], end - offsets[k], fit);
+        }
+        Ok(caches)
+    }
+
+    /// 只计算给定的区间，供多卡分片和批量笔画使用
+    fn compute_intervals(
+        &self,
+        points: &[Point2D],
+        config: &FitConfig,
+        intervals: impl Iterator<Item = (usize, usize)>,
+    ) -> Result<ErrorCache, String> {
+        match config.precision {
+            Precision::F64 => self.compute_with::<f64>(points, config, intervals),
+            Precision::F32 => self.compute_with::<f32>(points, config, intervals),
+        }
+    }
+
+    /// 误差和 DP 前向递推都在设备上完成，误差不回传，只取回每个下标的上一个断点。
+    /// 不支持必经点和禁止断点；终点不可达时返回错误，由调用方回退
+    pub fn solve_dp(&self, points: &[Point2D], config: &FitConfig) -> Result<Vec<usize>, String> {
+        match config.precision {
+            Precision::F64 => self.solve_dp_with::<f64>(points, config, &self.dp_func),
+            Precision::F32 => self.solve_dp_with::<f32>(points, config, &self.dp_func_f32),
+        }
+    }
+
+    /// 按精度和 `newton_refine` 选择误差内核
+    fn error_func(&self, config: &FitConfig) -> &CudaFunction {
+        match (config.precision, config.cuda.newton_refine) {
+            (Precision::F64, false) => &self.func,
+            (Precision::F32, false) => &self.func_f32,
+            (Precision::F64, true) => &self.func_newton,
+            (Precision::F32, true) => &self.func_newton_f32,
+        }
+    }
+
+    fn compute_with<T: GpuScalar>(
+        &self,
+        points: &[Point2D],
+        config: &FitConfig,
+        intervals: impl Iterator<Item = (usize, usize)>,
+    ) -> Result<ErrorCache, String> {
+        let mut intervals = intervals.peekable();
+        if points.is_empty() || intervals.peek().is_none() {
+            return Ok(ErrorCache::new());
+        }
+        let chunk_len = Self::chunk_len::<T>(points.len(), config, 0)?;
+        let (d_points_x, d_points_y) = self.upload_points::<T>(points)?;
+
+        let mut cache = ErrorCache::new();
+        self.run_pipeline(
+            points,
+            config,
+            (&d_points_x, &d_points_y),
+            intervals,
+            chunk_len,
+            None,
+            &mut cache,
+        )?;
+        Ok(cache)
+    }
+
+    fn solve_dp_with<T: GpuScalar>(
+        &self,
+        points: &[Point2D],
+        config: &FitConfig,
+        dp_func: &CudaFunction,
+    ) -> Result<Vec<usize>, String> {
+        let n = points.len();
+        if n <= config.min_segment_len.max(1) {
+            return Err("too few points for the device dp".to_string());
+        }
+        // 与 IntervalIter 的枚举顺序一致：按终点分行，每行按起点升序
+        let mut row_offset = vec![0i64; n];
+        let mut total = 0usize;
+        for (i, offset) in row_offset.iter_mut().enumerate().skip(config.min_segment_len) {
+            *offset = total as i64;
+            total += candidate_starts(i, config).count();
+        }
+        let resident_bytes = total * std::mem::size_of::<T>()
+            + n * (2 * std::mem::size_of::<i32>() + std::mem::size_of::<f64>())
+            + n * std::mem::size_of::<i64>();
+        let chunk_len = Self::chunk_len::<T>(n, config, resident_bytes)?;
+        let block_dim = config.cuda.block_size;
+
+        let dev = &self.dev;
+        let (d_points_x, d_points_y) = self.upload_points::<T>(points)?;
+        let d_errors = dev
+            .alloc_zeros::<T>(total.max(1))
+            .map_err(|e| format!("alloc errors: {e}"))?;
+        // 误差直接写入常驻显存，主机端缓存保持为空
+        let mut unused = ErrorCache::new();
+        self.run_pipeline(
+            points,
+            config,
+            (&d_points_x, &d_points_y),
+            IntervalIter::new(n, config).peekable(),
+            chunk_len,
+            Some(&d_errors),
+            &mut unused,
+        )?;
+
+        let d_row_offset = dev
+            .htod_copy(row_offset)
+            .map_err(|e| format!("copy row offsets: {e}"))?;
+        let d_seg = dev
+            .alloc_zeros::<i32>(n)
+            .map_err(|e| format!("alloc dp: {e}"))?;
+        let d_err = dev
+            .alloc_zeros::<f64>(n)
+            .map_err(|e| format!("alloc dp: {e}"))?;
+        let d_parent = dev
+            .alloc_zeros::<i32>(n)
+            .map_err(|e| format!("alloc dp: {e}"))?;
+
+        // 各行依次依赖之前的行，只用一个 block 在设备内同步
+        let cfg = LaunchConfig {
+            block_dim: (block_dim, 1, 1),
+            grid_dim: (1, 1, 1),
+            shared_mem_bytes: block_dim
+                * (std::mem::size_of::<f64>() + 2 * std::mem::size_of::<i32>()) as u32,
+        };
+        let (objective, lambda) = match config.objective {
+            Objective::MinSegments => (0i32, 0.0f64),
+            Objective::Penalized { lambda } => (1, lambda),
+        };
+        let n_points = n as i32;
+        let min_len = config.min_segment_len as i32;
+        let max_len = config.max_segment_len as i32;
+        let max_error = config.max_error;
+        let mut args: Vec<*mut std::ffi::c_void> = vec![
+            (&d_errors).as_kernel_param(),
+            (&d_row_offset).as_kernel_param(),
+            n_points.as_kernel_param(),
+            min_len.as_kernel_param(),
+            max_len.as_kernel_param(),
+            max_error.as_kernel_param(),
+            objective.as_kernel_param(),
+            lambda.as_kernel_param(),
+            (&d_seg).as_kernel_param(),
+            (&d_err).as_kernel_param(),
+            (&d_parent).as_kernel_param(),
+        ];
+        unsafe {
+            dp_func
+                .clone()
+                .launch(cfg, &mut args)
+                .map_err(|e| format!("launch dp: {e}"))?;
+        }
+
+        let parent = dev
+            .dtoh_sync_copy(&d_parent)
+            .map_err(|e| format!("copy back parents: {e}"))?;
+        if parent[n - 1] < 0 {
+            return Err("no feasible segmentation on the device".to_string());
+        }
+        Ok(parent.into_iter().map(|p| p.max(0) as usize).collect())
+    }
+
+    /// 每条流一块的区间数：点集和常驻数据之外的显存预算按流数均分
+    fn chunk_len<T: GpuScalar>(
+        n: usize,
+        config: &FitConfig,
+        resident_bytes: usize,
+    ) -> Result<usize, String> {
+        if !(1..=1024).contains(&config.cuda.block_size) {
+            return Err(format!("invalid cuda block size {}", config.cuda.block_size));
+        }
+        // 每个区间占用 6 个控制点坐标、输出误差和起止下标
+        let streams = config.cuda.streams.max(1);
+        let points_bytes = 2 * n * std::mem::size_of::<T>();
+        let segment_bytes = 7 * std::mem::size_of::<T>() + 2 * std::mem::size_of::<i32>();
+        let chunk_len = (config
+            .cuda
+            .memory_budget
+            .saturating_sub(points_bytes + resident_bytes)
+            / (segment_bytes * streams))
+            .min(PIPELINE_CHUNK_LEN);
+        if chunk_len == 0 {
+            return Err(format!(
+                "cuda memory budget of {} bytes is too small for {n} points",
+                config.cuda.memory_budget
+            ));
+        }
+        Ok(chunk_len)
+    }
+
+    /// 点集常驻显存，供所有分块共用
+    fn upload_points<T: GpuScalar>(
+        &self,
+        points: &[Point2D],
+    ) -> Result<(CudaSlice<T>, CudaSlice<T>), String> {
+        self.dev
+            .bind_to_thread()
+            .map_err(|e| format!("bind context: {e}"))?;
+        let points_x: Vec<T> = points.iter().map(|p| T::from_f64(p.x)).collect();
+        let points_y: Vec<T> = points.iter().map(|p| T::from_f64(p.y)).collect();
+        let d_points_x = self
+            .dev
+            .htod_copy(points_x)
+            .map_err(|e| format!("copy points x: {e}"))?;
+        let d_points_y = self
+            .dev
+            .htod_copy(points_y)
+            .map_err(|e| format!("copy points y: {e}"))?;
+        Ok((d_points_x, d_points_y))
+    }
+
+    /// 区间分块后轮流提交到多条流：一块在计算时，下一块在 CPU 上求控制点并经页锁定内存
+    /// 异步上传。`resident` 为空时误差回传写入 cache，否则按枚举顺序直接写入该显存
+    #[allow(clippy::too_many_arguments)]
+    fn run_pipeline<T: GpuScalar>(
+        &self,
+        points: &[Point2D],
+        config: &FitConfig,
+        d_points: (&CudaSlice<T>, &CudaSlice<T>),
+        mut intervals: Peekable<impl Iterator<Item = (usize, usize)>>,
+        chunk_len: usize,
+        resident: Option<&CudaSlice<T>>,
+        cache: &mut ErrorCache,
+    ) -> Result<(), String> {
+        let func = self.error_func(config);
+        let streams = config.cuda.streams.max(1);
+        let mut slots: Vec<PipelineSlot<T>> = Vec::with_capacity(streams);
+        let mut capacity = 0;
+        let mut next = 0;
+        let mut offset = 0;
+        while intervals.peek().is_some() {
+            let chunk: Vec<(usize, usize)> = intervals.by_ref().take(chunk_len).collect();
+            let beziers: Vec<_> = chunk
+                .iter()
+                .map(|&(start, end)| BezierFitter::compute_bezier(&points[start..=end]))
+                .collect();
+            // 缓冲区按首块大小分配，后续块不会更大；流在首次用到时才创建
+            if slots.len() < streams && next == slots.len() {
+                if slots.is_empty() {
+                    capacity = chunk.len();
+                }
+                slots.push(PipelineSlot::new(&self.dev, capacity)?);
+            }
+            let output = resident
+                .map(|d| *d.device_ptr() + (offset * std::mem::size_of::<T>()) as sys::CUdeviceptr);
+            offset += chunk.len();
+            let slot = &mut slots[next % streams];
+            slot.drain(cache, config)?;
+            slot.submit(func, d_points, points.len(), chunk, beziers, output, config)?;
+            next += 1;
+        }
+        for slot in &mut slots {
+            slot.drain(cache, config)?;
+        }
+        Ok(())
+    }
+}
+
+/// 单条流每块最多提交的区间数；块太大时流水线退化为串行
+const PIPELINE_CHUNK_LEN: usize = 1 << 16;
+
+/// 页锁定的主机缓冲区，异步拷贝的源和目的必须不可换页，否则驱动会退化为同步拷贝
+struct PinnedBuffer<T> {
+    ptr: *mut T,
+    len: usize,
+}
+
+impl<T: ValidAsZeroBits + Copy> PinnedBuffer<T> {
+    /// 需要在已绑定设备上下文的线程中调用
+    fn new(len: usize) -> Result<Self, String> {
+        let bytes = len.max(1) * std::mem::size_of::<T>();
+        let mut ptr = std::ptr::null_mut();
+        unsafe {
+            sys::lib()
+                .cuMemAllocHost_v2(&mut ptr, bytes)
+                .result()
+                .map_err(|e| format!("alloc pinned: {e}"))?;
+            std::ptr::write_bytes(ptr as *mut u8, 0, bytes);
+        }
+        Ok(Self {
+            ptr: ptr.cast(),
+            len,
+        })
+    }
+
+    fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl<T> Drop for PinnedBuffer<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = sys::lib().cuMemFreeHost(self.ptr.cast());
+        }
+    }
+}
+
+/// 流水线中一条流及其独占的主机/显存缓冲区，同一时刻最多有一块区间在途
+struct PipelineSlot<T: GpuScalar> {
+    stream: CudaStream,
+    host_starts: PinnedBuffer<i32>,
+    host_ends: PinnedBuffer<i32>,
+    /// p0x, p0y, p1x, p1y, p2x, p2y
+    host_coords: Vec<PinnedBuffer<T>>,
+    host_out: PinnedBuffer<T>,
+    d_starts: CudaSlice<i32>,
+    d_ends: CudaSlice<i32>,
+    d_coords: Vec<CudaSlice<T>>,
+    d_out: CudaSlice<T>,
+    /// 已提交、结果尚未取回的区间及其控制点
+    pending: Vec<(usize, usize)>,
+    pending_beziers: Vec<QuadraticBezier>,
+    in_flight: bool,
+}
+
+impl<T: GpuScalar> PipelineSlot<T> {
+    fn new(dev: &Arc<CudaDevice>, capacity: usize) -> Result<Self, String> {
+        let alloc_i32 = || {
+            dev.alloc_zeros::<i32>(capacity)
+                .map_err(|e| format!("alloc indices: {e}"))
+        };
+        let alloc_t = || {
+            dev.alloc_zeros::<T>(capacity)
+                .map_err(|e| format!("alloc coords: {e}"))
+        };
+        let d_starts = alloc_i32()?;
+        let d_ends = alloc_i32()?;
+        let d_coords = (0..6).map(|_| alloc_t()).collect::<Result<Vec<_>, _>>()?;
+        let d_out = alloc_t()?;
+        // 显存在默认流上分配，派生的流会先等待默认流上已提交的操作
+        let stream = dev
+            .fork_default_stream()
+            .map_err(|e| format!("create stream: {e}"))?;
+        Ok(Self {
+            stream,
+            host_starts: PinnedBuffer::new(capacity)?,
+            host_ends: PinnedBuffer::new(capacity)?,
+            host_coords: (0..6)
+                .map(|_| PinnedBuffer::new(capacity))
+                .collect::<Result<Vec<_>, _>>()?,
+            host_out: PinnedBuffer::new(capacity)?,
+            d_starts,
+            d_ends,
+            d_coords,
+            d_out,
+            pending: Vec::new(),
+            pending_beziers: Vec::new(),
+            in_flight: false,
+        })
+    }
+
+    /// 在本流上异步上传一块区间并启动内核，不等待完成。`output` 为空时误差回传到本流的
+    /// 主机缓冲区，否则直接写入该显存地址
+    #[allow(clippy::too_many_arguments)]
+    fn submit(
+        &mut self,
+        func: &CudaFunction,
+        (d_points_x, d_points_y): (&CudaSlice<T>, &CudaSlice<T>),
+        n_points: usize,
+        intervals: Vec<(usize, usize)>,
+        beziers: Vec<QuadraticBezier>,
+        output: Option<sys::CUdeviceptr>,
+        config: &FitConfig,
+    ) -> Result<(), String> {
+        let segment_count = intervals.len();
+        debug_assert!(!self.in_flight && segment_count <= self.host_out.len);
+        for (k, &(start, end)) in intervals.iter().enumerate() {
+            self.host_starts.as_mut_slice()[k] = start as i32;
+            self.host_ends.as_mut_slice()[k] = end as i32;
+        }
+        for (k, b) in beziers.iter().enumerate() {
+            let values = [b.p0.x, b.p0.y, b.p1.x, b.p1.y, b.p2.x, b.p2.y];
+            for (host, value) in self.host_coords.iter_mut().zip(values) {
+                host.as_mut_slice()[k] = T::from_f64(value);
+            }
+        }
+
+        let stream = self.stream.stream;
+        let upload = |dst: &CudaSlice<_>, src: &[_], what: &str| unsafe {
+            result::memcpy_htod_async(*dst.device_ptr(), src, stream)
+                .map_err(|e| format!("copy {what}: {e}"))
+        };
+        upload(
+            &self.d_starts,
+            &self.host_starts.as_slice()[..segment_count],
+            "starts",
+        )?;
+        upload(
+            &self.d_ends,
+            &self.host_ends.as_slice()[..segment_count],
+            "ends",
+        )?;
+        for (d, host) in self.d_coords.iter().zip(&self.host_coords) {
+            unsafe {
+                result::memcpy_htod_async(
+                    *d.device_ptr(),
+                    &host.as_slice()[..segment_count],
+                    stream,
+                )
+            }
+            .map_err(|e| format!("copy control points: {e}"))?;
+        }
+
+        let block_dim = config.cuda.block_size;
+        let grid_dim = (segment_count as u32).div_ceil(block_dim);
+        // 每个线程在共享内存中缓存一个点的两个坐标
+        let cfg = LaunchConfig {
+            block_dim: (block_dim, 1, 1),
+            grid_dim: (grid_dim, 1, 1),
+            shared_mem_bytes: 2 * block_dim * std::mem::size_of::<T>() as u32,
         };
-        for j in start..=end {
-            let segment = &points[j..=i];
-            let bezier = BezierFitter::compute_bezier(segment);
-            starts.push(j as i32);
-            ends.push(i as i32);
-            p0x.push(bezier.p0.x);
-            p0y.push(bezier.p0.y);
-            p1x.push(bezier.p1.x);
-            p1y.push(bezier.p1.y);
-            p2x.push(bezier.p2.x);
-            p2y.push(bezier.p2.y);
-            beziers.push(bezier);
-        }
-    }
-
-    let segment_count = starts.len();
-    if segment_count == 0 {
-        return Ok(HashMap::new());
-    }
-
-    let points_x: Vec<f64> = points.iter().map(|p| p.x).collect();
-    let points_y: Vec<f64> = points.iter().map(|p| p.y).collect();
-
-    let dev = CudaDevice::new(0).map_err(|e| format!("cuda init: {e}"))?;
-    let ptx = compile_ptx(CUDA_SRC).map_err(|e| format!("nvrtc: {e}"))?;
-    dev.load_ptx(ptx, "bezier", &["compute_errors"])
-        .map_err(|e| format!("load ptx: {e}"))?;
-    let func = dev
-        .get_func("bezier", "compute_errors")
-        .ok_or_else(|| "get func: compute_errors not found".to_string())?;
-
-    let d_points_x = dev
-        .htod_copy(points_x)
-        .map_err(|e| format!("copy points x: {e}"))?;
-    let d_points_y = dev
-        .htod_copy(points_y)
-        .map_err(|e| format!("copy points y: {e}"))?;
-    let d_p0x = dev.htod_copy(p0x).map_err(|e| format!("copy p0x: {e}"))?;
-    let d_p0y = dev.htod_copy(p0y).map_err(|e| format!("copy p0y: {e}"))?;
-    let d_p1x = dev.htod_copy(p1x).map_err(|e| format!("copy p1x: {e}"))?;
-    let d_p1y = dev.htod_copy(p1y).map_err(|e| format!("copy p1y: {e}"))?;
-    let d_p2x = dev.htod_copy(p2x).map_err(|e| format!("copy p2x: {e}"))?;
-    let d_p2y = dev.htod_copy(p2y).map_err(|e| format!("copy p2y: {e}"))?;
-    let d_starts = dev
-        .htod_copy(starts.clone())
-        .map_err(|e| format!("copy starts: {e}"))?;
-    let d_ends = dev
-        .htod_copy(ends.clone())
-        .map_err(|e| format!("copy ends: {e}"))?;
-    let mut d_out = dev
-        .alloc_zeros::<f64>(segment_count)
-        .map_err(|e| format!("alloc output: {e}"))?;
-
-    let block_dim = 128u32;
-    let grid_dim = ((segment_count as u32) + block_dim - 1) / block_dim;
-    let cfg = LaunchConfig {
-        block_dim: (block_dim, 1, 1),
-        grid_dim: (grid_dim, 1, 1),
-        shared_mem_bytes: 0,
-    };
-
-    let n_points = points.len() as i32;
-    let n_segments = segment_count as i32;
-    let max_error = config.max_error;
-    let mut args: Vec<*mut std::ffi::c_void> = vec![
-        (&d_points_x).as_kernel_param(),
-        (&d_points_y).as_kernel_param(),
-        (&n_points).as_kernel_param(),
-        (&d_p0x).as_kernel_param(),
-        (&d_p0y).as_kernel_param(),
-        (&d_p1x).as_kernel_param(),
-        (&d_p1y).as_kernel_param(),
-        (&d_p2x).as_kernel_param(),
-        (&d_p2y).as_kernel_param(),
-        (&d_starts).as_kernel_param(),
-        (&d_ends).as_kernel_param(),
-        (&max_error).as_kernel_param(),
-        (&mut d_out).as_kernel_param(),
-        (&n_segments).as_kernel_param(),
-    ];
-
-    unsafe {
-        func.launch(cfg, &mut args)
-            .map_err(|e| format!("launch: {e}"))?;
-    }
-
-    let errors = dev
-        .dtoh_sync_copy(&d_out)
+
+        let n_points = n_points as i32;
+        let n_segments = segment_count as i32;
+        let max_error = T::from_f64(config.max_error);
+        let out_ptr = output.unwrap_or(*self.d_out.device_ptr());
+        let mut args: Vec<*mut std::ffi::c_void> = vec![
+            d_points_x.as_kernel_param(),
+            d_points_y.as_kernel_param(),
+            n_points.as_kernel_param(),
+            (&self.d_coords[0]).as_kernel_param(),
+            (&self.d_coords[1]).as_kernel_param(),
+            (&self.d_coords[2]).as_kernel_param(),
+            (&self.d_coords[3]).as_kernel_param(),
+            (&self.d_coords[4]).as_kernel_param(),
+            (&self.d_coords[5]).as_kernel_param(),
+            (&self.d_starts).as_kernel_param(),
+            (&self.d_ends).as_kernel_param(),
+            max_error.as_kernel_param(),
+            out_ptr.as_kernel_param(),
+            n_segments.as_kernel_param(),
+        ];
+
+        unsafe {
+            func.clone()
+                .launch_on_stream(&self.stream, cfg, &mut args)
+                .map_err(|e| format!("launch: {e}"))?;
+        }
+        self.in_flight = true;
+        if output.is_some() {
+            return Ok(());
+        }
+        unsafe {
+            result::memcpy_dtoh_async(
+                &mut self.host_out.as_mut_slice()[..segment_count],
+                out_ptr,
+                stream,
+            )
+        }
         .map_err(|e| format!("copy back: {e}"))?;
 
-    let mut cache = HashMap::with_capacity(segment_count);
-    for idx in 0..segment_count {
-        let start = starts[idx] as usize;
-        let end = ends[idx] as usize;
-        cache.insert(
-            (start, end),
-            FitError {
-                bezier: beziers[idx],
-                error: errors[idx],
-            },
-        );
+        self.pending = intervals;
+        self.pending_beziers = beziers;
+        Ok(())
     }
 
-    Ok(cache)
+    /// 等待本流上在途的一块完成，回传的结果写入缓存
+    fn drain(&mut self, cache: &mut ErrorCache, config: &FitConfig) -> Result<(), String> {
+        if !self.in_flight {
+            return Ok(());
+        }
+        unsafe { result::stream::synchronize(self.stream.stream) }
+            .map_err(|e| format!("synchronize: {e}"))?;
+        self.in_flight = false;
+        let errors = self.host_out.as_slice();
+        for ((start, end), (bezier, &error)) in self
+            .pending
+            .drain(..)
+            .zip(self.pending_beziers.drain(..).zip(errors))
+        {
+            cache.insert(
+                start,
+                end,
+                FitError {
+                    bezier,
+                    error: BezierFitter::apply_limit(error.to_f64(), config.max_error),
+                    arc: None,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<T: GpuScalar> Drop for PipelineSlot<T> {
+    /// 出错提前返回时，先等在途的拷贝结束再释放页锁定内存
+    fn drop(&mut self) {
+        unsafe {
+            let _ = result::stream::synchronize(self.stream.stream);
+        }
+    }
 }
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::BoundingBox;
+use crate::io::SvgOptions;
+
+use super::dp::FitResult;
+
+/// 多条路径（笔画、轮廓）的拟合结果，整体导出为一条含多个子路径的 SVG 路径
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FitDocument {
+    pub paths: Vec<FitResult>,
+}
+
+impl FitDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, result: FitResult) {
+        self.paths.push(result);
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// 所有路径的分段总数
+    pub fn num_segments(&self) -> usize {
+        self.paths.iter().map(|p| p.num_segments).sum()
+    }
+
+    /// 所有路径的联合包围盒
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.paths
+            .iter()
+            .filter_map(FitResult::bounding_box)
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// 每条路径输出为一个 `M … Q …` 子路径，空路径跳过
+    pub fn to_svg_path(&self) -> String {
+        self.to_svg_path_with(&SvgOptions::default())
+    }
+
+    /// 按选项输出；相对坐标时每个子路径以绝对 `M` 开始，不依赖上一子路径的终点
+    pub fn to_svg_path_with(&self, options: &SvgOptions) -> String {
+        self.paths
+            .iter()
+            .map(|p| p.to_svg_path_with(options))
+            .filter(|d| !d.is_empty())
+            .map(|d| match d.strip_prefix('m') {
+                Some(rest) => format!("M{rest}"),
+                None => d,
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl From<Vec<FitResult>> for FitDocument {
+    fn from(paths: Vec<FitResult>) -> Self {
+        Self { paths }
+    }
+}
+
+impl FromIterator<FitResult> for FitDocument {
+    fn from_iter<I: IntoIterator<Item = FitResult>>(iter: I) -> Self {
+        Self {
+            paths: iter.into_iter().collect(),
+        }
+    }
+}
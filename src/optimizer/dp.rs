@@ -1,33 +1,244 @@
-﻿use crate::fitting::{BezierFitter, FitError};
-use crate::geometry::{Point2D, QuadraticBezier};
-use rayon::prelude::*;
+﻿use crate::fitting::{fit_arc_segment, BezierFitter, FitError};
+use crate::geometry::{BoundingBox, CubicBezier, IntoPoints, Point2D, QuadraticBezier, Segment, Transform2D};
+use crate::io::SvgOptions;
+use crate::preprocess::{dedup_indices, split_on_gaps, validate_points};
+use crate::par::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::f64::consts::{PI, TAU};
 
-use super::config::FitConfig;
+use super::cache::ErrorCache;
+use super::config::{Backend, CacheMode, EndpointPolicy, ErrorMetric, FitConfig, FitterKind, Multiresolution, Objective, Precision, PrimitiveSet};
+use super::error::FitCurveError;
+use super::intervals::{candidate_starts, IntervalIter};
 
-#[derive(Debug, Clone)]
+/// 候选起点数达到此值的 DP 行分块并行求最优
+const PARALLEL_ROW_LEN: usize = 512;
+
+/// 结果相对于配置约束的满足情况
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum FitStatus {
+    /// 满足所有约束的最优解
+    #[default]
+    Optimal,
+    /// 没有满足 max_error 的路径，放宽误差后求解；`achieved_error` 为实际的最大单段误差
+    Relaxed { achieved_error: f64 },
+    /// 误差不受限时段长、必经点或禁止分段的约束仍无法同时满足，结果违反了部分约束
+    Infeasible,
+    /// 满足所有约束，与 `Optimal` 的结果相同；但误差缓存超出 `FitConfig::max_cache_bytes`，
+    /// 改为不建缓存求解，未使用 GPU 后端。放宽误差或无解时仍报告 `Relaxed` / `Infeasible`
+    Degraded,
+}
+
+impl FitStatus {
+    /// 满足所有约束，即 `Optimal` 或 `Degraded`
+    pub fn is_satisfied(&self) -> bool {
+        matches!(self, FitStatus::Optimal | FitStatus::Degraded)
+    }
+}
+
+/// 单个分段的元数据，与 `curves` 一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SegmentInfo {
+    /// 覆盖的原始输入点下标范围 `start_index..=end_index`
+    pub start_index: usize,
+    pub end_index: usize,
+    /// 输出图元的弧长
+    pub arc_length: f64,
+    /// 范围内输入点到图元的最大距离（不是平方）
+    pub max_deviation: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct FitResult {
     pub curves: Vec<QuadraticBezier>,
     pub total_error: f64,
     pub num_segments: usize,
     pub config: FitConfig,
+    /// 分段边界在输入点中的下标，第 k 段覆盖 `breakpoints[k]..=breakpoints[k + 1]`
+    #[serde(default)]
+    pub breakpoints: Vec<usize>,
+    /// 与 `curves` 一一对应的输出图元，直线段在 SVG 中输出为 `L`
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    /// 与 `curves` 一一对应的单段误差
+    #[serde(default)]
+    pub segment_errors: Vec<f64>,
+    #[serde(default)]
+    pub status: FitStatus,
+    /// 非 `Optimal` 时违反约束的分段 `(start, end)`，下标对应输入点
+    #[serde(default)]
+    pub blocking_intervals: Vec<(usize, usize)>,
+    /// 设置了时间戳时每段覆盖的时间范围 `(start, end)`，与 `curves` 一一对应
+    #[serde(default)]
+    pub segment_times: Vec<(f64, f64)>,
+    /// 每段的下标范围、弧长和最大偏差，与 `curves` 一一对应；`transform` 后不会更新
+    #[serde(default)]
+    pub segment_info: Vec<SegmentInfo>,
 }
 
 impl FitResult {
     /// 杞崲涓?SVG 璺緞瀛楃涓?
     pub fn to_svg_path(&self) -> String {
-        if self.curves.is_empty() {
-            return String::new();
+        self.to_svg_path_with(&SvgOptions::default())
+    }
+
+    /// 所有曲线的精确包围盒，无曲线时返回 None
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.curves
+            .iter()
+            .map(|c| c.bounding_box())
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// 对所有曲线应用仿射变换，无需重新采样拟合；
+    /// `total_error` 保持原坐标系下的数值
+    pub fn transform(&self, m: &Transform2D) -> FitResult {
+        FitResult {
+            curves: self.curves.iter().map(|c| c.transform(m)).collect(),
+            segments: self.segments.iter().map(|s| s.transform(m)).collect(),
+            ..self.clone()
         }
+    }
 
-        let mut path = format!("M {:.2} {:.2}", self.curves[0].p0.x, self.curves[0].p0.y);
-        
-        for curve in &self.curves {
-            path.push(' ');
-            path.push_str(&curve.to_svg_command());
+    /// 把所有控制点取整到 `grid` 的整数倍（如字体单位 1.0 或 0.01 px），便于得到稳定的 diff 和紧凑的输出。
+    /// 相邻曲线取整后仍共用端点；圆弧改为其二次近似。`points` 与拟合时的输入匹配时按
+    /// `config.metric` 重新计算各段误差和 `segment_info`，`status` 不变；`grid` 不是正数时原样返回
+    pub fn quantize(&self, points: &[Point2D], grid: f64) -> FitResult {
+        if !(grid > 0.0 && grid.is_finite()) {
+            return self.clone();
+        }
+        let round = |p: Point2D| Point2D::new((p.x / grid).round() * grid, (p.y / grid).round() * grid);
+        let curves: Vec<QuadraticBezier> = self
+            .curves
+            .iter()
+            .map(|c| QuadraticBezier::new(round(c.p0), round(c.p1), round(c.p2)))
+            .collect();
+        let segments = self
+            .segments
+            .iter()
+            .zip(&curves)
+            .map(|(segment, curve)| match segment {
+                Segment::Line { .. } => Segment::Line { start: curve.p0, end: curve.p2 },
+                _ => Segment::Quad(*curve),
+            })
+            .collect();
+        let mut result = FitResult {
+            curves,
+            segments,
+            ..self.clone()
+        };
+
+        let matches_input = result.breakpoints.len() == result.curves.len() + 1
+            && result.breakpoints.last().map(|&b| b + 1) == Some(points.len());
+        if matches_input {
+            result.segment_errors = result
+                .curves
+                .iter()
+                .zip(result.breakpoints.windows(2))
+                .map(|(curve, w)| {
+                    let segment = &points[w[0]..=w[1]];
+                    match result.config.metric {
+                        ErrorMetric::Mse => BezierFitter::compute_error(curve, segment),
+                        ErrorMetric::Max => BezierFitter::compute_max_error(curve, segment, f64::INFINITY),
+                    }
+                })
+                .collect();
+            result.total_error = result.segment_errors.iter().sum();
+            result.segment_info = DPOptimizer::segment_info(points, &result);
+        }
+        result
+    }
+
+    /// 每个输入点到其所属分段曲线的距离；分段边界上的点归入前一段。
+    /// `points` 须为拟合时的同一组输入，否则退化为到最近曲线的距离
+    pub fn residuals(&self, points: &[Point2D]) -> Vec<f64> {
+        let matches_input = self.breakpoints.len() == self.curves.len() + 1
+            && self.breakpoints.last().map(|&b| b + 1) == Some(points.len());
+        if !matches_input {
+            return points
+                .iter()
+                .map(|p| {
+                    self.curves
+                        .iter()
+                        .map(|c| c.distance_to_point(p))
+                        .fold(f64::INFINITY, f64::min)
+                })
+                .collect();
         }
 
-        path
+        let mut residuals = Vec::with_capacity(points.len());
+        for (k, curve) in self.curves.iter().enumerate() {
+            let start = if k == 0 { 0 } else { self.breakpoints[k] + 1 };
+            let end = self.breakpoints[k + 1];
+            residuals.extend(points[start..=end].iter().map(|p| curve.distance_to_point(p)));
+        }
+        residuals
+    }
+
+    /// 贪心合并相邻分段：两段整体重新拟合后误差不超过 `config.max_error` 即合并，不受
+    /// max_segment_len 限制，用于把被段长上限切开的平缓长弧连成一段；必经点不会被合并掉。
+    /// `points` 和 `config` 须与拟合时相同
+    pub fn merge_segments(&self, points: &[Point2D], config: &FitConfig) -> FitResult {
+        if self.breakpoints.len() != self.curves.len() + 1 || self.curves.len() < 2 {
+            return self.clone();
+        }
+        // 分段下标换算到预处理后的点集
+        let breakpoints: Vec<usize> = match DPOptimizer::preprocess(points, config).1 {
+            Some(map) => self
+                .breakpoints
+                .iter()
+                .map(|b| map.binary_search(b).unwrap_or_else(|i| i))
+                .collect(),
+            None => self.breakpoints.clone(),
+        };
+        let mut merged = DPOptimizer::with_preprocessed(points, config, |points, config| {
+            DPOptimizer::merge_adjacent(points, config, breakpoints)
+        });
+        merged.status = self.status;
+        merged.blocking_intervals = self
+            .blocking_intervals
+            .iter()
+            .copied()
+            .filter(|&(start, end)| merged.breakpoints.windows(2).any(|w| w == [start, end]))
+            .collect();
+        merged
+    }
+
+    /// 按更宽的误差容差重新分段，输入是对已拟合曲线的采样而不是原始点，适合快速生成 LOD。
+    /// 每段按原先覆盖的点数采样，因此段长约束、必经点和分段下标仍对应原始输入
+    pub fn simplify(&self, new_max_error: f64) -> FitResult {
+        if self.curves.is_empty() {
+            return self.clone();
+        }
+        let has_breakpoints = self.breakpoints.len() == self.curves.len() + 1;
+        let mut samples = Vec::new();
+        for (k, curve) in self.curves.iter().enumerate() {
+            let count = if has_breakpoints {
+                self.breakpoints[k + 1] - self.breakpoints[k] + 1
+            } else {
+                16
+            };
+            samples.extend(curve.sample(count).into_iter().skip(usize::from(k > 0)));
+        }
+        // 采样前的点已经预处理过
+        let config = FitConfig {
+            max_error: new_max_error,
+            presmooth: None,
+            dedup_distance: None,
+            ..self.config.clone()
+        };
+        let mut result = DPOptimizer::optimize(&samples, &config);
+        result.config = FitConfig {
+            max_error: new_max_error,
+            ..self.config.clone()
+        };
+        result
+    }
+
+    /// 将所有曲线升阶为三次贝塞尔曲线
+    pub fn to_cubic_curves(&self) -> Vec<CubicBezier> {
+        self.curves.iter().map(|c| c.to_cubic()).collect()
     }
 
     /// 鑾峰彇鎵€鏈夋帶鍒剁偣
@@ -47,9 +258,14 @@ impl FitResult {
             .collect()
     }
 
-    /// 杞崲涓?JSON
-    pub fn to_json(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string_pretty(self)
+    /// 导出为紧凑的二进制格式（bincode），适合批量存储
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// 从 `to_bytes` 的输出恢复
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
     }
 }
 
@@ -59,158 +275,1221 @@ impl serde::Serialize for FitResult {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("FitResult", 4)?;
+        let mut state = serializer.serialize_struct("FitResult", 11)?;
         state.serialize_field("curves", &self.curves)?;
         state.serialize_field("total_error", &self.total_error)?;
         state.serialize_field("num_segments", &self.num_segments)?;
         state.serialize_field("config", &self.config)?;
+        state.serialize_field("breakpoints", &self.breakpoints)?;
+        state.serialize_field("segments", &self.segments)?;
+        state.serialize_field("segment_errors", &self.segment_errors)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("blocking_intervals", &self.blocking_intervals)?;
+        state.serialize_field("segment_times", &self.segment_times)?;
+        state.serialize_field("segment_info", &self.segment_info)?;
         state.end()
     }
 }
 
+/// 拟合前的资源估计，用于提前拒绝或降采样过大的输入
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FitEstimate {
+    /// 误差缓存需要计算的区间数
+    pub intervals: usize,
+    /// 误差缓存占用内存的近似值（字节）
+    pub cache_bytes: usize,
+    /// 计算误差缓存的浮点运算次数，仅作数量级参考
+    pub approx_flops: u64,
+}
+
+/// 误差缓存与 CPU 结果的一致性检查，由 `DPOptimizer::verify_cache` 返回
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParityReport {
+    /// 抽查的区间数
+    pub samples: usize,
+    /// 最大偏差 |cache - cpu| / max(1, cpu)，超过 max_error 的误差按 max_error 计
+    pub max_divergence: f64,
+    /// 偏差最大的区间，缓存中缺失的区间偏差为无穷大
+    pub worst_interval: Option<(usize, usize)>,
+    /// 按精度取的容差：`F64` 为 1e-6，`F32` 为 1e-3
+    pub tolerance: f64,
+}
+
+impl ParityReport {
+    pub fn passed(&self) -> bool {
+        self.max_divergence <= self.tolerance
+    }
+}
+
 pub struct DPOptimizer;
 
 impl DPOptimizer {
     /// 涓讳紭鍖栧嚱鏁?
     pub fn optimize(points: &[Point2D], config: &FitConfig) -> FitResult {
+        Self::optimize_checked(points, config, &mut None)
+    }
+
+    /// 同 `optimize`；`Backend::CudaVerified` 抽查未通过时把报告写入 `divergence`，
+    /// 误差缓存改在 CPU 上计算。内部重新进入优化的路径（多分辨率、放宽误差）都经由此函数
+    fn optimize_checked(points: &[Point2D], config: &FitConfig, divergence: &mut Option<ParityReport>) -> FitResult {
+        Self::with_preprocessed(points, config, |points, config| {
+            let multires = config.multiresolution.filter(|m| m.factor > 1 && config.error_overrides.is_empty());
+            if let Some(multires) = multires {
+                return Self::optimize_multiresolution(points, config, multires, divergence);
+            }
+            let adapted = Self::adapt_window(points, config);
+            let config = adapted.as_ref().unwrap_or(config);
+            // 绗竴姝ワ細骞惰棰勮绠楁墍鏈夊彲鑳藉尯闂寸殑璇樊
+            // 不建缓存时结果相同，但无法使用 GPU
+            if config.cuda.device_dp {
+                if let Ok(result) = Self::solve_on_device(points, config) {
+                    return result;
+                }
+            }
+            let over_budget = config
+                .max_cache_bytes
+                .is_some_and(|budget| Self::estimate(points.len(), config).cache_bytes > budget);
+            if over_budget || config.cache_mode == CacheMode::None {
+                let mut result = Self::with_thread_pool(config, || Self::solve(points, config, None, divergence));
+                if over_budget && result.status == FitStatus::Optimal {
+                    result.status = FitStatus::Degraded;
+                }
+                return result;
+            }
+            let gpu_cache = if config.deterministic {
+                Err("deterministic mode computes on the cpu".to_string())
+            } else {
+                try_compute_error_cache_gpu(points, config, divergence)
+            };
+            let error_cache = match gpu_cache {
+                Ok(cache) => cache,
+                Err(_) => Self::compute_error_cache(points, config),
+            };
+
+            Self::with_thread_pool(config, || Self::solve(points, config, Some(&error_cache), divergence))
+        })
+    }
+
+    /// 使用已有的误差缓存优化（例如 `ErrorCache::build` 或 `ErrorCache::from_bytes` 的结果），
+    /// 缺失的区间会按完整误差补算并写回缓存，同一缓存可在不同 max_error 下复用。
+    /// 缓存按更严的上限计算时，记为正无穷的区间会重新计算；单段拟合方式（拟合器、鲁棒损失、
+    /// 误差度量、精度、图元等）与 `config` 不同时整个缓存重新计算
+    pub fn optimize_with_cache(
+        points: &[Point2D],
+        config: &FitConfig,
+        cache: &mut ErrorCache,
+    ) -> FitResult {
+        Self::with_preprocessed(points, config, |points, config| {
+            let adapted = Self::adapt_window(points, config);
+            let config = adapted.as_ref().unwrap_or(config);
+            cache.prepare_for(config);
+            Self::fill_error_cache(points, config, cache, f64::INFINITY);
+            Self::with_thread_pool(config, || Self::solve(points, config, Some(cache), &mut None))
+        })
+    }
+
+    /// 设置了 `adaptive_window` 时按局部曲率生成 `max_segment_lens`，否则返回 None
+    fn adapt_window(points: &[Point2D], config: &FitConfig) -> Option<FitConfig> {
+        let adaptive = config.adaptive_window?;
+        let n = points.len();
+        let stride = adaptive.stride.max(1);
+
+        // 跨 2·stride 个点的弦方向作为切线，turning[k] 为 0..=k 的累计转角；弦退化时沿用上一个方向
+        let mut turning = vec![0.0; n];
+        let mut previous: Option<f64> = None;
+        for k in 0..n {
+            let (a, b) = (points[k.saturating_sub(stride)], points[(k + stride).min(n - 1)]);
+            let angle = if a == b { previous } else { Some((b.y - a.y).atan2(b.x - a.x)) };
+            if let (Some(from), Some(to)) = (previous, angle) {
+                let turn = (to - from + PI).rem_euclid(TAU) - PI;
+                turning[k] = turning[k - 1] + turn.abs();
+            } else if k > 0 {
+                turning[k] = turning[k - 1];
+            }
+            previous = angle;
+        }
+
+        // 累计转角单调不减，最早的起点随终点单调右移
+        let mut first = 0;
+        let max_segment_lens = (0..n)
+            .map(|i| {
+                while turning[i] - turning[first] > adaptive.max_turning {
+                    first += 1;
+                }
+                (i - first + 1).clamp(config.min_segment_len, config.max_segment_len)
+            })
+            .collect();
+        Some(FitConfig {
+            max_segment_lens,
+            ..config.clone()
+        })
+    }
+
+    /// 按配置去重、平滑；无需预处理时返回 None。第二项为去重后保留点的原始下标
+    pub(crate) fn preprocess(
+        points: &[Point2D],
+        config: &FitConfig,
+    ) -> (Option<Vec<Point2D>>, Option<Vec<usize>>, Option<Transform2D>) {
+        let mut prepared: Option<Vec<Point2D>> = None;
+        let mut index_map = None;
+
+        if let Some(min_distance) = config.dedup_distance {
+            let mut kept = dedup_indices(points, min_distance);
+            // 必经点不能被去重掉
+            if !config.required_breakpoints.is_empty() {
+                kept.extend(config.required_breakpoints.iter().filter(|&&r| r < points.len()));
+                kept.sort_unstable();
+                kept.dedup();
+            }
+            if kept.len() < points.len() {
+                prepared = Some(kept.iter().map(|&i| points[i]).collect());
+                index_map = Some(kept);
+            }
+        }
+        if let Some(smoothing) = &config.presmooth {
+            prepared = Some(smoothing.apply(prepared.as_deref().unwrap_or(points)));
+        }
+
+        // 归一化放在最后，去重距离和平滑参数仍按原坐标解释；返回映射回原坐标的变换
+        let mut denormalize = None;
+        if config.normalize {
+            let source = prepared.as_deref().unwrap_or(points);
+            if let Some(bbox) = BoundingBox::from_points(source) {
+                let size = bbox.width().max(bbox.height());
+                if size > 0.0 && size.is_finite() {
+                    let origin = bbox.min;
+                    let normalized = source
+                        .iter()
+                        .map(|p| Point2D::new((p.x - origin.x) / size, (p.y - origin.y) / size))
+                        .collect();
+                    prepared = Some(normalized);
+                    denormalize = Some(
+                        Transform2D::scale(size, size)
+                            .then(&Transform2D::translate(origin.x, origin.y)),
+                    );
+                }
+            }
+        }
+        (prepared, index_map, denormalize)
+    }
+
+    /// 按配置去重、平滑、归一化后再拟合，并把分段下标和曲线映射回原始输入
+    fn with_preprocessed(
+        points: &[Point2D],
+        config: &FitConfig,
+        fit: impl FnOnce(&[Point2D], &FitConfig) -> FitResult,
+    ) -> FitResult {
+        let original_len = points.len();
+        let original_points = points;
+        let (prepared, index_map, denormalize) = Self::preprocess(points, config);
+        let points = prepared.as_deref().unwrap_or(points);
+
+        // 必经点、禁止分段的下标、时间戳和误差覆盖范围换算到去重后的点集
+        let remapped;
+        let fit_config = match &index_map {
+            Some(map)
+                if !config.required_breakpoints.is_empty()
+                    || !config.forbidden_breakpoints.is_empty()
+                    || !config.timestamps.is_empty()
+                    || !config.error_overrides.is_empty()
+                    || !config.max_segment_lens.is_empty() =>
+            {
+                remapped = FitConfig {
+                    required_breakpoints: config
+                        .required_breakpoints
+                        .iter()
+                        .filter_map(|r| map.binary_search(r).ok())
+                        .collect(),
+                    forbidden_breakpoints: if config.forbidden_breakpoints.is_empty() {
+                        Vec::new()
+                    } else {
+                        map.iter()
+                            .map(|&i| config.forbidden_breakpoints.get(i).copied().unwrap_or(false))
+                            .collect()
+                    },
+                    timestamps: if config.timestamps.len() == original_len {
+                        map.iter().map(|&i| config.timestamps[i]).collect()
+                    } else {
+                        Vec::new()
+                    },
+                    error_overrides: config
+                        .error_overrides
+                        .iter()
+                        .map(|(range, limit)| {
+                            let start = map.partition_point(|&i| i < range.start);
+                            let end = map.partition_point(|&i| i < range.end);
+                            (start..end, *limit)
+                        })
+                        .collect(),
+                    max_segment_lens: if config.max_segment_lens.is_empty() {
+                        Vec::new()
+                    } else {
+                        map.iter().map(|&i| config.max_segment_len_at(i)).collect()
+                    },
+                    ..config.clone()
+                };
+                &remapped
+            }
+            _ => config,
+        };
+
+        let mut result = match Self::trivial_result(points, fit_config) {
+            Some(result) => result,
+            None => fit(points, fit_config),
+        };
+        if !fit_config.snap_endpoints {
+            Self::refine_endpoints(points, fit_config, &mut result);
+        }
+        result.config = config.clone();
+        if let Some(m) = denormalize {
+            result = result.transform(&m);
+        }
+
+        if let Some(map) = index_map {
+            for b in &mut result.breakpoints {
+                *b = map[*b];
+            }
+            for (start, end) in &mut result.blocking_intervals {
+                *start = map[*start];
+                *end = map[*end];
+            }
+        }
+        if config.timestamps.len() == original_len {
+            result.segment_times = result
+                .breakpoints
+                .windows(2)
+                .map(|w| (config.timestamps[w[0]], config.timestamps[w[1]]))
+                .collect();
+        }
+        result.segment_info = Self::segment_info(original_points, &result);
+        result
+    }
+
+    /// 按原始输入计算每段的元数据；分段下标与输入不匹配时为空
+    pub(crate) fn segment_info(points: &[Point2D], result: &FitResult) -> Vec<SegmentInfo> {
+        let valid = result.breakpoints.len() == result.curves.len() + 1
+            && result.segments.len() == result.curves.len()
+            && result.breakpoints.last().is_some_and(|&b| b < points.len());
+        if !valid {
+            return Vec::new();
+        }
+        result
+            .segments
+            .iter()
+            .zip(result.breakpoints.windows(2))
+            .map(|(segment, w)| SegmentInfo {
+                start_index: w[0],
+                end_index: w[1],
+                arc_length: segment.length(),
+                max_deviation: points[w[0]..=w[1]]
+                    .iter()
+                    .map(|p| segment.distance_to_point(p))
+                    .fold(0.0, f64::max),
+            })
+            .collect()
+    }
+
+    /// 点数为 0 或不超过最短段长时无需 DP
+    fn trivial_result(points: &[Point2D], config: &FitConfig) -> Option<FitResult> {
         let n = points.len();
 
-        // 杈圭晫妫€鏌?
         if n == 0 {
-            return FitResult {
+            return Some(FitResult {
                 curves: vec![],
                 total_error: 0.0,
                 num_segments: 0,
                 config: config.clone(),
-            };
+                breakpoints: vec![],
+                segments: vec![],
+                segment_errors: vec![],
+                status: FitStatus::Optimal,
+                blocking_intervals: vec![],
+                segment_times: vec![],
+                segment_info: vec![],
+            });
         }
 
-        if n <= config.min_segment_len {
+        if n <= config.min_segment_len && Self::required_indices(config, n).is_empty() {
             // 鐐瑰お灏戞垨鍒氬ソ锛岀洿鎺ユ嫙鍚堜竴娈?
-            let fit = BezierFitter::fit_segment(points);
-            return FitResult {
+            let fit = Self::fit_interval(points, 0, n - 1, config, f64::INFINITY);
+            return Some(FitResult {
                 curves: vec![fit.bezier],
                 total_error: fit.error,
                 num_segments: 1,
                 config: config.clone(),
-            };
+                breakpoints: vec![0, n - 1],
+                segments: vec![Self::output_segment(&fit, config)],
+                segment_errors: vec![fit.error],
+                status: FitStatus::Optimal,
+                blocking_intervals: vec![],
+                segment_times: vec![],
+                segment_info: vec![],
+            });
         }
 
-        // 绗竴姝ワ細骞惰棰勮绠楁墍鏈夊彲鑳藉尯闂寸殑璇樊
-        let error_cache = match try_compute_error_cache_cuda(points, config) {
-            Ok(cache) => cache,
-            Err(_) => Self::compute_error_cache(points, config),
-        };
+        None
+    }
+
+    /// `error_cache` 为 None 时在 DP 中逐行计算区间误差，只保留被选中的拟合结果
+    fn solve(
+        points: &[Point2D],
+        config: &FitConfig,
+        error_cache: Option<&ErrorCache>,
+        divergence: &mut Option<ParityReport>,
+    ) -> FitResult {
+        let n = points.len();
 
         // 绗簩姝ワ細DP
-        let mut seg_dp = vec![usize::MAX; n];
-        let mut err_dp = vec![f64::INFINITY; n];
-        let mut parent = vec![0; n];
-        seg_dp[0] = 0;
-        err_dp[0] = 0.0;
-
-        let max_len = config.max_segment_len.max(1);
-        for i in config.min_segment_len..n {
-            let start = i.saturating_sub(max_len - 1);
-            let end = if config.min_segment_len > 0 {
-                i.saturating_sub(config.min_segment_len - 1)
-            } else {
-                i  // 杈圭晫淇濇姢
-            };
+        let mut table = DpTable::new(n);
 
-            for j in start..=end {
-                if let Some(fit) = error_cache.get(&(j, i)) {
-                    if fit.error > config.max_error {
-                        continue; // 鍓灊
-                    }
+        // 分段不能跨过必经点：next_required[j] 为从 j 出发的分段最远可到达的下标
+        let required = Self::required_indices(config, n);
+        let mut next_required = vec![n - 1; n];
+        let mut next = n - 1;
+        for j in (0..n).rev() {
+            next_required[j] = next;
+            if required.binary_search(&j).is_ok() {
+                next = j;
+            }
+        }
+        let forced = Self::forced_pieces(points, config, &required);
+        let forbidden = |i: usize| {
+            i + 1 < n
+                && config.forbidden_breakpoints.get(i).copied().unwrap_or(false)
+                && required.binary_search(&i).is_err()
+        };
 
-                    if seg_dp[j] == usize::MAX {
-                        continue;
+        // 不使用缓存时按终点记录当前选中的拟合结果，供回溯使用
+        let mut chosen: HashMap<usize, FitError> = HashMap::new();
+
+        for i in 1..n {
+            if forbidden(i) {
+                continue; // 不能在此结束分段，后续也就无法从这里出发
+            }
+            if let Some((j, fit)) = forced.get(&i) {
+                table.relax(config.objective, i, *j, fit.error);
+            }
+            if i < config.min_segment_len {
+                continue;
+            }
+
+            let (start, end) = candidate_starts(i, config).into_inner();
+
+            let Some(error_cache) = error_cache else {
+                let mut row: Vec<usize> = (start..=end)
+                    .filter(|&j| i <= next_required[j] && table.seg[j] != usize::MAX)
+                    .collect();
+                // 最少段数目标下按起点的段数分组、由少到多计算，已有更少段数的可行候选时后面的组不可能更优
+                let grouped = config.objective == Objective::MinSegments;
+                if grouped {
+                    row.sort_by_key(|&j| (table.seg[j], j));
+                }
+                let mut rest = row.as_slice();
+                while let Some(&first) = rest.first() {
+                    let segs = table.seg[first] + 1;
+                    if grouped && segs > table.seg[i] {
+                        break;
                     }
-                    let cand_seg = seg_dp[j] + 1;
-                    let cand_err = err_dp[j] + fit.error;
-                    if cand_seg < seg_dp[i] || (cand_seg == seg_dp[i] && cand_err < err_dp[i]) {
-                        seg_dp[i] = cand_seg;
-                        err_dp[i] = cand_err;
-                        parent[i] = j;
+                    let len = if grouped {
+                        rest.partition_point(|&j| table.seg[j] + 1 == segs)
+                    } else {
+                        rest.len()
+                    };
+                    let (group, tail) = rest.split_at(len);
+                    let fits: Vec<(usize, FitError)> = group
+                        .par_iter()
+                        .map(|&j| (j, Self::fit_interval(points, j, i, config, config.max_error_for(j, i))))
+                        .collect();
+                    for (j, fit) in fits {
+                        if fit.error <= config.max_error_for(j, i) && table.relax(config.objective, i, j, fit.error) {
+                            chosen.insert(i, fit);
+                        }
                     }
+                    rest = tail;
                 }
+                continue;
+            };
+            let candidate = |j: usize| {
+                if i > next_required[j] {
+                    return None;
+                }
+                error_cache
+                    .get(j, i)
+                    .filter(|fit| fit.error <= config.max_error_for(j, i))
+                    .and_then(|fit| table.candidate(j, fit.error))
+            };
+            // 长行分块并行归约：各块先取块内最优，再合并；比较规则与逐个松弛相同，结果不变
+            let best = if end.saturating_sub(start) >= PARALLEL_ROW_LEN {
+                (start..end + 1)
+                    .into_par_iter()
+                    .with_min_len(PARALLEL_ROW_LEN / 4)
+                    .filter_map(candidate)
+                    .reduce_with(|a, b| if a.precedes(&b, config.objective) { a } else { b })
+            } else {
+                (start..=end)
+                    .filter_map(candidate)
+                    .reduce(|a, b| if a.precedes(&b, config.objective) { a } else { b })
+            };
+            if let Some(best) = best {
+                table.update(config.objective, i, best);
             }
         }
 
         // 绗笁姝ワ細鍥炴函璺緞
-        let total_error = err_dp[n - 1];
+        let total_error = table.err[n - 1];
         
         // 妫€鏌ユ槸鍚︽壘鍒版湁鏁堣矾寰?
         if total_error.is_infinite() {
             // 娌℃湁鎵惧埌绗﹀悎璇樊瑕佹眰鐨勮矾寰勶紝浣跨敤瀹芥澗鐨勮宸噸璇?
-            eprintln!("Warning: No valid path found with max_error={:.2}, using fallback", config.max_error);
-            let fallback_config = FitConfig::new_clamped(
-                config.min_segment_len,
-                config.max_segment_len,
-                f64::INFINITY  // 涓嶉檺鍒惰宸?
-            );
-            return Self::optimize(points, &fallback_config);
+            // 输入已经预处理过，回退时不再重复
+            let mut fallback_config = FitConfig {
+                max_error: f64::INFINITY,
+                presmooth: None,
+                dedup_distance: None,
+                error_overrides: Vec::new(),
+                ..config.clone()
+            };
+            if !config.max_error.is_finite() {
+                // 误差已不受限仍无解，说明必经点之间的间隔无法按段长约束划分
+                if config.min_segment_len <= 2 {
+                    return Self::single_segment_result(points, config);
+                }
+                fallback_config.min_segment_len = 2;
+            }
+            let mut result = Self::optimize_checked(points, &fallback_config, divergence);
+            if result.status.is_satisfied() {
+                Self::mark_relaxed(points, config, &mut result);
+            }
+            return result;
         }
         
-        let curves = Self::reconstruct_curves(n - 1, &parent, &error_cache);
+        let (fits, breakpoints) = Self::reconstruct_curves(n - 1, &table.parent, |start, end| {
+            match error_cache {
+                Some(cache) => cache.get(start, end),
+                None => chosen.get(&end),
+            }
+            .or_else(|| forced.get(&end).filter(|(j, _)| *j == start).map(|(_, fit)| fit))
+        });
+        Self::assemble_result(config, total_error, &fits, breakpoints)
+    }
+
+    /// `CudaOptions::device_dp`：误差和 DP 都在 CUDA 设备上完成，只取回断点，
+    /// 再在 CPU 上重新拟合路径上的各段；条件不满足或无解时由调用方回退
+    fn solve_on_device(points: &[Point2D], config: &FitConfig) -> Result<FitResult, String> {
+        if config.deterministic || !matches!(config.backend, Backend::Cuda | Backend::Auto) {
+            return Err("device dp needs the cuda backend".to_string());
+        }
+        check_gpu_kernel_support(config)?;
+        let n = points.len();
+        if !Self::required_indices(config, n).is_empty()
+            || config.forbidden_breakpoints.iter().any(|&f| f)
+        {
+            return Err("device dp does not support breakpoint constraints".to_string());
+        }
+        if !config.error_overrides.is_empty() || !config.max_segment_lens.is_empty() {
+            return Err("device dp does not support error overrides or per-end segment lengths".to_string());
+        }
+
+        let parent = try_solve_dp_cuda(points, config)?;
+        let mut chosen: HashMap<usize, FitError> = HashMap::new();
+        let mut end = n - 1;
+        while end > 0 {
+            let start = parent[end];
+            chosen.insert(end, Self::fit_interval(points, start, end, config, f64::INFINITY));
+            end = start;
+        }
+        let (fits, breakpoints) = Self::reconstruct_curves(n - 1, &parent, |_, end| chosen.get(&end));
+        let total_error = fits.iter().map(|fit| fit.error).sum();
+        Ok(Self::assemble_result(config, total_error, &fits, breakpoints))
+    }
+
+    /// 由回溯得到的各段拟合结果组装输出
+    fn assemble_result(
+        config: &FitConfig,
+        total_error: f64,
+        fits: &[FitError],
+        breakpoints: Vec<usize>,
+    ) -> FitResult {
+        let curves: Vec<QuadraticBezier> = fits.iter().map(|fit| fit.bezier).collect();
         let num_segments = curves.len();
+        let segments = fits
+            .iter()
+            .map(|fit| Self::output_segment(fit, config))
+            .collect();
 
         FitResult {
             curves,
             total_error,
             num_segments,
             config: config.clone(),
+            breakpoints,
+            segments,
+            segment_errors: fits.iter().map(|fit| fit.error).collect(),
+            status: FitStatus::Optimal,
+            blocking_intervals: vec![],
+            segment_times: vec![],
+            segment_info: vec![],
+        }
+    }
+
+    /// 按点数和段长约束估计误差缓存的规模，不做任何拟合；
+    /// 去重等预处理只会减少点数，因此结果是上界
+    pub fn estimate(points_len: usize, config: &FitConfig) -> FitEstimate {
+        // 最小二乘每点约 30 次运算，误差评估每点约 100 个采样、每个采样约 12 次运算
+        const FLOPS_PER_POINT: u64 = 30 + 100 * 12;
+        // 哈希表每个条目另有一个控制字节，且最多 7/8 满载
+        const ENTRY_BYTES: usize = std::mem::size_of::<((usize, usize), FitError)>() + 1;
+
+        let mut intervals = 0usize;
+        let mut points_total = 0u64;
+        for i in config.min_segment_len..points_len {
+            let starts = candidate_starts(i, config);
+            if starts.is_empty() {
+                continue;
+            }
+            let (start, end) = starts.into_inner();
+            let count = end - start + 1;
+            intervals += count;
+            // 区间点数从 i - end + 1 到 i - start + 1 的等差数列之和
+            points_total += (count as u64) * ((i - end + 1) + (i - start + 1)) as u64 / 2;
+        }
+
+        FitEstimate {
+            intervals,
+            cache_bytes: intervals.saturating_mul(ENTRY_BYTES) / 7 * 8,
+            approx_flops: points_total.saturating_mul(FLOPS_PER_POINT),
+        }
+    }
+
+    /// 按区间下标的哈希抽取约 `samples` 个候选区间，在 CPU 上重算误差并与 `cache` 比较；
+    /// 抽样是确定的，同样的输入总是检查同一组区间
+    pub fn verify_cache(
+        points: &[Point2D],
+        config: &FitConfig,
+        cache: &ErrorCache,
+        samples: usize,
+    ) -> ParityReport {
+        let total = Self::estimate(points.len(), config).intervals as u64;
+        let sampled: Vec<(usize, usize)> = IntervalIter::new(points.len(), config)
+            .filter(|&(j, i)| splitmix64(((j as u64) << 32) ^ i as u64) % total.max(1) < samples as u64)
+            .collect();
+        let limit = config.max_error;
+        let divergences: Vec<f64> = sampled
+            .par_iter()
+            .map(|&(j, i)| {
+                let Some(cached) = cache.get(j, i) else {
+                    return f64::INFINITY;
+                };
+                // 超过上限的误差按上限计，两者都超过时视为一致
+                let cpu = Self::fit_interval(points, j, i, config, limit).error.min(limit);
+                (cached.error.min(limit) - cpu).abs() / cpu.abs().max(1.0)
+            })
+            .collect();
+        let worst = divergences
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(k, &divergence)| (sampled[k], divergence));
+
+        ParityReport {
+            samples: sampled.len(),
+            max_divergence: worst.map_or(0.0, |(_, divergence)| divergence),
+            worst_interval: worst.map(|(interval, _)| interval),
+            tolerance: match config.precision {
+                Precision::F64 => 1e-6,
+                Precision::F32 => 1e-3,
+            },
         }
     }
 
     /// 骞惰璁＄畻鎵€鏈夊尯闂寸殑璇樊
-    fn compute_error_cache(
+    pub fn compute_error_cache(points: &[Point2D], config: &FitConfig) -> ErrorCache {
+        let mut cache = ErrorCache::new();
+        Self::fill_error_cache(points, config, &mut cache, config.max_error);
+        // 各区间按各自的上限截断，记录最严的一个
+        let tightest = config
+            .error_overrides
+            .iter()
+            .map(|&(_, limit)| limit)
+            .fold(config.max_error, f64::min);
+        cache.mark_source(config, tightest);
+        cache
+    }
+
+    /// 按配置选择 `points[start..=end]` 的单段拟合方式；误差超过 limit 时允许提前终止，记为正无穷
+    fn fit_interval(
         points: &[Point2D],
+        start: usize,
+        end: usize,
         config: &FitConfig,
-    ) -> HashMap<(usize, usize), FitError> {
+        limit: f64,
+    ) -> FitError {
+        let segment = &points[start..=end];
+        let times = if config.timestamps.len() == points.len() {
+            Some(&config.timestamps[start..=end])
+        } else {
+            None
+        };
+        let mut fit = match &config.fitter {
+            FitterKind::Ransac(params) => BezierFitter::fit_segment_ransac(segment, params),
+            FitterKind::Orthogonal { iterations } => {
+                let mut fit = BezierFitter::fit_segment_orthogonal(segment, *iterations);
+                if config.metric == ErrorMetric::Max {
+                    fit.error = BezierFitter::compute_max_error(&fit.bezier, segment, f64::INFINITY);
+                }
+                fit
+            }
+            FitterKind::LeastSquares => match (&config.robust, times) {
+                (Some(loss), _) => BezierFitter::fit_segment_robust(segment, loss),
+                (None, _) if config.function_graph => BezierFitter::fit_segment_graph(segment),
+                (None, Some(times)) => BezierFitter::fit_segment_timed(segment, times),
+                (None, None) if config.endpoint_policy != EndpointPolicy::Fixed => {
+                    Self::fit_interval_endpoints(points, start, end, config)
+                }
+                (None, None) if config.metric == ErrorMetric::Max => {
+                    BezierFitter::fit_segment_max(segment, limit)
+                }
+                (None, None) if config.precision == Precision::F32 => {
+                    BezierFitter::fit_segment_f32(segment, limit)
+                }
+                (None, None) if limit.is_finite() => {
+                    BezierFitter::fit_segment_with_limit(segment, limit)
+                }
+                (None, None) => BezierFitter::fit_segment(segment),
+            },
+        };
+
+        // 圆弧只有几何距离，无法与 RANSAC、按时间参数化和函数图像的误差比较（`validate` 拒绝这些组合）
+        let arc_comparable =
+            times.is_none() && !config.function_graph && !matches!(config.fitter, FitterKind::Ransac(_));
+        if config.primitives == PrimitiveSet::BezierAndArc && arc_comparable {
+            if let Some(mut arc) = fit_arc_segment(segment) {
+                // 圆弧拟合给出的是均方误差，与曲线比较前换成相同的度量
+                let robust = match config.fitter {
+                    FitterKind::LeastSquares => config.robust.as_ref(),
+                    _ => None,
+                };
+                if let Some(circle) = arc.arc {
+                    let distances = segment.iter().map(|p| circle.distance_to_point(p));
+                    match (robust, config.metric) {
+                        (Some(loss), _) => {
+                            arc.error = distances.map(|d| loss.loss(d)).sum::<f64>() / segment.len() as f64;
+                        }
+                        (None, ErrorMetric::Max) => arc.error = distances.map(|d| d * d).fold(0.0, f64::max),
+                        (None, ErrorMetric::Mse) => {}
+                    }
+                }
+                if arc.error <= fit.error {
+                    fit = arc;
+                }
+            }
+        }
+        fit.error = BezierFitter::apply_limit(fit.error, limit);
+        fit
+    }
+
+    /// 按 `endpoint_policy` 求解端点，误差按 `metric` 完整计算
+    fn fit_interval_endpoints(
+        points: &[Point2D],
+        start: usize,
+        end: usize,
+        config: &FitConfig,
+    ) -> FitError {
+        let segment = &points[start..=end];
+        let mut fit = match config.endpoint_policy {
+            EndpointPolicy::Free => BezierFitter::fit_segment_free(segment),
+            EndpointPolicy::LocalAverage { radius } => {
+                let average = |center: usize| {
+                    let last = (center + radius).min(points.len() - 1);
+                    let window = &points[center.saturating_sub(radius)..=last];
+                    let (sx, sy) = window.iter().fold((0.0, 0.0), |(sx, sy), p| (sx + p.x, sy + p.y));
+                    Point2D::new(sx / window.len() as f64, sy / window.len() as f64)
+                };
+                BezierFitter::fit_segment_with_endpoints(segment, average(start), average(end))
+            }
+            EndpointPolicy::Fixed => BezierFitter::fit_segment(segment),
+        };
+        if config.metric == ErrorMetric::Max {
+            fit.error = BezierFitter::compute_max_error(&fit.bezier, segment, f64::INFINITY);
+        }
+        fit
+    }
+
+    /// `snap_endpoints = false` 时逐个断点在采样点附近做模式搜索，移动相邻两段共用的端点；
+    /// 两段误差之和下降、且都不超过各自上限（或原有误差）时接受。分段下标不变
+    fn refine_endpoints(points: &[Point2D], config: &FitConfig, result: &mut FitResult) {
+        const SWEEPS: usize = 3;
+        const STEPS: usize = 12;
+
+        let plain = config.fitter == FitterKind::LeastSquares
+            && config.robust.is_none()
+            && !config.function_graph
+            && config.timestamps.len() != points.len()
+            && config.endpoint_policy == EndpointPolicy::Fixed
+            && config.primitives == PrimitiveSet::Bezier;
+        let breakpoints = result.breakpoints.clone();
+        if !plain
+            || breakpoints.len() < 3
+            || breakpoints.len() != result.curves.len() + 1
+            || result.segment_errors.len() != result.curves.len()
+        {
+            return;
+        }
+        let fit = |start: usize, end: usize, p0: Point2D, p2: Point2D| {
+            let segment = &points[start..=end];
+            let mut fit = BezierFitter::fit_segment_with_endpoints(segment, p0, p2);
+            if config.metric == ErrorMetric::Max {
+                fit.error = BezierFitter::compute_max_error(&fit.bezier, segment, f64::INFINITY);
+            }
+            fit
+        };
+
+        let mut ends: Vec<Point2D> = breakpoints.iter().map(|&b| points[b]).collect();
+        let mut fits: Vec<FitError> = result
+            .curves
+            .iter()
+            .zip(&result.segment_errors)
+            .map(|(&bezier, &error)| FitError { bezier, error, arc: None })
+            .collect();
+        let mut changed = vec![false; fits.len()];
+        for _ in 0..SWEEPS {
+            for k in 1..breakpoints.len() - 1 {
+                let (prev, b, next) = (breakpoints[k - 1], breakpoints[k], breakpoints[k + 1]);
+                let limits = (config.max_error_for(prev, b), config.max_error_for(b, next));
+                // 端点只在断点到相邻采样点的距离内移动
+                let sample = points[b];
+                let radius = points[b - 1].distance_to(&sample).max(points[b + 1].distance_to(&sample));
+                let mut step = radius / 2.0;
+                for _ in 0..STEPS {
+                    let current = fits[k - 1].error + fits[k].error;
+                    let best = [(step, 0.0), (-step, 0.0), (0.0, step), (0.0, -step)]
+                        .into_iter()
+                        .filter_map(|(dx, dy)| {
+                            let q = Point2D::new(ends[k].x + dx, ends[k].y + dy);
+                            if q.distance_to(&sample) > radius {
+                                return None;
+                            }
+                            let left = fit(prev, b, ends[k - 1], q);
+                            let right = fit(b, next, q, ends[k + 1]);
+                            let within = left.error <= limits.0.max(fits[k - 1].error)
+                                && right.error <= limits.1.max(fits[k].error);
+                            (within && left.error + right.error < current).then_some((q, left, right))
+                        })
+                        .min_by(|a, b| (a.1.error + a.2.error).total_cmp(&(b.1.error + b.2.error)));
+                    match best {
+                        Some((q, left, right)) => {
+                            ends[k] = q;
+                            fits[k - 1] = left;
+                            fits[k] = right;
+                            changed[k - 1] = true;
+                            changed[k] = true;
+                        }
+                        None => step /= 2.0,
+                    }
+                }
+            }
+        }
+
+        if !changed.contains(&true) {
+            return;
+        }
+        for (k, fit) in fits.iter().enumerate().filter(|&(k, _)| changed[k]) {
+            result.curves[k] = fit.bezier;
+            result.segments[k] = Self::output_segment(fit, config);
+            result.segment_errors[k] = fit.error;
+        }
+        result.total_error = result.segment_errors.iter().sum();
+    }
+
+    /// 缓存中的区间结果转换为输出图元
+    fn output_segment(fit: &FitError, config: &FitConfig) -> Segment {
+        match fit.arc {
+            Some(arc) => Segment::Arc(arc),
+            None => Segment::from_quad(&fit.bezier, config.line_tolerance),
+        }
+    }
+
+    /// 补算缓存中缺失的区间；误差超过 limit 的区间记为正无穷，
+    /// limit 有限时各区间改用 `FitConfig::max_error_for` 给出的上限
+    fn fill_error_cache(
+        points: &[Point2D],
+        config: &FitConfig,
+        cache: &mut ErrorCache,
+        limit: f64,
+    ) {
+        let n = points.len();
+        let intervals: Vec<(usize, usize)> = IntervalIter::new(n, config)
+            .filter(|&(j, i)| !cache.contains(j, i))
+            .collect();
+
+        // 下界已超过 limit 的区间不做精确的误差计算，只记录下界；仅用于普通最小二乘
+        let prunable = limit.is_finite()
+            && config.fitter == FitterKind::LeastSquares
+            && config.robust.is_none()
+            && !config.function_graph
+            && config.timestamps.len() != n
+            && config.endpoint_policy == EndpointPolicy::Fixed
+            && config.primitives == PrimitiveSet::Bezier
+            && config.precision == Precision::F64;
+
+        // 骞惰璁＄畻
+        let compute = || -> Vec<_> {
+            intervals
+                .par_iter()
+                .map(|&(start, end)| {
+                    let segment = &points[start..=end];
+                    let limit = if limit.is_finite() { config.max_error_for(start, end) } else { limit };
+                    let pruned = prunable.then(|| {
+                        let bezier = BezierFitter::compute_bezier(segment);
+                        let bound = error_lower_bound(&bezier, segment, config.metric, limit);
+                        FitError { bezier, error: bound, arc: None }
+                    });
+                    let fit = match pruned {
+                        Some(fit) if fit.error > limit * (1.0 + 1e-9) => FitError {
+                            error: f64::INFINITY,
+                            ..fit
+                        },
+                        _ => Self::fit_interval(points, start, end, config, limit),
+                    };
+                    ((start, end), fit)
+                })
+                .collect()
+        };
+        let results = Self::with_thread_pool(config, compute);
+
+        for ((start, end), fit) in results {
+            cache.insert(start, end, fit);
+        }
+    }
+
+    /// 在 `num_threads` 指定大小的线程池中运行，未指定时使用当前线程池
+    #[cfg(feature = "parallel")]
+    fn with_thread_pool<R: Send>(config: &FitConfig, run: impl FnOnce() -> R + Send) -> R {
+        let pool = config.num_threads.and_then(|num_threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads.max(1))
+                .build()
+                .ok()
+        });
+        match pool {
+            Some(pool) => pool.install(run),
+            None => run(),
+        }
+    }
+
+    /// 未启用 `parallel` 时没有线程池，直接在当前线程运行
+    #[cfg(not(feature = "parallel"))]
+    fn with_thread_pool<R: Send>(_config: &FitConfig, run: impl FnOnce() -> R + Send) -> R {
+        run()
+    }
+
+    /// 范围内、去掉首尾后排序去重的必经点下标
+    fn required_indices(config: &FitConfig, n: usize) -> Vec<usize> {
+        let mut required: Vec<usize> = config
+            .required_breakpoints
+            .iter()
+            .copied()
+            .filter(|&r| r > 0 && r + 1 < n)
+            .collect();
+        required.sort_unstable();
+        required.dedup();
+        required
+    }
+
+    /// 相邻必经点之间短于最短段长的间隔只能整体拟合为一段，按终点下标索引
+    fn forced_pieces(
+        points: &[Point2D],
+        config: &FitConfig,
+        required: &[usize],
+    ) -> HashMap<usize, (usize, FitError)> {
         let n = points.len();
-        let mut intervals = Vec::new();
-
-        // 鐢熸垚鎵€鏈夐渶瑕佽绠楃殑鍖洪棿
-        let max_len = config.max_segment_len.max(1);
-        for i in config.min_segment_len..n {
-            let start = i.saturating_sub(max_len - 1);
-            let end = if config.min_segment_len > 0 {
-                i.saturating_sub(config.min_segment_len - 1)
+        let mut anchors = Vec::with_capacity(required.len() + 2);
+        anchors.push(0);
+        anchors.extend_from_slice(required);
+        anchors.push(n - 1);
+
+        anchors
+            .windows(2)
+            .filter(|w| w[1] < config.min_segment_len || w[1] - w[0] + 1 < config.min_segment_len)
+            .map(|w| {
+                let fit = Self::fit_interval(points, w[0], w[1], config, f64::INFINITY);
+                (w[1], (w[0], fit))
+            })
+            .collect()
+    }
+
+    /// 粗到细的近似拟合：降采样后按换算的段长约束求解，断点映射回原始下标，
+    /// 再在每个断点的窗口内逐层 DP 选出误差和最小的位置；个别仍超出误差的段单独精确拆分
+    fn optimize_multiresolution(
+        points: &[Point2D],
+        config: &FitConfig,
+        multires: Multiresolution,
+        divergence: &mut Option<ParityReport>,
+    ) -> FitResult {
+        let n = points.len();
+        let factor = multires.factor;
+        let required = Self::required_indices(config, n);
+        let forbidden = |i: usize| config.forbidden_breakpoints.get(i).copied().unwrap_or(false);
+
+        // 必经点和终点一定保留在降采样的点集中
+        let mut coarse_indices: Vec<usize> = (0..n).step_by(factor).collect();
+        coarse_indices.extend(required.iter().copied().chain([n - 1]));
+        coarse_indices.sort_unstable();
+        coarse_indices.dedup();
+
+        // 粗点集中 c 个点大约对应原始的 (c - 1)·factor + 1 个点
+        let min_segment_len = config.min_segment_len.saturating_sub(1).div_ceil(factor) + 1;
+        let coarse_config = FitConfig {
+            min_segment_len,
+            max_segment_len: (config.max_segment_len.saturating_sub(1) / factor + 1).max(min_segment_len),
+            required_breakpoints: required
+                .iter()
+                .filter_map(|r| coarse_indices.binary_search(r).ok())
+                .collect(),
+            forbidden_breakpoints: if config.forbidden_breakpoints.is_empty() {
+                Vec::new()
             } else {
-                i
-            };
-            for j in start..=end {
-                intervals.push((j, i));
-            }
+                coarse_indices.iter().map(|&i| forbidden(i)).collect()
+            },
+            timestamps: if config.timestamps.len() == n {
+                coarse_indices.iter().map(|&i| config.timestamps[i]).collect()
+            } else {
+                Vec::new()
+            },
+            multiresolution: None,
+            max_segment_lens: Vec::new(),
+            ..config.clone()
+        };
+        let coarse_points: Vec<Point2D> = coarse_indices.iter().map(|&i| points[i]).collect();
+        let coarse = Self::optimize_checked(&coarse_points, &coarse_config, divergence);
+        let mut degraded = coarse.status == FitStatus::Degraded;
+        let anchors: Vec<usize> = coarse.breakpoints.iter().map(|&b| coarse_indices[b]).collect();
+        if anchors.len() < 2 {
+            return Self::single_segment_result(points, config);
         }
 
-        // 骞惰璁＄畻
-        let results: Vec<_> = intervals
-            .par_iter()
-            .map(|&(start, end)| {
-                let segment = &points[start..=end];
-                let fit = BezierFitter::fit_segment_with_limit(segment, config.max_error);
-                ((start, end), fit)
+        // 首末点和必经点固定，其余断点在窗口内移动
+        let last = anchors.len() - 1;
+        let candidates: Vec<Vec<usize>> = anchors
+            .iter()
+            .enumerate()
+            .map(|(m, &b)| {
+                if m == 0 || m == last || required.binary_search(&b).is_ok() {
+                    return vec![b];
+                }
+                let lo = b.saturating_sub(multires.window).max(1);
+                let hi = (b + multires.window).min(n - 2);
+                (lo..=hi).filter(|&c| c == b || !forbidden(c)).collect()
             })
             .collect();
+        let lengths = config.min_segment_len..=config.max_segment_len;
+
+        let refine = |limit: f64| -> Option<(Vec<usize>, Vec<FitError>)> {
+            // 每层记录 (累计误差, 上一层的候选序号, 该段拟合结果)
+            let mut layers: Vec<Vec<(f64, usize, Option<FitError>)>> = vec![vec![(0.0, 0, None)]];
+            for m in 1..candidates.len() {
+                let (prev, starts) = (&layers[m - 1], &candidates[m - 1]);
+                let layer = candidates[m]
+                    .par_iter()
+                    .map(|&end| {
+                        let mut best = (f64::INFINITY, 0, None);
+                        for (p, &start) in starts.iter().enumerate() {
+                            if prev[p].0.is_infinite() || start >= end || !lengths.contains(&(end - start + 1)) {
+                                continue;
+                            }
+                            let fit = Self::fit_interval(points, start, end, config, limit);
+                            let cost = prev[p].0 + fit.error;
+                            if fit.error <= limit && cost < best.0 {
+                                best = (cost, p, Some(fit));
+                            }
+                        }
+                        best
+                    })
+                    .collect();
+                layers.push(layer);
+            }
+
+            let mut p = 0;
+            let mut breakpoints = vec![n - 1];
+            let mut fits = Vec::with_capacity(last);
+            for m in (1..=last).rev() {
+                let (_, parent, fit) = layers[m][p].clone();
+                fits.push(fit?);
+                p = parent;
+                breakpoints.push(candidates[m - 1][p]);
+            }
+            breakpoints.reverse();
+            fits.reverse();
+            Some((breakpoints, fits))
+        };
+
+        // 窗口内无法满足误差要求时放宽误差，仍不行时直接使用粗解的断点
+        let (coarse_breakpoints, coarse_fits) = refine(config.max_error)
+            .or_else(|| refine(f64::INFINITY))
+            .unwrap_or_else(|| {
+                let fits = anchors
+                    .windows(2)
+                    .map(|w| Self::fit_interval(points, w[0], w[1], config, f64::INFINITY))
+                    .collect();
+                (anchors, fits)
+            });
 
-        results.into_iter().collect()
+        // 仍超出误差的段不长于 max_segment_len，在原始点上精确求解后替换
+        let mut breakpoints = vec![0];
+        let mut fits = Vec::with_capacity(coarse_fits.len());
+        for (w, fit) in coarse_breakpoints.windows(2).zip(coarse_fits) {
+            let (start, end) = (w[0], w[1]);
+            if fit.error > config.max_error {
+                let sub_config = FitConfig {
+                    required_breakpoints: Vec::new(),
+                    forbidden_breakpoints: config
+                        .forbidden_breakpoints
+                        .get(start..=end)
+                        .map(<[bool]>::to_vec)
+                        .unwrap_or_default(),
+                    timestamps: if config.timestamps.len() == n {
+                        config.timestamps[start..=end].to_vec()
+                    } else {
+                        Vec::new()
+                    },
+                    multiresolution: None,
+                    max_segment_lens: config
+                        .max_segment_lens
+                        .get(start..=end)
+                        .map(<[usize]>::to_vec)
+                        .unwrap_or_default(),
+                    ..config.clone()
+                };
+                let sub = Self::optimize_checked(&points[start..=end], &sub_config, divergence);
+                degraded |= sub.status == FitStatus::Degraded;
+                if sub.status.is_satisfied() {
+                    for piece in sub.breakpoints.windows(2) {
+                        let (s, e) = (start + piece[0], start + piece[1]);
+                        fits.push(Self::fit_interval(points, s, e, config, f64::INFINITY));
+                        breakpoints.push(e);
+                    }
+                    continue;
+                }
+            }
+            fits.push(fit);
+            breakpoints.push(end);
+        }
+
+        let mut result = FitResult {
+            curves: fits.iter().map(|fit| fit.bezier).collect(),
+            total_error: fits.iter().map(|fit| fit.error).sum(),
+            num_segments: fits.len(),
+            config: config.clone(),
+            breakpoints,
+            segments: fits.iter().map(|fit| Self::output_segment(fit, config)).collect(),
+            segment_errors: fits.iter().map(|fit| fit.error).collect(),
+            status: FitStatus::Optimal,
+            blocking_intervals: vec![],
+            segment_times: vec![],
+            segment_info: vec![],
+        };
+        if fits.iter().any(|fit| fit.error > config.max_error) {
+            Self::mark_relaxed(points, config, &mut result);
+        } else if degraded {
+            result.status = FitStatus::Degraded;
+        }
+        result
+    }
+
+    /// 从左到右尝试把每段与下一段合并，成功后继续尝试与再下一段合并
+    fn merge_adjacent(points: &[Point2D], config: &FitConfig, mut breakpoints: Vec<usize>) -> FitResult {
+        let required = Self::required_indices(config, points.len());
+        let mut fits: Vec<FitError> = breakpoints
+            .windows(2)
+            .map(|w| Self::fit_interval(points, w[0], w[1], config, f64::INFINITY))
+            .collect();
+
+        let mut k = 0;
+        while k + 1 < fits.len() {
+            let (start, middle, end) = (breakpoints[k], breakpoints[k + 1], breakpoints[k + 2]);
+            if required.binary_search(&middle).is_err() {
+                let limit = config.max_error_for(start, end);
+                let fit = Self::fit_interval(points, start, end, config, limit);
+                if fit.error <= limit {
+                    fits[k] = fit;
+                    fits.remove(k + 1);
+                    breakpoints.remove(k + 1);
+                    continue;
+                }
+            }
+            k += 1;
+        }
+
+        FitResult {
+            curves: fits.iter().map(|fit| fit.bezier).collect(),
+            total_error: fits.iter().map(|fit| fit.error).sum(),
+            num_segments: fits.len(),
+            config: config.clone(),
+            breakpoints,
+            segments: fits.iter().map(|fit| Self::output_segment(fit, config)).collect(),
+            segment_errors: fits.iter().map(|fit| fit.error).collect(),
+            status: FitStatus::Optimal,
+            blocking_intervals: vec![],
+            segment_times: vec![],
+            segment_info: vec![],
+        }
+    }
+
+    /// 回退求得的结果按原配置重新核对，记录违反约束的分段
+    fn mark_relaxed(points: &[Point2D], config: &FitConfig, result: &mut FitResult) {
+        let mut achieved_error = 0.0f64;
+        let mut blocking = Vec::new();
+        for w in result.breakpoints.windows(2) {
+            let (start, end) = (w[0], w[1]);
+            let error = Self::fit_interval(points, start, end, config, f64::INFINITY).error;
+            achieved_error = achieved_error.max(error);
+            let len = end - start + 1;
+            let bad_len = len < config.min_segment_len || len > config.max_segment_len;
+            if error > config.max_error_for(start, end) || (!config.max_error.is_finite() && bad_len) {
+                blocking.push((start, end));
+            }
+        }
+        result.status = if config.max_error.is_finite() {
+            FitStatus::Relaxed { achieved_error }
+        } else {
+            FitStatus::Infeasible
+        };
+        result.blocking_intervals = blocking;
+    }
+
+    /// 所有点拟合为一段，用于无法满足段长约束时的兜底
+    fn single_segment_result(points: &[Point2D], config: &FitConfig) -> FitResult {
+        let n = points.len();
+        let fit = Self::fit_interval(points, 0, n - 1, config, f64::INFINITY);
+        FitResult {
+            curves: vec![fit.bezier],
+            total_error: fit.error,
+            num_segments: 1,
+            config: config.clone(),
+            breakpoints: vec![0, n - 1],
+            segments: vec![Self::output_segment(&fit, config)],
+            segment_errors: vec![fit.error],
+            status: FitStatus::Infeasible,
+            blocking_intervals: vec![(0, n - 1)],
+            segment_times: vec![],
+            segment_info: vec![],
+        }
     }
 
     /// 鍥炴函鏋勫缓鏇茬嚎搴忓垪
-    fn reconstruct_curves(
+    fn reconstruct_curves<'a>(
         mut end: usize,
         parent: &[usize],
-        cache: &HashMap<(usize, usize), FitError>,
-    ) -> Vec<QuadraticBezier> {
+        lookup: impl Fn(usize, usize) -> Option<&'a FitError>,
+    ) -> (Vec<FitError>, Vec<usize>) {
         let mut segments = Vec::new();
+        let mut breakpoints = vec![end];
         
         while end > 0 {
             let start = parent[end];
-            if let Some(fit) = cache.get(&(start, end)) {
-                segments.push(fit.bezier);
+            breakpoints.push(start);
+            if let Some(fit) = lookup(start, end) {
+                segments.push(fit.clone());
             } else {
                 // 鐞嗚涓婁笉搴旇鍙戠敓锛屼絾涓轰簡鍋ュ．鎬?
                 eprintln!("Warning: segment ({}, {}) not found in cache", start, end);
@@ -219,20 +1498,450 @@ impl DPOptimizer {
         }
 
         segments.reverse();
-        segments
+        breakpoints.reverse();
+        (segments, breakpoints)
     }
 }
 
 /// 渚挎嵎鍑芥暟
-pub fn fit_curve(points: &[Point2D], config: &FitConfig) -> FitResult {
-    DPOptimizer::optimize(points, config)
+/// 输入可以是 `Point2D`、`[f64; 2]`，启用对应 feature 后也可以是 nalgebra / glam 的点
+pub fn fit_curve<P: IntoPoints + ?Sized>(points: &P, config: &FitConfig) -> FitResult {
+    DPOptimizer::optimize(&points.to_points(), config)
+}
+
+/// 按 `max_gap` 切分笔画后逐段拟合，每个笔画一个结果。
+/// 使用 CUDA 时所有笔画的误差缓存打包在同一批内核启动中计算
+pub fn fit_multistroke<P: IntoPoints + ?Sized>(
+    points: &P,
+    max_gap: f64,
+    config: &FitConfig,
+) -> Vec<FitResult> {
+    let strokes = split_on_gaps(&points.to_points(), max_gap);
+    fit_strokes(&strokes, config, DPOptimizer::optimize)
+}
+
+/// 已切分好的多条笔画逐条校验后拟合，校验规则与 `try_fit_curve` 相同，每条笔画一个结果；
+/// 误差缓存与 `fit_multistroke` 一样批量计算
+pub fn try_fit_strokes(
+    strokes: &[Vec<Point2D>],
+    config: &FitConfig,
+) -> Result<Vec<FitResult>, FitCurveError> {
+    for stroke in strokes {
+        check_input(stroke, config)?;
+    }
+    let mut divergence = None;
+    let results = fit_strokes(strokes, config, |stroke, config| {
+        DPOptimizer::optimize_checked(stroke, config, &mut divergence)
+    });
+    match divergence {
+        Some(report) => Err(FitCurveError::CacheDivergence { report }),
+        None => Ok(results),
+    }
+}
+
+/// 能批量计算误差缓存时共用一次内核启动，否则逐条调用 `fit`
+fn fit_strokes(
+    strokes: &[Vec<Point2D>],
+    config: &FitConfig,
+    mut fit: impl FnMut(&[Point2D], &FitConfig) -> FitResult,
+) -> Vec<FitResult> {
+    if let Ok(mut caches) = try_compute_error_cache_batch(strokes, config) {
+        return strokes
+            .iter()
+            .zip(&mut caches)
+            .map(|(stroke, cache)| DPOptimizer::optimize_with_cache(stroke, config, cache))
+            .collect();
+    }
+    strokes.iter().map(|stroke| fit(stroke, config)).collect()
+}
+
+/// DP 表：到达每个下标的段数、累计误差和上一个断点，不可达时段数为 `usize::MAX`
+struct DpTable {
+    seg: Vec<usize>,
+    err: Vec<f64>,
+    parent: Vec<usize>,
+}
+
+impl DpTable {
+    fn new(n: usize) -> Self {
+        let mut table = Self {
+            seg: vec![usize::MAX; n],
+            err: vec![f64::INFINITY; n],
+            parent: vec![0; n],
+        };
+        table.seg[0] = 0;
+        table.err[0] = 0.0;
+        table
+    }
+
+    /// 从 j 出发、单段误差为 error 的候选，j 不可达时为 None
+    fn candidate(&self, j: usize, error: f64) -> Option<DpEntry> {
+        (self.seg[j] != usize::MAX).then(|| DpEntry {
+            seg: self.seg[j] + 1,
+            err: self.err[j] + error,
+            parent: j,
+        })
+    }
+
+    /// 候选优于 i 的当前值时写入，返回是否被采用
+    fn update(&mut self, objective: Objective, i: usize, cand: DpEntry) -> bool {
+        let current = DpEntry {
+            seg: self.seg[i],
+            err: self.err[i],
+            parent: self.parent[i],
+        };
+        let better = current.seg == usize::MAX || cand.precedes(&current, objective);
+        if better {
+            self.seg[i] = cand.seg;
+            self.err[i] = cand.err;
+            self.parent[i] = cand.parent;
+        }
+        better
+    }
+
+    /// 用从 j 出发、单段误差为 error 的候选更新 i，返回是否被采用
+    fn relax(&mut self, objective: Objective, i: usize, j: usize, error: f64) -> bool {
+        self.candidate(j, error)
+            .is_some_and(|cand| self.update(objective, i, cand))
+    }
+}
+
+/// DP 表中一个下标的取值
+#[derive(Clone, Copy)]
+struct DpEntry {
+    seg: usize,
+    err: f64,
+    parent: usize,
+}
+
+impl DpEntry {
+    /// 是否严格优于 other；代价完全相同时取较小的上一个断点，因此结果与候选的枚举和归约顺序无关
+    fn precedes(&self, other: &Self, objective: Objective) -> bool {
+        match objective {
+            Objective::MinSegments => {
+                (self.seg, self.err) < (other.seg, other.err)
+                    || ((self.seg, self.err) == (other.seg, other.err) && self.parent < other.parent)
+            }
+            Objective::Penalized { lambda } => {
+                let cost = self.err + lambda * (self.seg as f64);
+                let other_cost = other.err + lambda * (other.seg as f64);
+                cost < other_cost || (cost == other_cost && self.parent < other.parent)
+            }
+        }
+    }
+}
+
+/// 分块拟合任意长的输入：每次拟合 `chunk` 个点的窗口，只保留终点不晚于窗口末尾前 `overlap`
+/// 个点的分段，下一个窗口从最后保留的断点开始，重叠部分随之重新优化。
+/// 内存为 O(chunk·max_segment_len)；`chunk` 至少取 `overlap + max_segment_len + 1`。
+/// 分块边界附近的结果接近但不保证全局最优
+pub fn fit_curve_chunked<P: IntoPoints + ?Sized>(
+    points: &P,
+    config: &FitConfig,
+    chunk: usize,
+    overlap: usize,
+) -> FitResult {
+    let points = points.to_points();
+    let n = points.len();
+    let chunk = chunk.max(overlap + config.max_segment_len + 1);
+    if n <= chunk {
+        return DPOptimizer::optimize(&points, config);
+    }
+
+    let mut result = FitResult {
+        curves: vec![],
+        total_error: 0.0,
+        num_segments: 0,
+        config: config.clone(),
+        breakpoints: vec![0],
+        segments: vec![],
+        segment_errors: vec![],
+        status: FitStatus::Optimal,
+        blocking_intervals: vec![],
+        segment_times: vec![],
+        segment_info: vec![],
+    };
+    let mut start = 0;
+    while start < n - 1 {
+        let end = (start + chunk).min(n) - 1;
+        let window_config = FitConfig {
+            required_breakpoints: config
+                .required_breakpoints
+                .iter()
+                .filter(|&&r| (start..=end).contains(&r))
+                .map(|&r| r - start)
+                .collect(),
+            forbidden_breakpoints: config
+                .forbidden_breakpoints
+                .iter()
+                .skip(start)
+                .take(end - start + 1)
+                .copied()
+                .collect(),
+            timestamps: if config.timestamps.len() == n {
+                config.timestamps[start..=end].to_vec()
+            } else {
+                Vec::new()
+            },
+            error_overrides: config
+                .error_overrides
+                .iter()
+                .filter(|(range, _)| range.start <= end && start < range.end)
+                .map(|(range, limit)| (range.start.max(start) - start..range.end.min(end + 1) - start, *limit))
+                .collect(),
+            max_segment_lens: config
+                .max_segment_lens
+                .iter()
+                .skip(start)
+                .take(end - start + 1)
+                .copied()
+                .collect(),
+            ..config.clone()
+        };
+        let window = DPOptimizer::optimize(&points[start..=end], &window_config);
+
+        // 最后一个窗口全部保留，否则至少保留一段以保证前进
+        let keep = if end == n - 1 {
+            window.num_segments
+        } else {
+            let cutoff = end - start - overlap;
+            window.breakpoints[1..]
+                .iter()
+                .take_while(|&&b| b <= cutoff)
+                .count()
+                .max(1)
+        };
+
+        result.curves.extend_from_slice(&window.curves[..keep]);
+        result.segments.extend_from_slice(&window.segments[..keep]);
+        result.segment_errors.extend_from_slice(&window.segment_errors[..keep]);
+        result.breakpoints.extend(window.breakpoints[1..=keep].iter().map(|&b| b + start));
+        if let Some(times) = window.segment_times.get(..keep) {
+            result.segment_times.extend_from_slice(times);
+        }
+        result.segment_info.extend(window.segment_info.iter().take(keep).map(|info| SegmentInfo {
+            start_index: info.start_index + start,
+            end_index: info.end_index + start,
+            ..*info
+        }));
+        let kept_end = window.breakpoints[keep];
+        result.blocking_intervals.extend(
+            window
+                .blocking_intervals
+                .iter()
+                .filter(|&&(_, e)| e <= kept_end)
+                .map(|&(s, e)| (s + start, e + start)),
+        );
+        result.status = match (result.status, window.status) {
+            (FitStatus::Infeasible, _) | (_, FitStatus::Infeasible) => FitStatus::Infeasible,
+            (FitStatus::Relaxed { achieved_error: a }, FitStatus::Relaxed { achieved_error: b }) => {
+                FitStatus::Relaxed { achieved_error: a.max(b) }
+            }
+            (FitStatus::Relaxed { achieved_error }, _) | (_, FitStatus::Relaxed { achieved_error }) => {
+                FitStatus::Relaxed { achieved_error }
+            }
+            (FitStatus::Degraded, _) | (_, FitStatus::Degraded) => FitStatus::Degraded,
+            _ => FitStatus::Optimal,
+        };
+        start += kept_end;
+    }
+
+    result.num_segments = result.curves.len();
+    result.total_error = result.segment_errors.iter().sum();
+    result
+}
+
+/// 先校验输入再拟合；含 NaN / 无穷大坐标、时间戳个数与点数不符、函数图像模式下 x 不严格递增时
+/// 返回错误而不是无意义的结果，
+/// `Backend::CudaVerified` 抽查未通过时返回 `FitCurveError::CacheDivergence`
+pub fn try_fit_curve<P: IntoPoints + ?Sized>(
+    points: &P,
+    config: &FitConfig,
+) -> Result<FitResult, FitCurveError> {
+    let points = points.to_points();
+    check_input(&points, config)?;
+    let mut divergence = None;
+    let result = DPOptimizer::optimize_checked(&points, config, &mut divergence);
+    match divergence {
+        Some(report) => Err(FitCurveError::CacheDivergence { report }),
+        None => Ok(result),
+    }
+}
+
+fn check_input(points: &[Point2D], config: &FitConfig) -> Result<(), FitCurveError> {
+    validate_points(points)?;
+    if config.function_graph {
+        if let Some(k) = points.windows(2).position(|w| w[1].x <= w[0].x) {
+            return Err(FitCurveError::NonIncreasingX { index: k + 1 });
+        }
+    }
+    if !config.timestamps.is_empty() && config.timestamps.len() != points.len() {
+        return Err(FitCurveError::TimestampCount {
+            expected: points.len(),
+            found: config.timestamps.len(),
+        });
+    }
+    Ok(())
+}
+
+/// 单段误差的廉价下界：曲线按参数均匀切成 `BOUND_PIECES` 段折线，折线与曲线的偏差不超过
+/// |p0 - 2p1 + p2|·h²/4，因此点到折线的距离减去该偏差不大于点到曲线的距离。
+/// 均方误差累计超过 limit 后提前返回
+fn error_lower_bound(bezier: &QuadraticBezier, segment: &[Point2D], metric: ErrorMetric, limit: f64) -> f64 {
+    const BOUND_PIECES: usize = 8;
+    let ax = bezier.p0.x - 2.0 * bezier.p1.x + bezier.p2.x;
+    let ay = bezier.p0.y - 2.0 * bezier.p1.y + bezier.p2.y;
+    let slack = (ax * ax + ay * ay).sqrt() / (4 * BOUND_PIECES * BOUND_PIECES) as f64;
+    let polyline: Vec<Point2D> = (0..=BOUND_PIECES)
+        .map(|k| bezier.evaluate(k as f64 / BOUND_PIECES as f64))
+        .collect();
+
+    let n = segment.len() as f64;
+    let mut total = 0.0f64;
+    for p in segment {
+        let to_polyline = polyline
+            .windows(2)
+            .map(|w| Segment::Line { start: w[0], end: w[1] }.distance_to_point(p))
+            .fold(f64::INFINITY, f64::min);
+        let d = (to_polyline - slack).max(0.0);
+        match metric {
+            ErrorMetric::Mse => {
+                total += d * d;
+                if total > limit * n {
+                    break;
+                }
+            }
+            ErrorMetric::Max => total = total.max(d * d),
+        }
+    }
+    match metric {
+        ErrorMetric::Mse => total / n,
+        ErrorMetric::Max => total,
+    }
+}
+
+/// SplitMix64 混合函数，用于确定性抽样
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// GPU 内核只实现了固定端点的最小二乘贝塞尔拟合和均方误差
+fn check_gpu_kernel_support(config: &FitConfig) -> Result<(), String> {
+    if config.robust.is_some()
+        || config.metric != ErrorMetric::Mse
+        || !config.timestamps.is_empty()
+        || config.function_graph
+        || config.endpoint_policy != EndpointPolicy::Fixed
+        || config.fitter != FitterKind::LeastSquares
+        || config.primitives != PrimitiveSet::Bezier
+    {
+        return Err("only plain least squares is supported by the gpu kernels".to_string());
+    }
+    Ok(())
+}
+
+/// 按 `config.backend` 选择 GPU 后端，失败时由调用方回退到 CPU
+fn try_compute_error_cache_gpu(
+    points: &[Point2D],
+    config: &FitConfig,
+    divergence: &mut Option<ParityReport>,
+) -> Result<ErrorCache, String> {
+    check_gpu_kernel_support(config)?;
+    // 内核只有一个误差上限：按最宽的容差截断，逐区间的比较留给 DP
+    let widened;
+    let config = if config.error_overrides.is_empty() {
+        config
+    } else {
+        widened = FitConfig {
+            max_error: config.widest_max_error(),
+            ..config.clone()
+        };
+        &widened
+    };
+    match config.backend {
+        Backend::Cpu => Err("cpu backend selected".to_string()),
+        Backend::Cuda => try_compute_error_cache_cuda(points, config),
+        Backend::Wgpu => try_compute_error_cache_wgpu(points, config),
+        Backend::OpenCl => try_compute_error_cache_opencl(points, config),
+        Backend::CudaVerified => {
+            let cache = try_compute_error_cache_cuda(points, config)?;
+            let report = DPOptimizer::verify_cache(points, config, &cache, config.cuda.verify_samples);
+            if !report.passed() {
+                *divergence = Some(report);
+                return Err("cuda error cache diverges from cpu".to_string());
+            }
+            Ok(cache)
+        }
+        Backend::Auto => try_compute_error_cache_cuda(points, config)
+            .or_else(|_| try_compute_error_cache_opencl(points, config))
+            .or_else(|e| {
+                if config.precision == Precision::F32 {
+                    try_compute_error_cache_wgpu(points, config)
+                } else {
+                    Err(e)
+                }
+            }),
+    }
+}
+
+/// 多笔画批量计算误差缓存；只在笔画无需预处理、按常规缓存路径求解时使用，
+/// 否则缓存下标与预处理后的点对不上
+fn try_compute_error_cache_batch(
+    strokes: &[Vec<Point2D>],
+    config: &FitConfig,
+) -> Result<Vec<ErrorCache>, String> {
+    if config.deterministic
+        || !matches!(config.backend, Backend::Cuda | Backend::Auto)
+        || config.dedup_distance.is_some()
+        || config.presmooth.is_some()
+        || config.normalize
+        || config.multiresolution.is_some_and(|m| m.factor > 1)
+        || config.cache_mode == CacheMode::None
+        || config.cuda.device_dp
+        || !config.error_overrides.is_empty()
+    {
+        return Err("batched strokes need the plain cuda path".to_string());
+    }
+    // 所有笔画的缓存同时驻留，按总量对照内存上限；超出时逐条求解，各自按上限降级
+    let batch_bytes: usize = strokes
+        .iter()
+        .map(|s| DPOptimizer::estimate(s.len(), config).cache_bytes)
+        .sum();
+    if config.max_cache_bytes.is_some_and(|budget| batch_bytes > budget) {
+        return Err("batched caches exceed max_cache_bytes".to_string());
+    }
+    check_gpu_kernel_support(config)?;
+    let mut caches = try_compute_error_cache_cuda_batch(strokes, config)?;
+    for cache in &mut caches {
+        cache.mark_source(config, config.max_error);
+    }
+    Ok(caches)
+}
+
+#[cfg(feature = "cuda")]
+fn try_compute_error_cache_cuda_batch(
+    strokes: &[Vec<Point2D>],
+    config: &FitConfig,
+) -> Result<Vec<ErrorCache>, String> {
+    super::cuda::compute_error_cache_cuda_batch(strokes, config)
+}
+
+#[cfg(not(feature = "cuda"))]
+fn try_compute_error_cache_cuda_batch(
+    _strokes: &[Vec<Point2D>],
+    _config: &FitConfig,
+) -> Result<Vec<ErrorCache>, String> {
+    Err("cuda feature disabled".to_string())
 }
 
 #[cfg(feature = "cuda")]
 fn try_compute_error_cache_cuda(
     points: &[Point2D],
     config: &FitConfig,
-) -> Result<HashMap<(usize, usize), FitError>, String> {
+) -> Result<ErrorCache, String> {
     super::cuda::compute_error_cache_cuda(points, config)
 }
 
@@ -240,7 +1949,48 @@ fn try_compute_error_cache_cuda(
 fn try_compute_error_cache_cuda(
     _points: &[Point2D],
     _config: &FitConfig,
-) -> Result<HashMap<(usize, usize), FitError>, String> {
+) -> Result<ErrorCache, String> {
     Err("cuda feature disabled".to_string())
 }
 
+#[cfg(feature = "cuda")]
+fn try_solve_dp_cuda(points: &[Point2D], config: &FitConfig) -> Result<Vec<usize>, String> {
+    super::cuda::solve_dp_cuda(points, config)
+}
+
+#[cfg(not(feature = "cuda"))]
+fn try_solve_dp_cuda(_points: &[Point2D], _config: &FitConfig) -> Result<Vec<usize>, String> {
+    Err("cuda feature disabled".to_string())
+}
+
+#[cfg(feature = "opencl")]
+fn try_compute_error_cache_opencl(
+    points: &[Point2D],
+    config: &FitConfig,
+) -> Result<ErrorCache, String> {
+    super::opencl::compute_error_cache_opencl(points, config)
+}
+
+#[cfg(not(feature = "opencl"))]
+fn try_compute_error_cache_opencl(
+    _points: &[Point2D],
+    _config: &FitConfig,
+) -> Result<ErrorCache, String> {
+    Err("opencl feature disabled".to_string())
+}
+
+#[cfg(feature = "gpu-wgpu")]
+fn try_compute_error_cache_wgpu(
+    points: &[Point2D],
+    config: &FitConfig,
+) -> Result<ErrorCache, String> {
+    super::wgpu_backend::compute_error_cache_wgpu(points, config)
+}
+
+#[cfg(not(feature = "gpu-wgpu"))]
+fn try_compute_error_cache_wgpu(
+    _points: &[Point2D],
+    _config: &FitConfig,
+) -> Result<ErrorCache, String> {
+    Err("gpu-wgpu feature disabled".to_string())
+}
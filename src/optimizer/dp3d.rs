@@ -0,0 +1,151 @@
+use crate::fitting::BezierFitter;
+use crate::geometry::{Point3D, QuadraticBezier3D};
+use crate::par::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::config::{FitConfig, Objective};
+use super::dp::FitStatus;
+use super::intervals::{candidate_starts, IntervalIter};
+
+/// 三维拟合结果，字段含义与 `FitResult` 相同
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FitResult3D {
+    pub curves: Vec<QuadraticBezier3D>,
+    pub total_error: f64,
+    pub num_segments: usize,
+    pub breakpoints: Vec<usize>,
+    pub segment_errors: Vec<f64>,
+    pub status: FitStatus,
+}
+
+impl FitResult3D {
+    /// 每段采样若干点
+    pub fn sample_points(&self, points_per_segment: usize) -> Vec<Point3D> {
+        self.curves
+            .iter()
+            .flat_map(|c| c.sample(points_per_segment))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// 三维折线拟合。使用 `min_segment_len`、`max_segment_len`、`max_error` 和 `objective`，
+/// 其余选项（预处理、鲁棒拟合、图元、GPU 后端等）只对二维生效
+pub fn fit_curve_3d(points: &[Point3D], config: &FitConfig) -> FitResult3D {
+    let n = points.len();
+    if n == 0 {
+        return FitResult3D {
+            curves: vec![],
+            total_error: 0.0,
+            num_segments: 0,
+            breakpoints: vec![],
+            segment_errors: vec![],
+            status: FitStatus::Optimal,
+        };
+    }
+    if n <= config.min_segment_len {
+        return single_segment(points, FitStatus::Optimal);
+    }
+
+    let intervals: Vec<(usize, usize)> = IntervalIter::new(n, config).collect();
+    let cache: HashMap<(usize, usize), (QuadraticBezier3D, f64)> = intervals
+        .par_iter()
+        .map(|&(j, i)| ((j, i), BezierFitter::fit_segment_3d(&points[j..=i])))
+        .collect();
+
+    match solve(n, config, &cache, config.max_error) {
+        Some(result) => result,
+        None if config.max_error.is_finite() => match solve(n, config, &cache, f64::INFINITY) {
+            Some(mut result) => {
+                let achieved_error = result.segment_errors.iter().copied().fold(0.0, f64::max);
+                result.status = FitStatus::Relaxed { achieved_error };
+                result
+            }
+            None => single_segment(points, FitStatus::Infeasible),
+        },
+        None => single_segment(points, FitStatus::Infeasible),
+    }
+}
+
+fn single_segment(points: &[Point3D], status: FitStatus) -> FitResult3D {
+    let (bezier, error) = BezierFitter::fit_segment_3d(points);
+    FitResult3D {
+        curves: vec![bezier],
+        total_error: error,
+        num_segments: 1,
+        breakpoints: vec![0, points.len() - 1],
+        segment_errors: vec![error],
+        status,
+    }
+}
+
+/// 与二维 DP 相同的递推，`max_error` 有限时按 `FitConfig::max_error_for` 逐段取上限；
+/// 没有满足上限的路径时返回 None
+fn solve(
+    n: usize,
+    config: &FitConfig,
+    cache: &HashMap<(usize, usize), (QuadraticBezier3D, f64)>,
+    max_error: f64,
+) -> Option<FitResult3D> {
+    let mut seg_dp = vec![usize::MAX; n];
+    let mut err_dp = vec![f64::INFINITY; n];
+    let mut parent = vec![0; n];
+    seg_dp[0] = 0;
+    err_dp[0] = 0.0;
+
+    for i in 1..n {
+        for j in candidate_starts(i, config) {
+            let Some(&(_, error)) = cache.get(&(j, i)) else {
+                continue;
+            };
+            let limit = if max_error.is_finite() { config.max_error_for(j, i) } else { max_error };
+            if error > limit || seg_dp[j] == usize::MAX {
+                continue;
+            }
+            let cand_seg = seg_dp[j] + 1;
+            let cand_err = err_dp[j] + error;
+            let better = match config.objective {
+                Objective::MinSegments => {
+                    cand_seg < seg_dp[i] || (cand_seg == seg_dp[i] && cand_err < err_dp[i])
+                }
+                Objective::Penalized { lambda } => {
+                    seg_dp[i] == usize::MAX
+                        || cand_err + lambda * (cand_seg as f64)
+                            < err_dp[i] + lambda * (seg_dp[i] as f64)
+                }
+            };
+            if better {
+                seg_dp[i] = cand_seg;
+                err_dp[i] = cand_err;
+                parent[i] = j;
+            }
+        }
+    }
+
+    if seg_dp[n - 1] == usize::MAX {
+        return None;
+    }
+
+    let mut breakpoints = vec![n - 1];
+    let mut end = n - 1;
+    while end > 0 {
+        end = parent[end];
+        breakpoints.push(end);
+    }
+    breakpoints.reverse();
+
+    let (curves, segment_errors): (Vec<_>, Vec<_>) =
+        breakpoints.windows(2).map(|w| cache[&(w[0], w[1])]).unzip();
+    Some(FitResult3D {
+        total_error: err_dp[n - 1],
+        num_segments: curves.len(),
+        curves,
+        breakpoints,
+        segment_errors,
+        status: FitStatus::Optimal,
+    })
+}
@@ -0,0 +1,61 @@
+use std::fmt;
+
+use crate::geometry::Point2D;
+
+use super::dp::ParityReport;
+
+/// `try_fit_curve` 的错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum FitCurveError {
+    /// 输入中含有 NaN 或无穷大坐标
+    NonFinitePoint { index: usize, point: Point2D },
+    /// `FitConfig::function_graph` 要求 x 严格递增，`index` 处的 x 不大于前一个点
+    NonIncreasingX { index: usize },
+    /// `FitConfig::timestamps` 非空，但个数与输入点数不同
+    TimestampCount { expected: usize, found: usize },
+    /// `Backend::CudaVerified` 抽查的 CUDA 误差缓存与 CPU 重算的偏差超出容差
+    CacheDivergence { report: ParityReport },
+}
+
+impl fmt::Display for FitCurveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FitCurveError::NonFinitePoint { index, point } => write!(
+                f,
+                "point {} has non-finite coordinates ({}, {})",
+                index, point.x, point.y
+            ),
+            FitCurveError::NonIncreasingX { index } => write!(
+                f,
+                "function_graph needs strictly increasing x, point {} is not",
+                index
+            ),
+            FitCurveError::TimestampCount { expected, found } => write!(
+                f,
+                "expected {} timestamps, one per point, got {}",
+                expected, found
+            ),
+            FitCurveError::CacheDivergence { report } => write!(
+                f,
+                "cuda error cache diverges from cpu: {} exceeds tolerance {}",
+                report.max_divergence, report.tolerance
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FitCurveError {}
+
+/// 配置参数无效，由 `FitConfig::validate` 和 `FitConfigBuilder::build` 返回
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub reason: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid config: {}", self.reason)
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,84 @@
+use crate::geometry::{IntoPoints, Point2D, QuadraticBezier, Segment};
+
+use super::config::FitConfig;
+use super::dp::{DPOptimizer, FitResult, FitStatus};
+
+/// 不做优化的 Catmull–Rom 插值：曲线精确经过每个输入点，相邻两点之间一段。
+/// `alpha` 为参数化指数，0 为均匀、0.5 为向心（不会自交或打结）、1 为弦长，超出 `[0, 1]` 时截断。
+/// 每段三次曲线按中点一致降为二次曲线，因此分段处只近似 G1 连续；
+/// 首末两段用端点的镜像点补齐邻点。结果的误差均为 0，`config` 为默认配置
+pub fn interpolate_catmull_rom<P: IntoPoints + ?Sized>(points: &P, alpha: f64) -> FitResult {
+    let points = points.to_points();
+    let alpha = if alpha.is_nan() {
+        0.5
+    } else {
+        alpha.clamp(0.0, 1.0)
+    };
+    let n = points.len();
+
+    let mut curves = Vec::with_capacity(n.saturating_sub(1));
+    for i in 0..n.saturating_sub(1) {
+        let (p1, p2) = (points[i], points[i + 1]);
+        let p0 = if i > 0 { points[i - 1] } else { mirror(p2, p1) };
+        let p3 = if i + 2 < n {
+            points[i + 2]
+        } else {
+            mirror(p1, p2)
+        };
+        curves.push(catmull_rom_span(p0, p1, p2, p3, alpha));
+    }
+
+    let breakpoints = if n < 2 { vec![] } else { (0..n).collect() };
+    let mut result = FitResult {
+        segments: curves.iter().map(|c| Segment::from_quad(c, None)).collect(),
+        segment_errors: vec![0.0; curves.len()],
+        num_segments: curves.len(),
+        curves,
+        total_error: 0.0,
+        config: FitConfig::default(),
+        breakpoints,
+        status: FitStatus::Optimal,
+        blocking_intervals: vec![],
+        segment_times: vec![],
+        segment_info: vec![],
+    };
+    result.segment_info = DPOptimizer::segment_info(&points, &result);
+    result
+}
+
+/// `p` 关于 `center` 的对称点
+fn mirror(p: Point2D, center: Point2D) -> Point2D {
+    Point2D::new(2.0 * center.x - p.x, 2.0 * center.y - p.y)
+}
+
+/// p1 到 p2 之间的 Catmull–Rom 段（Barry–Goldman 形式的切线），再降为二次曲线
+fn catmull_rom_span(
+    p0: Point2D,
+    p1: Point2D,
+    p2: Point2D,
+    p3: Point2D,
+    alpha: f64,
+) -> QuadraticBezier {
+    // 重合点的参数间隔为 0，对应项按 0 处理
+    let knot = |a: Point2D, b: Point2D| a.distance_to(&b).powf(alpha).max(f64::EPSILON);
+    let (t01, t12, t23) = (knot(p0, p1), knot(p1, p2), knot(p2, p3));
+
+    // m = (p2 - p1) + t12 * ((b - a) / t_ab - (d - c) / t_cd)
+    let tangent = |a: Point2D, b: Point2D, t_ab: f64, c: Point2D, d: Point2D, t_cd: f64| {
+        Point2D::new(
+            p2.x - p1.x + t12 * ((b.x - a.x) / t_ab - (d.x - c.x) / t_cd),
+            p2.y - p1.y + t12 * ((b.y - a.y) / t_ab - (d.y - c.y) / t_cd),
+        )
+    };
+    let m1 = tangent(p0, p1, t01, p0, p2, t01 + t12);
+    let m2 = tangent(p2, p3, t23, p1, p3, t12 + t23);
+
+    let c1 = Point2D::new(p1.x + m1.x / 3.0, p1.y + m1.y / 3.0);
+    let c2 = Point2D::new(p2.x - m2.x / 3.0, p2.y - m2.y / 3.0);
+    // 二次曲线与三次曲线在 t = 0.5 处重合
+    let control = Point2D::new(
+        (3.0 * (c1.x + c2.x) - p1.x - p2.x) / 4.0,
+        (3.0 * (c1.y + c2.y) - p1.y - p2.y) / 4.0,
+    );
+    QuadraticBezier::new(p1, control, p2)
+}
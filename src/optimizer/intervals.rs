@@ -0,0 +1,54 @@
+use std::ops::RangeInclusive;
+
+use super::config::FitConfig;
+
+/// 按终点、起点升序惰性枚举满足段长约束的候选区间 `(起点, 终点)`，两端均包含。
+/// CPU 缓存、GPU 后端和 DP 共用这一份枚举，保证候选集合一致
+#[derive(Debug, Clone)]
+pub(crate) struct IntervalIter<'a> {
+    config: &'a FitConfig,
+    n: usize,
+    end: usize,
+    starts: RangeInclusive<usize>,
+}
+
+impl<'a> IntervalIter<'a> {
+    pub(crate) fn new(n: usize, config: &'a FitConfig) -> Self {
+        let end = config.min_segment_len;
+        let starts = if end < n {
+            candidate_starts(end, config)
+        } else {
+            RangeInclusive::new(1, 0)
+        };
+        Self {
+            config,
+            n,
+            end,
+            starts,
+        }
+    }
+}
+
+impl Iterator for IntervalIter<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(start) = self.starts.next() {
+                return Some((start, self.end));
+            }
+            if self.end + 1 >= self.n {
+                return None;
+            }
+            self.end += 1;
+            self.starts = candidate_starts(self.end, self.config);
+        }
+    }
+}
+
+/// 满足段长约束、以 i 结尾的分段的起点范围，可能为空
+pub(crate) fn candidate_starts(i: usize, config: &FitConfig) -> RangeInclusive<usize> {
+    let start = i.saturating_sub(config.max_segment_len_at(i).max(1) - 1);
+    let end = i.saturating_sub(config.min_segment_len.max(1) - 1);
+    start..=end
+}
@@ -0,0 +1,76 @@
+// 与 kernels.cu 的 compute_errors 相同的均匀采样误差，每个工作项计算一个区间。
+// 以 -D USE_FP64 编译时按双精度计算，需要设备支持 cl_khr_fp64
+#ifdef USE_FP64
+#pragma OPENCL EXTENSION cl_khr_fp64 : enable
+typedef double real;
+#else
+typedef float real;
+#endif
+
+real distance2_at(real t, real px, real py, real p0x, real p0y, real p1x, real p1y,
+                  real p2x, real p2y) {
+    real mt = (real)1 - t;
+    real bx = mt * mt * p0x + (real)2 * mt * t * p1x + t * t * p2x;
+    real by = mt * mt * p0y + (real)2 * mt * t * p1y + t * t * p2y;
+    real dx = bx - px;
+    real dy = by - py;
+    return dx * dx + dy * dy;
+}
+
+// ctrl 每个区间 6 个坐标：p0x p0y p1x p1y p2x p2y；range 每个区间起止下标（含）。
+// 误差超过上限时写入正无穷，与 CPU 的 BezierFitter::apply_limit 一致
+__kernel void compute_errors(
+    __global const real* pts_x,
+    __global const real* pts_y,
+    __global const real* ctrl,
+    __global const int* range,
+    real max_error,
+    __global real* out_err,
+    int n_segments
+) {
+    int idx = (int)get_global_id(0);
+    if (idx >= n_segments) {
+        return;
+    }
+
+    int start = range[2 * idx];
+    int end = range[2 * idx + 1];
+    int len = end - start + 1;
+    if (len <= 0) {
+        out_err[idx] = (real)0;
+        return;
+    }
+
+    __global const real* c = ctrl + 6 * idx;
+    real p0x = c[0], p0y = c[1], p1x = c[2], p1y = c[3], p2x = c[4], p2y = c[5];
+    real dx01 = p0x - p1x;
+    real dy01 = p0y - p1y;
+    real dx12 = p1x - p2x;
+    real dy12 = p1y - p2y;
+    real curve_len = sqrt(dx01 * dx01 + dy01 * dy01) + sqrt(dx12 * dx12 + dy12 * dy12);
+
+    int samples = (int)(curve_len / (real)2);
+    if (samples < 50) samples = 50;
+    if (samples > 200) samples = 200;
+    real denom = (real)(samples - 1);
+
+    real max_sum = max_error * (real)len;
+    real sum = (real)0;
+    for (int i = start; i <= end; ++i) {
+        real px = pts_x[i];
+        real py = pts_y[i];
+        real min_d2 = (real)3.0e38;
+        for (int s = 0; s < samples; ++s) {
+            real d2 = distance2_at((real)s / denom, px, py, p0x, p0y, p1x, p1y, p2x, p2y);
+            if (d2 < min_d2) {
+                min_d2 = d2;
+            }
+        }
+        sum += min_d2;
+        if (sum > max_sum) {
+            out_err[idx] = INFINITY;
+            return;
+        }
+    }
+    out_err[idx] = sum / (real)len;
+}
@@ -0,0 +1,344 @@
+// 误差超过上限时写入正无穷，与 CPU 的 BezierFitter::apply_limit 一致
+__device__ inline double limit_exceeded(double) { return __longlong_as_double(0x7ff0000000000000LL); }
+__device__ inline float limit_exceeded(float) { return __int_as_float(0x7f800000); }
+
+template <typename T>
+__device__ T distance2_at(T t, T px, T py, T p0x, T p0y, T p1x, T p1y, T p2x, T p2y) {
+    T mt = (T)1 - t;
+    T bx = mt * mt * p0x + (T)2 * mt * t * p1x + t * t * p2x;
+    T by = mt * mt * p0y + (T)2 * mt * t * p1y + t * t * p2y;
+    T dx = bx - px;
+    T dy = by - py;
+    return dx * dx + dy * dy;
+}
+
+// 与 CPU 的 distance_to_point 相同：按曲线长度均匀采样 50..200 个参数取最近
+template <typename T>
+__device__ T nearest_d2_sampled(T px, T py, T p0x, T p0y, T p1x, T p1y, T p2x, T p2y,
+                                int samples, T denom) {
+    T min_d2 = (T)3.0e38;
+    for (int s = 0; s < samples; ++s) {
+        T d2 = distance2_at((T)s / denom, px, py, p0x, p0y, p1x, p1y, p2x, p2y);
+        if (d2 < min_d2) {
+            min_d2 = d2;
+        }
+    }
+    return min_d2;
+}
+
+// 8 个粗采样取最近点作初值，再用 3 次牛顿迭代求 (B(t) - p)·B'(t) = 0，
+// 与 CPU 的 closest_parameter 相同；结果不差于粗采样
+template <typename T>
+__device__ T nearest_d2_newton(T px, T py, T p0x, T p0y, T p1x, T p1y, T p2x, T p2y) {
+    const int coarse = 8;
+    T best_t = (T)0;
+    T best_d2 = (T)3.0e38;
+    for (int s = 0; s < coarse; ++s) {
+        T t = (T)s / (T)(coarse - 1);
+        T d2 = distance2_at(t, px, py, p0x, p0y, p1x, p1y, p2x, p2y);
+        if (d2 < best_d2) {
+            best_d2 = d2;
+            best_t = t;
+        }
+    }
+
+    // B''/2 为常向量
+    T ax = p0x - (T)2 * p1x + p2x;
+    T ay = p0y - (T)2 * p1y + p2y;
+    T t = best_t;
+    for (int k = 0; k < 3; ++k) {
+        T mt = (T)1 - t;
+        T rx = mt * mt * p0x + (T)2 * mt * t * p1x + t * t * p2x - px;
+        T ry = mt * mt * p0y + (T)2 * mt * t * p1y + t * t * p2y - py;
+        T dx = (T)2 * (mt * (p1x - p0x) + t * (p2x - p1x));
+        T dy = (T)2 * (mt * (p1y - p0y) + t * (p2y - p1y));
+        T f = rx * dx + ry * dy;
+        T df = dx * dx + dy * dy + (T)2 * (rx * ax + ry * ay);
+        if (df > (T)-1e-12 && df < (T)1e-12) {
+            break;
+        }
+        t -= f / df;
+        if (t < (T)0) t = (T)0;
+        if (t > (T)1) t = (T)1;
+    }
+
+    T d2 = distance2_at(t, px, py, p0x, p0y, p1x, p1y, p2x, p2y);
+    return d2 < best_d2 ? d2 : best_d2;
+}
+
+// 点坐标按块分片载入共享内存，每个点只从全局内存读一次。块内区间按终点、起点升序相邻，
+// 覆盖的点窗口只比最长的区间略长。提前终止的线程仍参与载入，整块都结束后才退出循环
+template <typename T, bool NEWTON>
+__device__ void compute_errors_impl(
+    const T* pts_x,
+    const T* pts_y,
+    int n_points,
+    const T* p0x,
+    const T* p0y,
+    const T* p1x,
+    const T* p1y,
+    const T* p2x,
+    const T* p2y,
+    const int* start_idx,
+    const int* end_idx,
+    T max_error,
+    T* out_err,
+    int n_segments
+) {
+    extern __shared__ __align__(8) unsigned char tile_raw[];
+    T* tile_x = reinterpret_cast<T*>(tile_raw);
+    T* tile_y = tile_x + blockDim.x;
+    __shared__ int window_lo;
+    __shared__ int window_hi;
+
+    int idx = (int)(blockIdx.x * blockDim.x + threadIdx.x);
+    bool active = idx < n_segments;
+    int start = active ? start_idx[idx] : 0;
+    int end = active ? end_idx[idx] : -1;
+    int len = end - start + 1;
+    bool done = len <= 0;
+
+    if (threadIdx.x == 0) {
+        window_lo = 0x7fffffff;
+        window_hi = -1;
+    }
+    __syncthreads();
+    if (!done) {
+        atomicMin(&window_lo, start);
+        atomicMax(&window_hi, end);
+    }
+    __syncthreads();
+
+    T p0xv = (T)0, p0yv = (T)0, p1xv = (T)0, p1yv = (T)0, p2xv = (T)0, p2yv = (T)0;
+    if (active) {
+        p0xv = p0x[idx];
+        p0yv = p0y[idx];
+        p1xv = p1x[idx];
+        p1yv = p1y[idx];
+        p2xv = p2x[idx];
+        p2yv = p2y[idx];
+    }
+
+    T dx01 = p0xv - p1xv;
+    T dy01 = p0yv - p1yv;
+    T dx12 = p1xv - p2xv;
+    T dy12 = p1yv - p2yv;
+    T curve_len = sqrt(dx01 * dx01 + dy01 * dy01)
+                + sqrt(dx12 * dx12 + dy12 * dy12);
+
+    int samples = (int)(curve_len / (T)2);
+    if (samples < 50) samples = 50;
+    if (samples > 200) samples = 200;
+    T denom = (samples > 1) ? (T)(samples - 1) : (T)1;
+
+    T max_sum = max_error * (T)len;
+    T sum = (T)0;
+    bool exceeded = false;
+
+    for (int base = window_lo; base <= window_hi; base += (int)blockDim.x) {
+        int k = base + (int)threadIdx.x;
+        if (k <= window_hi) {
+            tile_x[threadIdx.x] = pts_x[k];
+            tile_y[threadIdx.x] = pts_y[k];
+        }
+        __syncthreads();
+
+        if (!done) {
+            int first = start > base ? start : base;
+            int last = base + (int)blockDim.x - 1;
+            if (end < last) last = end;
+            for (int i = first; i <= last; ++i) {
+                T px = tile_x[i - base];
+                T py = tile_y[i - base];
+                if (NEWTON) {
+                    sum += nearest_d2_newton(px, py, p0xv, p0yv, p1xv, p1yv, p2xv, p2yv);
+                } else {
+                    sum += nearest_d2_sampled(px, py, p0xv, p0yv, p1xv, p1yv, p2xv, p2yv,
+                                              samples, denom);
+                }
+                if (sum > max_sum) {
+                    exceeded = true;
+                    break;
+                }
+            }
+            done = exceeded || last == end;
+        }
+
+        // 同时作为下一片载入前的屏障
+        if (__syncthreads_count(done) == (int)blockDim.x) {
+            break;
+        }
+    }
+
+    if (active) {
+        if (len <= 0) {
+            out_err[idx] = (T)0;
+        } else if (exceeded) {
+            out_err[idx] = limit_exceeded(sum);
+        } else {
+            out_err[idx] = sum / (T)len;
+        }
+    }
+}
+
+extern "C" __global__ void compute_errors(
+    const double* pts_x, const double* pts_y, int n_points,
+    const double* p0x, const double* p0y, const double* p1x,
+    const double* p1y, const double* p2x, const double* p2y,
+    const int* start_idx, const int* end_idx,
+    double max_error, double* out_err, int n_segments
+) {
+    compute_errors_impl<double, false>(pts_x, pts_y, n_points, p0x, p0y, p1x, p1y, p2x, p2y,
+                                       start_idx, end_idx, max_error, out_err, n_segments);
+}
+
+extern "C" __global__ void compute_errors_f32(
+    const float* pts_x, const float* pts_y, int n_points,
+    const float* p0x, const float* p0y, const float* p1x,
+    const float* p1y, const float* p2x, const float* p2y,
+    const int* start_idx, const int* end_idx,
+    float max_error, float* out_err, int n_segments
+) {
+    compute_errors_impl<float, false>(pts_x, pts_y, n_points, p0x, p0y, p1x, p1y, p2x, p2y,
+                                      start_idx, end_idx, max_error, out_err, n_segments);
+}
+
+extern "C" __global__ void compute_errors_newton(
+    const double* pts_x, const double* pts_y, int n_points,
+    const double* p0x, const double* p0y, const double* p1x,
+    const double* p1y, const double* p2x, const double* p2y,
+    const int* start_idx, const int* end_idx,
+    double max_error, double* out_err, int n_segments
+) {
+    compute_errors_impl<double, true>(pts_x, pts_y, n_points, p0x, p0y, p1x, p1y, p2x, p2y,
+                                      start_idx, end_idx, max_error, out_err, n_segments);
+}
+
+extern "C" __global__ void compute_errors_newton_f32(
+    const float* pts_x, const float* pts_y, int n_points,
+    const float* p0x, const float* p0y, const float* p1x,
+    const float* p1y, const float* p2x, const float* p2y,
+    const int* start_idx, const int* end_idx,
+    float max_error, float* out_err, int n_segments
+) {
+    compute_errors_impl<float, true>(pts_x, pts_y, n_points, p0x, p0y, p1x, p1y, p2x, p2y,
+                                     start_idx, end_idx, max_error, out_err, n_segments);
+}
+
+// DP 候选 a 是否严格优于 b，与 CPU 的 DpEntry::precedes 相同；段数为负表示没有候选。
+// 代价显式按 IEEE 舍入计算，避免编译器合并为 FMA 后与 CPU 的平局判定不一致
+__device__ bool dp_precedes(int seg_a, double err_a, int par_a,
+                            int seg_b, double err_b, int par_b,
+                            int objective, double lambda) {
+    if (seg_a < 0) {
+        return false;
+    }
+    if (seg_b < 0) {
+        return true;
+    }
+    if (objective == 0) {
+        if (seg_a != seg_b) {
+            return seg_a < seg_b;
+        }
+        if (err_a != err_b) {
+            return err_a < err_b;
+        }
+        return par_a < par_b;
+    }
+    double cost_a = __dadd_rn(err_a, __dmul_rn(lambda, (double)seg_a));
+    double cost_b = __dadd_rn(err_b, __dmul_rn(lambda, (double)seg_b));
+    if (cost_a != cost_b) {
+        return cost_a < cost_b;
+    }
+    return par_a < par_b;
+}
+
+// 单个 block 按终点顺序逐行递推：行内候选起点由各线程分摊，在共享内存中归约出最优后
+// 由 0 号线程写回。errors 按终点、起点升序存放所有候选区间的误差，row_offset[i]
+// 为以 i 结尾的第一个候选的位置。不可达下标的段数和上一个断点为 -1
+template <typename T>
+__device__ void dp_forward_impl(
+    const T* errors, const long long* row_offset, int n,
+    int min_len, int max_len, double max_error, int objective, double lambda,
+    int* seg, double* err, int* parent
+) {
+    extern __shared__ __align__(8) unsigned char dp_raw[];
+    double* s_err = (double*)dp_raw;
+    int* s_seg = (int*)(s_err + blockDim.x);
+    int* s_par = s_seg + blockDim.x;
+    int tid = threadIdx.x;
+
+    for (int k = tid; k < n; k += blockDim.x) {
+        seg[k] = k == 0 ? 0 : -1;
+        err[k] = k == 0 ? 0.0 : __longlong_as_double(0x7ff0000000000000LL);
+        parent[k] = k == 0 ? 0 : -1;
+    }
+    __syncthreads();
+
+    int max_span = (max_len > 1 ? max_len : 1) - 1;
+    int min_span = (min_len > 1 ? min_len : 1) - 1;
+    for (int i = min_len > 1 ? min_len : 1; i < n; ++i) {
+        int lo = i > max_span ? i - max_span : 0;
+        int hi = i > min_span ? i - min_span : 0;
+        const T* row = errors + row_offset[i];
+
+        int best_seg = -1;
+        double best_err = 0.0;
+        int best_par = -1;
+        for (int j = lo + tid; j <= hi; j += blockDim.x) {
+            double e = (double)row[j - lo];
+            if (seg[j] < 0 || !(e <= max_error)) {
+                continue;
+            }
+            int cand_seg = seg[j] + 1;
+            double cand_err = err[j] + e;
+            if (dp_precedes(cand_seg, cand_err, j, best_seg, best_err, best_par, objective, lambda)) {
+                best_seg = cand_seg;
+                best_err = cand_err;
+                best_par = j;
+            }
+        }
+        s_seg[tid] = best_seg;
+        s_err[tid] = best_err;
+        s_par[tid] = best_par;
+        __syncthreads();
+
+        for (int stride = 1; stride < (int)blockDim.x; stride *= 2) {
+            int other = tid + stride;
+            if (tid % (2 * stride) == 0 && other < (int)blockDim.x
+                && dp_precedes(s_seg[other], s_err[other], s_par[other],
+                               s_seg[tid], s_err[tid], s_par[tid], objective, lambda)) {
+                s_seg[tid] = s_seg[other];
+                s_err[tid] = s_err[other];
+                s_par[tid] = s_par[other];
+            }
+            __syncthreads();
+        }
+
+        // 同一 block 内 __syncthreads 之后全局内存的写入对其他线程可见
+        if (tid == 0 && s_seg[0] >= 0) {
+            seg[i] = s_seg[0];
+            err[i] = s_err[0];
+            parent[i] = s_par[0];
+        }
+        __syncthreads();
+    }
+}
+
+extern "C" __global__ void dp_forward(
+    const double* errors, const long long* row_offset, int n,
+    int min_len, int max_len, double max_error, int objective, double lambda,
+    int* seg, double* err, int* parent
+) {
+    dp_forward_impl<double>(errors, row_offset, n, min_len, max_len, max_error, objective, lambda,
+                            seg, err, parent);
+}
+
+extern "C" __global__ void dp_forward_f32(
+    const float* errors, const long long* row_offset, int n,
+    int min_len, int max_len, double max_error, int objective, double lambda,
+    int* seg, double* err, int* parent
+) {
+    dp_forward_impl<float>(errors, row_offset, n, min_len, max_len, max_error, objective, lambda,
+                           seg, err, parent);
+}
@@ -1,7 +1,53 @@
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
 pub mod config;
+#[cfg(feature = "std")]
+pub mod document;
+#[cfg(feature = "std")]
 pub mod dp;
+#[cfg(feature = "std")]
+pub mod dp3d;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
+pub mod interpolate;
+#[cfg(feature = "std")]
+mod intervals;
+#[cfg(feature = "std")]
+pub mod params;
+#[cfg(feature = "std")]
+mod path;
+#[cfg(feature = "std")]
+pub mod quality;
+#[cfg(feature = "std")]
+pub mod streaming;
+pub mod serial;
 #[cfg(feature = "cuda")]
 pub mod cuda;
+#[cfg(feature = "gpu-wgpu")]
+pub mod wgpu_backend;
+#[cfg(feature = "opencl")]
+pub mod opencl;
 
-pub use config::FitConfig;
-pub use dp::{DPOptimizer, FitResult, fit_curve};
+#[cfg(feature = "std")]
+pub use cache::ErrorCache;
+#[cfg(feature = "std")]
+pub use config::{AdaptiveWindow, Backend, CacheMode, CudaOptions, EndpointPolicy, ErrorMetric, FitConfig, FitConfigBuilder, FitterKind, Multiresolution, Objective, Precision, PrimitiveSet};
+#[cfg(feature = "std")]
+pub use document::FitDocument;
+#[cfg(feature = "std")]
+pub use dp::{DPOptimizer, FitEstimate, FitResult, FitStatus, ParityReport, SegmentInfo, fit_curve, fit_curve_chunked, fit_multistroke, try_fit_curve, try_fit_strokes};
+#[cfg(feature = "std")]
+pub use dp3d::{FitResult3D, fit_curve_3d};
+#[cfg(feature = "std")]
+pub use error::{ConfigError, FitCurveError};
+#[cfg(feature = "std")]
+pub use interpolate::interpolate_catmull_rom;
+#[cfg(feature = "std")]
+pub use params::FitParams;
+#[cfg(feature = "std")]
+pub use quality::{EquivarianceReport, FitQuality, PathMetric, SegmentQuality};
+#[cfg(feature = "std")]
+pub use streaming::StreamingFitter;
+pub use serial::{SerialFit, fit_curve_serial};
@@ -0,0 +1,263 @@
+#![cfg(feature = "opencl")]
+
+use std::sync::{Arc, Mutex, OnceLock};
+
+use ocl::{flags, Buffer, Context, Device, Kernel, OclPrm, Platform, Program, Queue};
+
+use crate::fitting::{BezierFitter, FitError};
+use crate::geometry::Point2D;
+use crate::optimizer::cache::ErrorCache;
+use crate::optimizer::config::{FitConfig, Precision};
+use crate::optimizer::intervals::IntervalIter;
+
+/// 单次提交的区间数上限
+const CHUNK_LEN: usize = 1 << 18;
+
+/// 误差内核源码，与 CUDA 内核的均匀采样版本一致
+const CL_SRC: &str = include_str!("kernels.cl");
+
+/// 内核支持的浮点类型
+trait ClScalar: OclPrm {
+    fn from_f64(v: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl ClScalar for f64 {
+    fn from_f64(v: f64) -> Self {
+        v
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl ClScalar for f32 {
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+/// 按配置取共享引擎计算误差缓存
+pub fn compute_error_cache_opencl(
+    points: &[Point2D],
+    config: &FitConfig,
+) -> Result<ErrorCache, String> {
+    OpenClEngine::shared()?.compute_error_cache(points, config)
+}
+
+/// OpenCL 计算后端，使用第一个平台的第一个设备。双精度需要设备支持 `cl_khr_fp64`，
+/// 不支持时 `Precision::F64` 返回错误，由调用方回退到 CPU；不支持 `CudaOptions::newton_refine`
+pub struct OpenClEngine {
+    queue: Queue,
+    program: Program,
+    program_f64: Option<Program>,
+    device_name: String,
+}
+
+impl OpenClEngine {
+    pub fn new() -> Result<Self, String> {
+        let platform = Platform::list()
+            .into_iter()
+            .next()
+            .ok_or_else(|| "no opencl platform".to_string())?;
+        let device = Device::first(platform).map_err(|e| format!("opencl device: {e}"))?;
+        let context = Context::builder()
+            .platform(platform)
+            .devices(device)
+            .build()
+            .map_err(|e| format!("opencl context: {e}"))?;
+        let queue = Queue::new(&context, device, None).map_err(|e| format!("opencl queue: {e}"))?;
+
+        let build = |options: &str| {
+            Program::builder()
+                .devices(device)
+                .src(CL_SRC)
+                .cmplr_opt(options)
+                .build(&context)
+        };
+        let program = build("").map_err(|e| format!("opencl build: {e}"))?;
+        let program_f64 = build("-D USE_FP64").ok();
+        let device_name = device.name().map_err(|e| format!("opencl device: {e}"))?;
+
+        Ok(Self {
+            queue,
+            program,
+            program_f64,
+            device_name,
+        })
+    }
+
+    /// 进程内共享的引擎，首次使用时初始化；初始化失败不缓存，下次调用会重试
+    pub fn shared() -> Result<Arc<OpenClEngine>, String> {
+        static ENGINE: OnceLock<Mutex<Option<Arc<OpenClEngine>>>> = OnceLock::new();
+        let mut engine = ENGINE
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(engine) = engine.as_ref() {
+            return Ok(engine.clone());
+        }
+        let created = Arc::new(Self::new()?);
+        *engine = Some(created.clone());
+        Ok(created)
+    }
+
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+
+    /// 设备是否支持双精度
+    pub fn supports_f64(&self) -> bool {
+        self.program_f64.is_some()
+    }
+
+    pub fn compute_error_cache(
+        &self,
+        points: &[Point2D],
+        config: &FitConfig,
+    ) -> Result<ErrorCache, String> {
+        match config.precision {
+            Precision::F32 => self.compute_with::<f32>(points, config, &self.program),
+            Precision::F64 => {
+                let program = self
+                    .program_f64
+                    .as_ref()
+                    .ok_or_else(|| format!("{} does not support cl_khr_fp64", self.device_name))?;
+                self.compute_with::<f64>(points, config, program)
+            }
+        }
+    }
+
+    fn compute_with<T: ClScalar>(
+        &self,
+        points: &[Point2D],
+        config: &FitConfig,
+        program: &Program,
+    ) -> Result<ErrorCache, String> {
+        let n = points.len();
+        if n == 0 {
+            return Ok(ErrorCache::new());
+        }
+
+        let mut intervals = IntervalIter::new(n, config).peekable();
+        if intervals.peek().is_none() {
+            return Ok(ErrorCache::new());
+        }
+
+        // 以首点为原点，减少单精度下大坐标的精度损失
+        let origin = points[0];
+        let points_x: Vec<T> = points.iter().map(|p| T::from_f64(p.x - origin.x)).collect();
+        let points_y: Vec<T> = points.iter().map(|p| T::from_f64(p.y - origin.y)).collect();
+        let d_points_x = self.upload(&points_x, "points x")?;
+        let d_points_y = self.upload(&points_y, "points y")?;
+
+        let mut cache = ErrorCache::new();
+        while intervals.peek().is_some() {
+            let chunk: Vec<(usize, usize)> = intervals.by_ref().take(CHUNK_LEN).collect();
+            let beziers: Vec<_> = chunk
+                .iter()
+                .map(|&(start, end)| BezierFitter::compute_bezier(&points[start..=end]))
+                .collect();
+            let ctrl: Vec<T> = beziers
+                .iter()
+                .flat_map(|b| {
+                    [
+                        b.p0.x - origin.x,
+                        b.p0.y - origin.y,
+                        b.p1.x - origin.x,
+                        b.p1.y - origin.y,
+                        b.p2.x - origin.x,
+                        b.p2.y - origin.y,
+                    ]
+                })
+                .map(T::from_f64)
+                .collect();
+            let ranges: Vec<i32> = chunk
+                .iter()
+                .flat_map(|&(start, end)| [start as i32, end as i32])
+                .collect();
+
+            let errors = self.launch_chunk(
+                program,
+                (&d_points_x, &d_points_y),
+                &ctrl,
+                &ranges,
+                T::from_f64(config.max_error),
+            )?;
+            for ((&(start, end), bezier), error) in chunk.iter().zip(beziers).zip(errors) {
+                cache.insert(
+                    start,
+                    end,
+                    FitError {
+                        bezier,
+                        error: BezierFitter::apply_limit(error.to_f64(), config.max_error),
+                        arc: None,
+                    },
+                );
+            }
+        }
+
+        Ok(cache)
+    }
+
+    fn upload<T: OclPrm>(&self, data: &[T], what: &str) -> Result<Buffer<T>, String> {
+        Buffer::<T>::builder()
+            .queue(self.queue.clone())
+            .flags(flags::MEM_READ_ONLY)
+            .len(data.len())
+            .copy_host_slice(data)
+            .build()
+            .map_err(|e| format!("copy {what}: {e}"))
+    }
+
+    /// 上传一块区间并启动内核，同步读回各区间误差
+    fn launch_chunk<T: ClScalar>(
+        &self,
+        program: &Program,
+        (d_points_x, d_points_y): (&Buffer<T>, &Buffer<T>),
+        ctrl: &[T],
+        ranges: &[i32],
+        max_error: T,
+    ) -> Result<Vec<T>, String> {
+        let count = ranges.len() / 2;
+        let d_ctrl = self.upload(ctrl, "control points")?;
+        let d_ranges = self.upload(ranges, "ranges")?;
+        let d_out = Buffer::<T>::builder()
+            .queue(self.queue.clone())
+            .flags(flags::MEM_WRITE_ONLY)
+            .len(count)
+            .build()
+            .map_err(|e| format!("alloc output: {e}"))?;
+
+        // 内核对象不可跨线程共享参数，每块单独创建
+        let kernel = Kernel::builder()
+            .program(program)
+            .name("compute_errors")
+            .queue(self.queue.clone())
+            .global_work_size(count)
+            .arg(d_points_x)
+            .arg(d_points_y)
+            .arg(&d_ctrl)
+            .arg(&d_ranges)
+            .arg(max_error)
+            .arg(&d_out)
+            .arg(count as i32)
+            .build()
+            .map_err(|e| format!("opencl kernel: {e}"))?;
+        unsafe {
+            kernel.enq().map_err(|e| format!("launch: {e}"))?;
+        }
+
+        let mut errors = vec![T::default(); count];
+        d_out
+            .read(&mut errors)
+            .enq()
+            .map_err(|e| format!("copy back: {e}"))?;
+        Ok(errors)
+    }
+}
@@ -0,0 +1,55 @@
+use serde::Deserialize;
+
+use super::config::FitConfig;
+use super::error::ConfigError;
+use crate::preprocess::Smoothing;
+
+/// 按名称给出的拟合参数，命令行、Python 接口和 HTTP 服务共用同一套解析规则；
+/// 未给出的取预设（默认 `FitConfig::default()`）的值
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct FitParams {
+    pub min_segment_len: Option<usize>,
+    pub max_segment_len: Option<usize>,
+    pub max_error: Option<f64>,
+    /// 见 `FitConfig::preset`
+    pub preset: Option<String>,
+    /// "auto" / "cpu" / "cuda" / "wgpu" / "cuda-verified" / "opencl"
+    pub backend: Option<String>,
+    /// "mse" / "max"
+    pub metric: Option<String>,
+    /// 拟合前高斯平滑的 sigma
+    pub presmooth: Option<f64>,
+}
+
+impl FitParams {
+    /// 显式参数覆盖预设后校验；名称未知或参数无效时报错，不自动修正
+    pub fn to_config(&self) -> Result<FitConfig, ConfigError> {
+        let invalid = |reason: String| ConfigError { reason };
+        let mut config = match self.preset.as_deref() {
+            Some(name) => {
+                FitConfig::preset(name).ok_or_else(|| invalid(format!("unknown preset: {name}")))?
+            }
+            None => FitConfig::default(),
+        };
+        if let Some(len) = self.min_segment_len {
+            config.min_segment_len = len;
+        }
+        if let Some(len) = self.max_segment_len {
+            config.max_segment_len = len;
+        }
+        if let Some(error) = self.max_error {
+            config.max_error = error;
+        }
+        if let Some(name) = self.backend.as_deref() {
+            config.backend = name.parse().map_err(invalid)?;
+        }
+        if let Some(name) = self.metric.as_deref() {
+            config.metric = name.parse().map_err(invalid)?;
+        }
+        if let Some(sigma) = self.presmooth {
+            config.presmooth = Some(Smoothing::Gaussian { sigma });
+        }
+        config.validate()?;
+        Ok(config)
+    }
+}
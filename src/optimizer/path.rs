@@ -0,0 +1,253 @@
+use crate::geometry::{Point2D, QuadraticBSpline, QuadraticBezier, Segment};
+
+use super::dp::FitResult;
+
+impl FitResult {
+    /// 整条路径的弧长
+    pub fn arc_length(&self) -> f64 {
+        self.curves.iter().map(|c| c.arc_length()).sum()
+    }
+
+    /// 把整条路径视为一条按弧长参数化的曲线，u ∈ [0, 1]，超出范围时取端点；无曲线时返回 None
+    pub fn evaluate(&self, u: f64) -> Option<Point2D> {
+        self.point_at_arclength(u.clamp(0.0, 1.0) * self.arc_length())
+    }
+
+    /// 从起点沿路径走过弧长 s 处的点，s 超出 [0, 弧长] 时取端点；无曲线时返回 None
+    pub fn point_at_arclength(&self, s: f64) -> Option<Point2D> {
+        let (k, t) = self.locate_arclength(s)?;
+        Some(self.curves[k].evaluate(t))
+    }
+
+    /// 弧长 s 处的单位切向量，可用于沿路径的航向；无曲线时返回 None
+    pub fn tangent_at_arclength(&self, s: f64) -> Option<Point2D> {
+        let (k, t) = self.locate_arclength(s)?;
+        Some(self.curves[k].tangent(t))
+    }
+
+    /// 偏移距离为 `distance` 的平行路径（正值在行进方向左侧，y 轴向上时），用于绘图仪、雕刻的刀具路径；
+    /// 每条曲线按 tolerance 用新的二次曲线近似，转角处偏移后的缺口用直线连接。
+    /// 结果不再对应输入点，`breakpoints`、`segment_errors` 等逐段信息为空
+    pub fn offset(&self, distance: f64, tolerance: f64) -> FitResult {
+        let mut curves: Vec<QuadraticBezier> = Vec::new();
+        for (k, curve) in self.curves.iter().enumerate() {
+            let mut pieces = curve.offset(distance, tolerance);
+            let connected = k > 0 && self.curves[k - 1].p2 == curve.p0;
+            if let (true, Some(last), Some(first)) = (connected, curves.last(), pieces.first_mut())
+            {
+                let end = last.p2;
+                if end.distance_to(&first.p0) <= tolerance {
+                    first.p0 = end;
+                } else {
+                    curves.push(QuadraticBezier::new(
+                        end,
+                        end.lerp(&first.p0, 0.5),
+                        first.p0,
+                    ));
+                }
+            }
+            curves.extend(pieces);
+        }
+        let segments = curves
+            .iter()
+            .map(|c| Segment::from_quad(c, self.config.line_tolerance))
+            .collect();
+        FitResult {
+            num_segments: curves.len(),
+            curves,
+            total_error: 0.0,
+            config: self.config.clone(),
+            breakpoints: vec![],
+            segments,
+            segment_errors: vec![],
+            status: self.status,
+            blocking_intervals: vec![],
+            segment_times: vec![],
+            segment_info: vec![],
+        }
+    }
+
+    /// 路径的自交点 `(i, t_i, j, t_j, point)`，i < j 为曲线下标；相邻曲线的公共端点
+    /// 和闭合路径首尾的接点不算自交
+    pub fn self_intersections(&self) -> Vec<(usize, f64, usize, f64, Point2D)> {
+        const EPS: f64 = 1e-6;
+        let n = self.curves.len();
+        let closed = n > 1 && self.curves[0].p0 == self.curves[n - 1].p2;
+        let mut found = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                let (a, b) = (&self.curves[i], &self.curves[j]);
+                let adjacent = j == i + 1 && a.p2 == b.p0;
+                let wraps = closed && i == 0 && j == n - 1;
+                for (ti, tj) in a.intersections(b) {
+                    if (adjacent && ti > 1.0 - EPS && tj < EPS)
+                        || (wraps && ti < EPS && tj > 1.0 - EPS)
+                    {
+                        continue;
+                    }
+                    found.push((i, ti, j, tj, a.evaluate(ti)));
+                }
+            }
+        }
+        found
+    }
+
+    /// 有向面积，y 轴向上时逆时针为正，可据此区分外轮廓和孔；
+    /// 路径未闭合时首尾、以及曲线之间不连续处都按直线连接。逐段用二次曲线的解析积分
+    pub fn signed_area(&self) -> f64 {
+        let cross = |a: Point2D, b: Point2D| a.x * b.y - a.y * b.x;
+        self.closed_loop()
+            .iter()
+            .map(|c| (2.0 * cross(c.p0, c.p1) + 2.0 * cross(c.p1, c.p2) + cross(c.p0, c.p2)) / 6.0)
+            .sum()
+    }
+
+    /// 路径绕 `point` 的环绕数，逆时针为正；非零即在内部（nonzero 填充规则）。
+    /// 闭合方式与 `signed_area` 相同
+    pub fn winding_of(&self, point: Point2D) -> i32 {
+        let mut winding = 0;
+        for curve in self.closed_loop() {
+            // 在 y 的极值处切开，每段在 y 上单调
+            let a = curve.p0.y - 2.0 * curve.p1.y + curve.p2.y;
+            let t = (curve.p0.y - curve.p1.y) / a;
+            let pieces = if a != 0.0 && t > 0.0 && t < 1.0 {
+                let (left, right) = curve.split(t);
+                vec![left, right]
+            } else {
+                vec![curve]
+            };
+            winding += pieces
+                .iter()
+                .map(|c| monotone_crossing(c, point))
+                .sum::<i32>();
+        }
+        winding
+    }
+
+    /// 所有曲线依次相接，不连续处和首尾之间补上直线
+    fn closed_loop(&self) -> Vec<QuadraticBezier> {
+        let line = |a: Point2D, b: Point2D| QuadraticBezier::new(a, a.lerp(&b, 0.5), b);
+        let mut curves = Vec::with_capacity(self.curves.len() + 1);
+        for curve in &self.curves {
+            if let Some(last) = curves.last().map(|c: &QuadraticBezier| c.p2) {
+                if last != curve.p0 {
+                    curves.push(line(last, curve.p0));
+                }
+            }
+            curves.push(*curve);
+        }
+        if let (Some(first), Some(last)) = (self.curves.first(), self.curves.last()) {
+            if last.p2 != first.p0 {
+                curves.push(line(last.p2, first.p0));
+            }
+        }
+        curves
+    }
+
+    /// 自适应细分为一条折线，每段与曲线的偏差不超过 tolerance，点数尽量少；
+    /// 适合交给光栅化或物理引擎。不连续的曲线之间直接相连
+    pub fn flatten(&self, tolerance: f64) -> Vec<Point2D> {
+        let mut points: Vec<Point2D> = Vec::new();
+        for curve in &self.curves {
+            let flat = curve.flatten(tolerance);
+            let skip = usize::from(points.last() == Some(&curve.p0));
+            points.extend(flat.into_iter().skip(skip));
+        }
+        points
+    }
+
+    /// 转换为二次 B 样条，供只接受 B 样条的 CAD 内核使用。G1 连续的分段处取单节点，
+    /// 节点间距按两侧控制柄的长度比选取，使样条在该处 C1 连续；其余分段处取二重节点并把端点作为控制点，
+    /// 曲线形状不变。无曲线或相邻曲线不首尾相接时返回 None
+    pub fn to_bspline(&self) -> Option<QuadraticBSpline> {
+        let first = self.curves.first()?;
+        if self.curves.windows(2).any(|w| w[0].p2 != w[1].p0) {
+            return None;
+        }
+
+        let mut control_points = vec![first.p0, first.p1];
+        let mut knots = vec![0.0; 3];
+        let mut u = 0.0;
+        let mut span = first.arc_length().max(f64::EPSILON);
+        for pair in self.curves.windows(2) {
+            let (prev, next) = (&pair[0], &pair[1]);
+            let a = (next.p0.x - prev.p1.x, next.p0.y - prev.p1.y);
+            let b = (next.p1.x - next.p0.x, next.p1.y - next.p0.y);
+            let (la, lb) = (a.0.hypot(a.1), b.0.hypot(b.1));
+            let cross = a.0 * b.1 - a.1 * b.0;
+            let dot = a.0 * b.0 + a.1 * b.1;
+            let smooth = la > 0.0 && lb > 0.0 && dot > 0.0 && cross.abs() <= 1e-9 * la * lb;
+
+            u += span;
+            knots.push(u);
+            if smooth {
+                span *= lb / la;
+            } else {
+                knots.push(u);
+                control_points.push(next.p0);
+                span = next.arc_length().max(f64::EPSILON);
+            }
+            control_points.push(next.p1);
+        }
+        u += span;
+        control_points.push(self.curves[self.curves.len() - 1].p2);
+        knots.extend([u; 3]);
+        for knot in &mut knots {
+            *knot /= u;
+        }
+
+        Some(QuadraticBSpline { control_points, knots })
+    }
+
+    /// 弧长 s 落在第几段以及段内的参数 t
+    pub(crate) fn locate_arclength(&self, s: f64) -> Option<(usize, f64)> {
+        let last = self.curves.len().checked_sub(1)?;
+        let mut remaining = s.max(0.0);
+        for (k, curve) in self.curves.iter().enumerate() {
+            let length = curve.arc_length();
+            if remaining <= length || k == last {
+                return Some((k, curve.parameter_at_length(remaining)));
+            }
+            remaining -= length;
+        }
+        None
+    }
+}
+
+/// y 单调的曲线与从 point 向 +x 方向射线的有向穿越次数；按半开区间计，公共端点只算一次
+fn monotone_crossing(curve: &QuadraticBezier, point: Point2D) -> i32 {
+    let (y0, y2) = (curve.p0.y, curve.p2.y);
+    let direction = if y0 <= point.y && point.y < y2 {
+        1
+    } else if y2 <= point.y && point.y < y0 {
+        -1
+    } else {
+        return 0;
+    };
+
+    // 解 y(t) = point.y
+    let a = y0 - 2.0 * curve.p1.y + y2;
+    let b = 2.0 * (curve.p1.y - y0);
+    let c = y0 - point.y;
+    let t = if a.abs() < 1e-12 * (b.abs() + c.abs()).max(1e-300) {
+        -c / b
+    } else {
+        let sqrt_disc = (b * b - 4.0 * a * c).max(0.0).sqrt();
+        let q = -0.5 * (b + b.signum() * sqrt_disc);
+        let roots = [q / a, if q != 0.0 { c / q } else { f64::NAN }];
+        roots
+            .into_iter()
+            .filter(|t| t.is_finite())
+            .min_by(|x, y| {
+                (x.clamp(0.0, 1.0) - x)
+                    .abs()
+                    .total_cmp(&(y.clamp(0.0, 1.0) - y).abs())
+            })
+            .unwrap_or(0.0)
+    };
+    if curve.evaluate(t.clamp(0.0, 1.0)).x > point.x {
+        direction
+    } else {
+        0
+    }
+}
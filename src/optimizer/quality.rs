@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::{Point2D, Transform2D};
+
+use super::dp::{DPOptimizer, FitResult};
+
+/// 拟合质量的汇总统计；误差均为点到曲线的距离（不是平方）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FitQuality {
+    pub rmse: f64,
+    pub max_error: f64,
+    /// 95% 分位数（最近秩法）
+    pub p95_error: f64,
+    /// 输入点数与输出控制点数（相邻分段共用端点）之比
+    pub compression_ratio: f64,
+    /// 逐段统计；分段下标与输入不匹配时为空
+    pub per_segment: Vec<SegmentQuality>,
+}
+
+/// 两条路径之间的距离度量，见 [`FitResult::distance_to`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathMetric {
+    /// 双向 Hausdorff 距离，与走向无关
+    Hausdorff,
+    /// 离散 Fréchet 距离，考虑沿路径的先后顺序，反向的同一路径距离不为零
+    Frechet,
+}
+
+impl std::str::FromStr for PathMetric {
+    type Err = String;
+
+    /// 不区分大小写的 "hausdorff" / "frechet"
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "hausdorff" => Ok(PathMetric::Hausdorff),
+            "frechet" => Ok(PathMetric::Frechet),
+            _ => Err(format!(
+                "unknown path metric: {name} (expected hausdorff or frechet)"
+            )),
+        }
+    }
+}
+
+/// 变换等变性检查的结果，由 [`FitResult::transform_equivariance_check`] 返回
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquivarianceReport {
+    /// 变换后重新拟合的分段下标与原结果相同
+    pub same_breakpoints: bool,
+    /// 重新拟合的路径与变换后的原路径之间的 Hausdorff 距离
+    pub path_distance: f64,
+    /// 路径距离的容差：变换后包围盒对角线（至少为 1）的 1e-6 倍
+    pub tolerance: f64,
+}
+
+impl EquivarianceReport {
+    pub fn passed(&self) -> bool {
+        self.same_breakpoints && self.path_distance <= self.tolerance
+    }
+}
+
+/// 计算路径距离时每条路径按弧长均匀采样的点数
+const PATH_SAMPLES: usize = 512;
+
+/// 单个分段的误差统计，分段边界上的点归入前一段
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SegmentQuality {
+    pub start_index: usize,
+    pub end_index: usize,
+    pub rmse: f64,
+    pub max_error: f64,
+}
+
+impl FitResult {
+    /// 按拟合时的输入点计算误差统计，省去调用方重新采样和计算
+    pub fn quality(&self, points: &[Point2D]) -> FitQuality {
+        let residuals = self.residuals(points);
+        let stats = |r: &[f64]| {
+            if r.is_empty() {
+                return (0.0, 0.0);
+            }
+            let mse = r.iter().map(|e| e * e).sum::<f64>() / r.len() as f64;
+            (mse.sqrt(), r.iter().copied().fold(0.0, f64::max))
+        };
+        let (rmse, max_error) = stats(&residuals);
+
+        let mut sorted = residuals.clone();
+        sorted.sort_by(f64::total_cmp);
+        let p95_error = match sorted.len() {
+            0 => 0.0,
+            n => sorted[((0.95 * n as f64).ceil() as usize).clamp(1, n) - 1],
+        };
+
+        let control_points = match self.curves.len() {
+            0 => 0,
+            k => 2 * k + 1,
+        };
+        let compression_ratio = if control_points > 0 {
+            points.len() as f64 / control_points as f64
+        } else {
+            0.0
+        };
+
+        let matches_input = self.breakpoints.len() == self.curves.len() + 1
+            && self.breakpoints.last().map(|&b| b + 1) == Some(points.len());
+        let per_segment = if matches_input {
+            self.breakpoints
+                .windows(2)
+                .enumerate()
+                .map(|(k, w)| {
+                    // 与 `residuals` 的归属一致
+                    let start = if k == 0 { 0 } else { w[0] + 1 };
+                    let (rmse, max_error) = stats(&residuals[start..=w[1]]);
+                    SegmentQuality {
+                        start_index: w[0],
+                        end_index: w[1],
+                        rmse,
+                        max_error,
+                    }
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        FitQuality {
+            rmse,
+            max_error,
+            p95_error,
+            compression_ratio,
+            per_segment,
+        }
+    }
+}
+
+impl FitResult {
+    /// 与另一条拟合路径的距离，用于比较不同配置的结果。两条路径各按弧长均匀采样
+    /// `PATH_SAMPLES` 个点后计算，离散化误差不超过较长路径采样间距的一半；
+    /// 两者都无曲线时为 0，只有一方无曲线时为无穷大
+    pub fn distance_to(&self, other: &FitResult, metric: PathMetric) -> f64 {
+        match (self.curves.is_empty(), other.curves.is_empty()) {
+            (true, true) => return 0.0,
+            (true, false) | (false, true) => return f64::INFINITY,
+            _ => {}
+        }
+        let a = resample(self, PATH_SAMPLES);
+        let b = resample(other, PATH_SAMPLES);
+        match metric {
+            PathMetric::Hausdorff => directed_hausdorff(&a, &b).max(directed_hausdorff(&b, &a)),
+            PathMetric::Frechet => discrete_frechet(&a, &b),
+        }
+    }
+}
+
+impl FitResult {
+    /// 验证"先变换再拟合"与"先拟合再变换"一致：对 `points` 施加 `m` 后按同一配置重新拟合，
+    /// 与 `self.transform(m)` 比较。平移、旋转和反射下误差不变，应当通过；缩放会改变误差的尺度，
+    /// 分段可能不同。`points` 须为拟合时的输入
+    pub fn transform_equivariance_check(&self, points: &[Point2D], m: &Transform2D) -> EquivarianceReport {
+        let moved: Vec<Point2D> = points.iter().map(|p| p.transform(m)).collect();
+        let refit = DPOptimizer::optimize(&moved, &self.config);
+        let expected = self.transform(m);
+        let scale = expected.bounding_box().map_or(0.0, |b| b.diagonal()).max(1.0);
+        EquivarianceReport {
+            same_breakpoints: refit.breakpoints == self.breakpoints,
+            path_distance: expected.distance_to(&refit, PathMetric::Hausdorff),
+            tolerance: 1e-6 * scale,
+        }
+    }
+}
+
+/// 沿路径按弧长等距取 n 个点，含首末端点
+fn resample(result: &FitResult, n: usize) -> Vec<Point2D> {
+    let lengths: Vec<f64> = result.curves.iter().map(|c| c.arc_length()).collect();
+    let step = lengths.iter().sum::<f64>() / (n - 1) as f64;
+    let mut samples = Vec::with_capacity(n);
+    let (mut k, mut start) = (0, 0.0);
+    for i in 0..n {
+        let s = i as f64 * step;
+        while k + 1 < lengths.len() && s > start + lengths[k] {
+            start += lengths[k];
+            k += 1;
+        }
+        let curve = &result.curves[k];
+        samples.push(curve.evaluate(curve.parameter_at_length(s - start)));
+    }
+    samples
+}
+
+fn directed_hausdorff(from: &[Point2D], to: &[Point2D]) -> f64 {
+    from.iter()
+        .map(|p| {
+            to.iter()
+                .map(|q| p.distance_squared(q))
+                .fold(f64::INFINITY, f64::min)
+        })
+        .fold(0.0, f64::max)
+        .sqrt()
+}
+
+/// Eiter–Mannila 动态规划，只保留一行
+fn discrete_frechet(a: &[Point2D], b: &[Point2D]) -> f64 {
+    let mut row = vec![0.0f64; b.len()];
+    for (i, p) in a.iter().enumerate() {
+        let mut diagonal = 0.0f64;
+        for (j, q) in b.iter().enumerate() {
+            let d = p.distance_squared(q);
+            let reach = match (i, j) {
+                (0, 0) => 0.0,
+                (0, _) => row[j - 1],
+                (_, 0) => row[0],
+                _ => diagonal.min(row[j]).min(row[j - 1]),
+            };
+            diagonal = row[j];
+            row[j] = d.max(reach);
+        }
+    }
+    row[b.len() - 1].sqrt()
+}
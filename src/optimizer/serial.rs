@@ -0,0 +1,82 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::fitting::BezierFitter;
+use crate::geometry::{Point2D, QuadraticBezier};
+
+/// `fit_curve_serial` 的结果
+#[derive(Debug, Clone)]
+pub struct SerialFit {
+    pub curves: Vec<QuadraticBezier>,
+    /// 分段端点下标，首尾为 0 和 n - 1
+    pub breakpoints: Vec<usize>,
+    /// 各段误差之和
+    pub total_error: f64,
+}
+
+/// 单线程、不建误差缓存的最少段数 DP，只依赖 `alloc`，关闭 `std` feature 后也可用，
+/// 适合在 MCU 上压缩笔画。每段为固定端点的最小二乘二次曲线，点数在
+/// `min_segment_len..=max_segment_len` 内且误差不超过 `max_error`；分段与默认配置下的
+/// `fit_curve` 相同。内存 O(n)，点数不足两个或找不到满足约束的分段时返回 None
+pub fn fit_curve_serial(
+    points: &[Point2D],
+    min_segment_len: usize,
+    max_segment_len: usize,
+    max_error: f64,
+) -> Option<SerialFit> {
+    let n = points.len();
+    if n < 2 {
+        return None;
+    }
+    let min_span = min_segment_len.max(1) - 1;
+    let max_span = max_segment_len.max(1) - 1;
+
+    // 到达每个下标的段数、累计误差、上一个断点和最后一段曲线，不可达时段数为 usize::MAX
+    let mut seg = vec![usize::MAX; n];
+    let mut err = vec![f64::INFINITY; n];
+    let mut parent = vec![0; n];
+    let mut last_curve: Vec<Option<QuadraticBezier>> = vec![None; n];
+    seg[0] = 0;
+    err[0] = 0.0;
+
+    for i in min_segment_len.max(1)..n {
+        let first = i.saturating_sub(max_span);
+        let last = i.saturating_sub(min_span).min(i - 1);
+        for j in first..=last {
+            if seg[j] == usize::MAX {
+                continue;
+            }
+            let fit = BezierFitter::fit_segment_with_limit(&points[j..=i], max_error);
+            let error = BezierFitter::apply_limit(fit.error, max_error);
+            if error > max_error {
+                continue;
+            }
+            // 段数少者优先，其次误差小者；完全相同时保留较小的起点，与 fit_curve 一致
+            let cand = (seg[j] + 1, err[j] + error);
+            if seg[i] == usize::MAX || cand < (seg[i], err[i]) {
+                (seg[i], err[i]) = cand;
+                parent[i] = j;
+                last_curve[i] = Some(fit.bezier);
+            }
+        }
+    }
+
+    if seg[n - 1] == usize::MAX {
+        return None;
+    }
+    let mut curves = Vec::with_capacity(seg[n - 1]);
+    let mut breakpoints = vec![n - 1];
+    let mut end = n - 1;
+    while end > 0 {
+        curves.push(last_curve[end]?);
+        end = parent[end];
+        breakpoints.push(end);
+    }
+    curves.reverse();
+    breakpoints.reverse();
+    Some(SerialFit {
+        curves,
+        breakpoints,
+        total_error: err[n - 1],
+    })
+}
@@ -0,0 +1,69 @@
+use crate::geometry::Point2D;
+
+use super::cache::ErrorCache;
+use super::config::{EndpointPolicy, FitConfig};
+use super::dp::{DPOptimizer, FitResult};
+
+/// 点集不断追加时的增量拟合：已缓存的前缀区间误差保持有效，
+/// 每次 `finalize` 只补算涉及新点的区间
+#[derive(Debug, Clone)]
+pub struct StreamingFitter {
+    config: FitConfig,
+    points: Vec<Point2D>,
+    /// 上次拟合时预处理后的点，用于判断缓存中哪些区间已失效
+    prepared: Vec<Point2D>,
+    cache: ErrorCache,
+}
+
+impl StreamingFitter {
+    pub fn new(config: FitConfig) -> Self {
+        Self {
+            config,
+            points: Vec::new(),
+            prepared: Vec::new(),
+            cache: ErrorCache::new(),
+        }
+    }
+
+    pub fn push(&mut self, point: Point2D) {
+        self.points.push(point);
+    }
+
+    pub fn extend(&mut self, points: &[Point2D]) {
+        self.points.extend_from_slice(points);
+    }
+
+    pub fn points(&self) -> &[Point2D] {
+        &self.points
+    }
+
+    pub fn config(&self) -> &FitConfig {
+        &self.config
+    }
+
+    pub fn cache(&self) -> &ErrorCache {
+        &self.cache
+    }
+
+    /// 对当前全部点拟合；可在追加点后反复调用
+    pub fn finalize(&mut self) -> FitResult {
+        // 平滑和去重会改动尾部附近的预处理结果，只保留完全落在未变前缀内的区间；
+        // `LocalAverage` 的端点取到区间之后 `radius` 个点，这些点也须未变
+        let (prepared, _, _) = DPOptimizer::preprocess(&self.points, &self.config);
+        let prepared = prepared.unwrap_or_else(|| self.points.clone());
+        let unchanged = self
+            .prepared
+            .iter()
+            .zip(&prepared)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let reach = match self.config.endpoint_policy {
+            EndpointPolicy::LocalAverage { radius } => radius,
+            EndpointPolicy::Fixed | EndpointPolicy::Free => 0,
+        };
+        self.cache.retain(|_, end| end + reach < unchanged);
+        self.prepared = prepared;
+
+        DPOptimizer::optimize_with_cache(&self.points, &self.config, &mut self.cache)
+    }
+}
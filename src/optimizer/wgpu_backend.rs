@@ -0,0 +1,324 @@
+#![cfg(feature = "gpu-wgpu")]
+
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use crate::fitting::{BezierFitter, FitError};
+use crate::geometry::Point2D;
+use crate::optimizer::cache::ErrorCache;
+use crate::optimizer::config::FitConfig;
+use crate::optimizer::intervals::IntervalIter;
+
+const WORKGROUP_SIZE: u32 = 64;
+
+const WGSL_SRC: &str = r#"
+struct Segment {
+    p0: vec2<f32>,
+    p1: vec2<f32>,
+    p2: vec2<f32>,
+    range: vec2<u32>,
+}
+
+struct Params {
+    max_error: f32,
+    count: u32,
+    _pad: vec2<u32>,
+}
+
+@group(0) @binding(0) var<storage, read> points: array<vec2<f32>>;
+@group(0) @binding(1) var<storage, read> segments: array<Segment>;
+@group(0) @binding(2) var<storage, read_write> errors: array<f32>;
+@group(0) @binding(3) var<uniform> params: Params;
+
+@compute @workgroup_size(64)
+fn compute_errors(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let idx = gid.x;
+    if (idx >= params.count) {
+        return;
+    }
+
+    let seg = segments[idx];
+    let start = seg.range.x;
+    let end = seg.range.y;
+    let len = f32(end - start + 1u);
+
+    let curve_len = distance(seg.p0, seg.p1) + distance(seg.p1, seg.p2);
+    let samples = u32(clamp(curve_len / 2.0, 50.0, 200.0));
+    let denom = f32(samples - 1u);
+
+    let max_sum = params.max_error * len;
+    var sum = 0.0;
+
+    for (var i = start; i <= end; i++) {
+        let p = points[i];
+        var min_d2 = 3.0e38;
+
+        for (var s = 0u; s < samples; s++) {
+            let t = f32(s) / denom;
+            let mt = 1.0 - t;
+            let b = mt * mt * seg.p0 + 2.0 * mt * t * seg.p1 + t * t * seg.p2;
+            let d = b - p;
+            min_d2 = min(min_d2, dot(d, d));
+        }
+
+        sum += min_d2;
+        if (sum > max_sum) {
+            // WGSL 没有无穷大字面量，写入单精度最大值，由主机端按 apply_limit 记为正无穷
+            errors[idx] = 3.4028235e38;
+            return;
+        }
+    }
+
+    errors[idx] = sum / len;
+}
+"#;
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct GpuSegment {
+    p0: [f32; 2],
+    p1: [f32; 2],
+    p2: [f32; 2],
+    range: [u32; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Params {
+    max_error: f32,
+    count: u32,
+    _pad: [u32; 2],
+}
+
+/// 按配置取共享引擎计算误差缓存
+pub fn compute_error_cache_wgpu(
+    points: &[Point2D],
+    config: &FitConfig,
+) -> Result<ErrorCache, String> {
+    WgpuEngine::shared()?.compute_error_cache(points, config)
+}
+
+/// wgpu 计算后端（Metal / Vulkan / DX12），WGSL 只保证单精度，误差始终按 f32 计算
+pub struct WgpuEngine {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    adapter_name: String,
+}
+
+impl WgpuEngine {
+    pub fn new() -> Result<Self, String> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: false,
+            compatible_surface: None,
+        }))
+        .map_err(|e| format!("wgpu adapter: {e}"))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("bezier_dp_fit"),
+            required_features: wgpu::Features::empty(),
+            required_limits: adapter.limits(),
+            ..Default::default()
+        }))
+        .map_err(|e| format!("wgpu device: {e}"))?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("compute_errors"),
+            source: wgpu::ShaderSource::Wgsl(WGSL_SRC.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("compute_errors"),
+            layout: None,
+            module: &module,
+            entry_point: Some("compute_errors"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+            adapter_name: adapter.get_info().name,
+        })
+    }
+
+    /// 进程内共享的引擎，首次使用时初始化；初始化失败不缓存，下次调用会重试
+    pub fn shared() -> Result<Arc<WgpuEngine>, String> {
+        static ENGINE: OnceLock<Mutex<Option<Arc<WgpuEngine>>>> = OnceLock::new();
+        let mut engine = ENGINE
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(engine) = engine.as_ref() {
+            return Ok(engine.clone());
+        }
+        let created = Arc::new(Self::new()?);
+        *engine = Some(created.clone());
+        Ok(created)
+    }
+
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+
+    pub fn compute_error_cache(
+        &self,
+        points: &[Point2D],
+        config: &FitConfig,
+    ) -> Result<ErrorCache, String> {
+        let n = points.len();
+        if n == 0 {
+            return Ok(ErrorCache::new());
+        }
+
+        let mut intervals = IntervalIter::new(n, config).peekable();
+        if intervals.peek().is_none() {
+            return Ok(ErrorCache::new());
+        }
+
+        let limits = self.device.limits();
+        let max_binding = limits.max_storage_buffer_binding_size as usize;
+        if n * std::mem::size_of::<[f32; 2]>() > max_binding {
+            return Err(format!("wgpu storage buffer too small for {n} points"));
+        }
+        let chunk_len = (max_binding / std::mem::size_of::<GpuSegment>())
+            .min(limits.max_compute_workgroups_per_dimension as usize * WORKGROUP_SIZE as usize);
+
+        // 以首点为原点再转单精度，减少大坐标下的精度损失
+        let origin = points[0];
+        let local = |p: &Point2D| [(p.x - origin.x) as f32, (p.y - origin.y) as f32];
+        let gpu_points: Vec<[f32; 2]> = points.iter().map(local).collect();
+        let points_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("points"),
+                contents: bytemuck::cast_slice(&gpu_points),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+
+        let mut cache = ErrorCache::new();
+        while intervals.peek().is_some() {
+            let chunk: Vec<(usize, usize)> = intervals.by_ref().take(chunk_len).collect();
+            let beziers: Vec<_> = chunk
+                .iter()
+                .map(|&(start, end)| BezierFitter::compute_bezier(&points[start..=end]))
+                .collect();
+            let segments: Vec<GpuSegment> = chunk
+                .iter()
+                .zip(&beziers)
+                .map(|(&(start, end), b)| GpuSegment {
+                    p0: local(&b.p0),
+                    p1: local(&b.p1),
+                    p2: local(&b.p2),
+                    range: [start as u32, end as u32],
+                })
+                .collect();
+            let errors = self.launch_chunk(&points_buffer, &segments, config.max_error as f32)?;
+            for ((&(start, end), bezier), error) in chunk.iter().zip(beziers).zip(errors) {
+                cache.insert(
+                    start,
+                    end,
+                    FitError {
+                        bezier,
+                        error: BezierFitter::apply_limit(error as f64, config.max_error),
+                        arc: None,
+                    },
+                );
+            }
+        }
+
+        Ok(cache)
+    }
+
+    /// 上传一块区间并派发计算，同步读回各区间误差
+    fn launch_chunk(
+        &self,
+        points_buffer: &wgpu::Buffer,
+        segments: &[GpuSegment],
+        max_error: f32,
+    ) -> Result<Vec<f32>, String> {
+        let device = &self.device;
+        let count = segments.len() as u32;
+        let out_size = (segments.len() * std::mem::size_of::<f32>()) as wgpu::BufferAddress;
+
+        let segments_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("segments"),
+            contents: bytemuck::cast_slice(segments),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("params"),
+            contents: bytemuck::bytes_of(&Params {
+                max_error,
+                count,
+                _pad: [0; 2],
+            }),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let out_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("errors"),
+            size: out_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: out_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: points_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: segments_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: out_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(count.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&out_buffer, 0, &readback, 0, out_size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let (tx, rx) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |r| {
+            let _ = tx.send(r);
+        });
+        device
+            .poll(wgpu::PollType::Wait)
+            .map_err(|e| format!("wgpu poll: {e}"))?;
+        rx.recv()
+            .map_err(|e| format!("wgpu map: {e}"))?
+            .map_err(|e| format!("wgpu map: {e}"))?;
+
+        let errors = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+        readback.unmap();
+        Ok(errors)
+    }
+}
@@ -0,0 +1,45 @@
+// 并行迭代的薄封装：启用 `parallel` feature 时就是 rayon 的 prelude；关闭时同名方法退化为
+// 普通迭代器，调用方不用区分两种构建。结果与并行版一致（归约的比较规则与顺序无关）
+
+#[cfg(feature = "parallel")]
+pub(crate) use rayon::prelude::*;
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) trait IntoParallelRefIterator<'a> {
+    type Iter: Iterator;
+
+    fn par_iter(&'a self) -> Self::Iter;
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<'a, T: 'a> IntoParallelRefIterator<'a> for [T] {
+    type Iter = core::slice::Iter<'a, T>;
+
+    fn par_iter(&'a self) -> Self::Iter {
+        self.iter()
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) trait IntoParallelIterator: IntoIterator + Sized {
+    fn into_par_iter(self) -> Self::IntoIter {
+        self.into_iter()
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<I: IntoIterator> IntoParallelIterator for I {}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) trait ParallelIterator: Iterator + Sized {
+    fn with_min_len(self, _min: usize) -> Self {
+        self
+    }
+
+    fn reduce_with(self, op: impl FnMut(Self::Item, Self::Item) -> Self::Item) -> Option<Self::Item> {
+        self.reduce(op)
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<I: Iterator> ParallelIterator for I {}
@@ -0,0 +1,27 @@
+use crate::geometry::Point2D;
+
+/// 转角检测：内部点处前后两个方向的夹角（弧度）不小于 `angle_threshold` 时视为角点。
+/// 方向取前后最近的不重合点，返回按顺序排列的下标，可直接用作 `required_breakpoints`
+pub fn detect_corners(points: &[Point2D], angle_threshold: f64) -> Vec<usize> {
+    let n = points.len();
+    let mut corners = Vec::new();
+    for i in 1..n.saturating_sub(1) {
+        let p = points[i];
+        if p == points[i - 1] {
+            continue; // 重复点只在第一次出现时报告
+        }
+        let prev = points[..i].iter().rev().find(|q| **q != p);
+        let next = points[i + 1..].iter().find(|q| **q != p);
+        let (Some(prev), Some(next)) = (prev, next) else {
+            continue;
+        };
+
+        let (ax, ay) = (p.x - prev.x, p.y - prev.y);
+        let (bx, by) = (next.x - p.x, next.y - p.y);
+        let turn = (ax * by - ay * bx).atan2(ax * bx + ay * by).abs();
+        if turn >= angle_threshold {
+            corners.push(i);
+        }
+    }
+    corners
+}
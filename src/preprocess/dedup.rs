@@ -0,0 +1,39 @@
+use crate::geometry::Point2D;
+
+/// 去除与上一个保留点距离小于 `min_distance` 的点（`min_distance` 为 0 时只去除完全重复的点），
+/// 返回保留点在原始输入中的下标。首尾点总会保留
+pub fn dedup_indices(points: &[Point2D], min_distance: f64) -> Vec<usize> {
+    let n = points.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let min_d2 = min_distance * min_distance;
+    let mut kept = vec![0];
+    for i in 1..n {
+        let last = &points[*kept.last().unwrap()];
+        let d2 = points[i].distance_squared(last);
+        if d2 > min_d2 || (min_d2 == 0.0 && d2 > 0.0) {
+            kept.push(i);
+        }
+    }
+
+    // 末点过近被丢弃时，用它替换最后一个保留点以保持终点不变
+    let tail = *kept.last().unwrap();
+    if tail != n - 1 {
+        if kept.len() > 1 {
+            *kept.last_mut().unwrap() = n - 1;
+        } else {
+            kept.push(n - 1);
+        }
+    }
+    kept
+}
+
+/// 去除重复和近似重复的点
+pub fn dedup(points: &[Point2D], min_distance: f64) -> Vec<Point2D> {
+    dedup_indices(points, min_distance)
+        .into_iter()
+        .map(|i| points[i])
+        .collect()
+}
@@ -0,0 +1,17 @@
+use crate::geometry::Point2D;
+
+/// 相邻采样点间距超过 `max_gap` 处切开（如数位板导出的多笔画拼接数组），返回各段折线
+pub fn split_on_gaps(points: &[Point2D], max_gap: f64) -> Vec<Vec<Point2D>> {
+    let mut strokes = Vec::new();
+    let mut start = 0;
+    for i in 1..points.len() {
+        if points[i].distance_to(&points[i - 1]) > max_gap {
+            strokes.push(points[start..i].to_vec());
+            start = i;
+        }
+    }
+    if start < points.len() {
+        strokes.push(points[start..].to_vec());
+    }
+    strokes
+}
@@ -0,0 +1,14 @@
+pub mod corners;
+pub mod dedup;
+pub mod gaps;
+pub mod simplify;
+pub mod smooth;
+pub mod validate;
+
+pub use corners::detect_corners;
+pub use dedup::{dedup, dedup_indices};
+pub use gaps::split_on_gaps;
+pub use simplify::{simplify, simplify_indices};
+pub use smooth::{smooth_gaussian, smooth_moving_average, Smoothing};
+
+pub use validate::{filter_non_finite, validate_points};
\ No newline at end of file
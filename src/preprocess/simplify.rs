@@ -0,0 +1,46 @@
+use crate::geometry::Point2D;
+
+/// Ramer–Douglas–Peucker 折线简化，返回保留点的下标；首尾点总会保留
+pub fn simplify_indices(points: &[Point2D], epsilon: f64) -> Vec<usize> {
+    let n = points.len();
+    if n < 3 {
+        return (0..n).collect();
+    }
+
+    let mut keep = vec![false; n];
+    keep[0] = true;
+    keep[n - 1] = true;
+
+    // 用显式栈代替递归，长折线不会爆栈
+    let mut stack = vec![(0, n - 1)];
+    while let Some((start, end)) = stack.pop() {
+        let (farthest, distance) = (start + 1..end)
+            .map(|i| (i, distance_to_chord(&points[i], &points[start], &points[end])))
+            .fold((start, 0.0), |best, cur| if cur.1 > best.1 { cur } else { best });
+        if distance > epsilon {
+            keep[farthest] = true;
+            stack.push((start, farthest));
+            stack.push((farthest, end));
+        }
+    }
+
+    (0..n).filter(|&i| keep[i]).collect()
+}
+
+/// Ramer–Douglas–Peucker 折线简化
+pub fn simplify(points: &[Point2D], epsilon: f64) -> Vec<Point2D> {
+    simplify_indices(points, epsilon)
+        .into_iter()
+        .map(|i| points[i])
+        .collect()
+}
+
+fn distance_to_chord(p: &Point2D, a: &Point2D, b: &Point2D) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len2 = dx * dx + dy * dy;
+    if len2 < 1e-20 {
+        return p.distance_to(a);
+    }
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / len2).clamp(0.0, 1.0);
+    p.distance_to(&a.lerp(b, t))
+}
@@ -0,0 +1,70 @@
+use crate::geometry::Point2D;
+use serde::{Deserialize, Serialize};
+
+/// 拟合前的平滑方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Smoothing {
+    Gaussian { sigma: f64 },
+    MovingAverage { window: usize },
+}
+
+impl Smoothing {
+    pub fn apply(&self, points: &[Point2D]) -> Vec<Point2D> {
+        match *self {
+            Smoothing::Gaussian { sigma } => smooth_gaussian(points, sigma),
+            Smoothing::MovingAverage { window } => smooth_moving_average(points, window),
+        }
+    }
+}
+
+/// 高斯平滑，核半径取 3σ，但不超过点数减一；首尾端点保持不动，边界处按复制端点延拓。
+/// σ 不是有限正数时原样返回
+pub fn smooth_gaussian(points: &[Point2D], sigma: f64) -> Vec<Point2D> {
+    if points.len() < 3 || !(sigma > 0.0 && sigma.is_finite()) {
+        return points.to_vec();
+    }
+
+    let radius = (3.0 * sigma).ceil().min((points.len() - 1) as f64) as isize;
+    let kernel: Vec<f64> = (-radius..=radius)
+        .map(|k| {
+            let k = k as f64;
+            (-(k * k) / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+    let norm: f64 = kernel.iter().sum();
+
+    convolve(points, radius, |k| kernel[(k + radius) as usize] / norm)
+}
+
+/// 滑动平均，窗口取奇数（偶数会加一），半径不超过点数减一；首尾端点保持不动
+pub fn smooth_moving_average(points: &[Point2D], window: usize) -> Vec<Point2D> {
+    if points.len() < 3 || window < 2 {
+        return points.to_vec();
+    }
+
+    let radius = (window / 2).min(points.len() - 1) as isize;
+    let weight = 1.0 / (2 * radius + 1) as f64;
+    convolve(points, radius, |_| weight)
+}
+
+fn convolve(points: &[Point2D], radius: isize, weight: impl Fn(isize) -> f64) -> Vec<Point2D> {
+    let n = points.len();
+    let last = n as isize - 1;
+
+    let mut out: Vec<Point2D> = (0..n)
+        .map(|i| {
+            let (mut x, mut y) = (0.0, 0.0);
+            for k in -radius..=radius {
+                let p = &points[(i as isize + k).clamp(0, last) as usize];
+                let w = weight(k);
+                x += w * p.x;
+                y += w * p.y;
+            }
+            Point2D::new(x, y)
+        })
+        .collect();
+
+    out[0] = points[0];
+    out[n - 1] = points[n - 1];
+    out
+}
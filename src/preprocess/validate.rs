@@ -0,0 +1,18 @@
+use crate::geometry::Point2D;
+use crate::optimizer::FitCurveError;
+
+/// 检查所有坐标均为有限值，返回第一个非法点
+pub fn validate_points(points: &[Point2D]) -> Result<(), FitCurveError> {
+    match points.iter().position(|p| !p.is_finite()) {
+        Some(index) => Err(FitCurveError::NonFinitePoint {
+            index,
+            point: points[index],
+        }),
+        None => Ok(()),
+    }
+}
+
+/// 丢弃含 NaN / 无穷大坐标的点
+pub fn filter_non_finite(points: &[Point2D]) -> Vec<Point2D> {
+    points.iter().copied().filter(Point2D::is_finite).collect()
+}
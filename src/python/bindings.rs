@@ -1,11 +1,13 @@
 use pyo3::prelude::*;
-use pyo3::types::PyList;
-use numpy::{PyArray2, PyArrayMethods, PyUntypedArrayMethods};
+use pyo3::types::{PyBytes, PyDict, PyIterator, PyList, PyTuple};
+use numpy::ndarray::{Array2, Array3};
+use numpy::{Element, IntoPyArray, PyArray1, PyArray2, PyArray3, PyArrayMethods, PyReadonlyArray2};
 
-use crate::geometry::Point2D;
-use crate::optimizer::{FitConfig, fit_curve};
+use crate::geometry::{Point2D, Segment};
+use crate::optimizer::{FitParams, try_fit_curve};
+use crate::preprocess;
 
-#[pyclass]
+#[pyclass(module = "bezier_dp_fit")]
 #[derive(Clone)]
 pub struct PyFitResult {
     #[pyo3(get)]
@@ -15,8 +17,118 @@ pub struct PyFitResult {
     inner: crate::optimizer::FitResult,
 }
 
+/// 单个拟合分段，由 `PyFitResult` 的下标访问和迭代得到
+#[pyclass(module = "bezier_dp_fit")]
+#[derive(Clone)]
+pub struct PyFitSegment {
+    /// 控制点 `[(x0,y0), (x1,y1), (x2,y2)]`
+    #[pyo3(get)]
+    pub control_points: Vec<(f64, f64)>,
+    /// 单段误差，旧版本导出的结果中可能缺失
+    #[pyo3(get)]
+    pub error: Option<f64>,
+    /// 覆盖的输入点下标范围 `start_index..=end_index`
+    #[pyo3(get)]
+    pub start_index: Option<usize>,
+    #[pyo3(get)]
+    pub end_index: Option<usize>,
+    /// 弧长和范围内输入点的最大偏差，旧版本导出的结果中可能缺失
+    #[pyo3(get)]
+    pub arc_length: Option<f64>,
+    #[pyo3(get)]
+    pub max_deviation: Option<f64>,
+}
+
+#[pymethods]
+impl PyFitSegment {
+    fn __repr__(&self) -> String {
+        let range = match (self.start_index, self.end_index) {
+            (Some(start), Some(end)) => format!("{start}..={end}"),
+            _ => "?".to_string(),
+        };
+        let error = self.error.map_or("None".to_string(), |e| format!("{e:.4}"));
+        format!("FitSegment(points={range}, error={error})")
+    }
+}
+
+impl PyFitResult {
+    fn segment(&self, index: usize) -> PyFitSegment {
+        let result = &self.inner;
+        let has_breakpoints = result.breakpoints.len() == result.curves.len() + 1;
+        PyFitSegment {
+            control_points: result.curves[index].control_points().to_vec(),
+            error: result.segment_errors.get(index).copied(),
+            start_index: has_breakpoints.then(|| result.breakpoints[index]),
+            end_index: has_breakpoints.then(|| result.breakpoints[index + 1]),
+            arc_length: result.segment_info.get(index).map(|info| info.arc_length),
+            max_deviation: result.segment_info.get(index).map(|info| info.max_deviation),
+        }
+    }
+}
+
+impl From<crate::optimizer::FitResult> for PyFitResult {
+    fn from(result: crate::optimizer::FitResult) -> Self {
+        Self {
+            total_error: result.total_error,
+            num_segments: result.num_segments,
+            inner: result,
+        }
+    }
+}
+
 #[pymethods]
 impl PyFitResult {
+    /// 从 `__reduce__` 导出的字节恢复，供 pickle / multiprocessing 使用
+    #[new]
+    fn new(state: &[u8]) -> PyResult<Self> {
+        crate::optimizer::FitResult::from_bytes(state)
+            .map(Self::from)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyTuple>)> {
+        let py = slf.py();
+        let bytes = slf
+            .borrow()
+            .inner
+            .to_bytes()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let args = PyTuple::new(py, [PyBytes::new(py, &bytes)])?;
+        Ok((slf.get_type().into_any(), args))
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.curves.len()
+    }
+
+    /// 第 index 段，支持负数下标
+    fn __getitem__(&self, index: isize) -> PyResult<PyFitSegment> {
+        let len = self.inner.curves.len() as isize;
+        let i = if index < 0 { index + len } else { index };
+        if i < 0 || i >= len {
+            return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(
+                "segment index out of range",
+            ));
+        }
+        Ok(self.segment(i as usize))
+    }
+
+    fn __iter__<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyIterator>> {
+        let segments: Vec<PyFitSegment> = {
+            let this = slf.borrow();
+            (0..this.inner.curves.len()).map(|i| this.segment(i)).collect()
+        };
+        PyList::new(slf.py(), segments)?.try_iter()
+    }
+
+    /// 按序列化后的内容逐位比较
+    fn __eq__(&self, other: &Self) -> bool {
+        match (self.inner.to_bytes(), other.inner.to_bytes()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+
     /// 获取控制点列表
     fn control_points(&self) -> Vec<Vec<(f64, f64)>> {
         self.inner
@@ -26,6 +138,70 @@ impl PyFitResult {
             .collect()
     }
 
+    /// 控制点数组，形状为 (段数, 3, 2)
+    fn control_points_array<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray3<f64>> {
+        let flat: Vec<f64> = self
+            .inner
+            .control_points()
+            .iter()
+            .flat_map(|points| points.iter().flat_map(|&(x, y)| [x, y]))
+            .collect();
+        Array3::from_shape_vec((self.inner.curves.len(), 3, 2), flat)
+            .expect("three control points per curve")
+            .into_pyarray(py)
+    }
+
+    /// 采样点数组，形状为 (M, 2)，不经过 Python 元组
+    fn sample_points_array<'py>(
+        &self,
+        py: Python<'py>,
+        points_per_segment: usize,
+    ) -> Bound<'py, PyArray2<f64>> {
+        let samples = self.inner.sample_points(points_per_segment);
+        let flat: Vec<f64> = samples.iter().flat_map(|&(x, y)| [x, y]).collect();
+        Array2::from_shape_vec((samples.len(), 2), flat)
+            .expect("two coordinates per point")
+            .into_pyarray(py)
+    }
+
+    /// `matplotlib.path.Path(vertices, codes)` 所需的顶点 (M, 2) 和指令码；
+    /// 直线段用 LINETO，其余（含圆弧的二次近似）用 CURVE3
+    fn to_matplotlib_path<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> (Bound<'py, PyArray2<f64>>, Bound<'py, PyArray1<u8>>) {
+        const MOVETO: u8 = 1;
+        const LINETO: u8 = 2;
+        const CURVE3: u8 = 3;
+
+        let mut vertices: Vec<f64> = Vec::new();
+        let mut codes: Vec<u8> = Vec::new();
+        let mut push = |p: Point2D, code: u8| {
+            vertices.extend([p.x, p.y]);
+            codes.push(code);
+        };
+        let mut last: Option<Point2D> = None;
+        for (i, curve) in self.inner.curves.iter().enumerate() {
+            if last != Some(curve.p0) {
+                push(curve.p0, MOVETO);
+            }
+            match self.inner.segments.get(i) {
+                Some(Segment::Line { end, .. }) => push(*end, LINETO),
+                _ => {
+                    push(curve.p1, CURVE3);
+                    push(curve.p2, CURVE3);
+                }
+            }
+            last = Some(curve.p2);
+        }
+
+        let rows = codes.len();
+        let vertices = Array2::from_shape_vec((rows, 2), vertices)
+            .expect("two coordinates per vertex")
+            .into_pyarray(py);
+        (vertices, codes.into_pyarray(py))
+    }
+
     /// 转换为SVG路径
     fn to_svg(&self) -> String {
         self.inner.to_svg_path()
@@ -36,6 +212,37 @@ impl PyFitResult {
         self.inner.sample_points(points_per_segment)
     }
 
+    /// 误差统计：`rmse`、`max_error`、`p95_error`、`compression_ratio` 和逐段的 `per_segment`，
+    /// `points` 须为拟合时的输入点
+    fn quality<'py>(&self, py: Python<'py>, points: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyDict>> {
+        let quality = self.inner.quality(&parse_points(points)?);
+        let per_segment = PyList::empty(py);
+        for seg in &quality.per_segment {
+            let item = PyDict::new(py);
+            item.set_item("start_index", seg.start_index)?;
+            item.set_item("end_index", seg.end_index)?;
+            item.set_item("rmse", seg.rmse)?;
+            item.set_item("max_error", seg.max_error)?;
+            per_segment.append(item)?;
+        }
+        let dict = PyDict::new(py);
+        dict.set_item("rmse", quality.rmse)?;
+        dict.set_item("max_error", quality.max_error)?;
+        dict.set_item("p95_error", quality.p95_error)?;
+        dict.set_item("compression_ratio", quality.compression_ratio)?;
+        dict.set_item("per_segment", per_segment)?;
+        Ok(dict)
+    }
+
+    /// 与另一个拟合结果的路径距离，`metric` 为 "hausdorff"（默认）或 "frechet"
+    #[pyo3(signature = (other, metric = "hausdorff"))]
+    fn distance_to(&self, other: &Self, metric: &str) -> PyResult<f64> {
+        let metric = metric
+            .parse()
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        Ok(self.inner.distance_to(&other.inner, metric))
+    }
+
     /// 转JSON
     fn to_json(&self) -> PyResult<String> {
         self.inner
@@ -51,60 +258,141 @@ impl PyFitResult {
     }
 }
 
-/// Python接口：拟合曲线
+/// Python接口：拟合曲线；`preset` 选择预设配置，显式给出的参数覆盖预设值。
+/// `backend` 为 "auto" / "cpu" / "cuda" / "wgpu" / "cuda-verified" / "opencl"，`metric` 为 "mse" / "max"，
+/// `presmooth` 为高斯平滑的 sigma；目前只支持 `degree=2`
 #[pyfunction]
-#[pyo3(signature = (points, min_segment_len=30, max_segment_len=200, max_error=2.0))]
+#[pyo3(signature = (
+    points,
+    min_segment_len=None,
+    max_segment_len=None,
+    max_error=None,
+    preset=None,
+    backend=None,
+    metric=None,
+    presmooth=None,
+    degree=2
+))]
+#[allow(clippy::too_many_arguments)]
 pub fn fit_curve_py(
     points: &Bound<'_, PyAny>,
-    min_segment_len: usize,
-    max_segment_len: usize,
-    max_error: f64,
+    min_segment_len: Option<usize>,
+    max_segment_len: Option<usize>,
+    max_error: Option<f64>,
+    preset: Option<String>,
+    backend: Option<String>,
+    metric: Option<String>,
+    presmooth: Option<f64>,
+    degree: usize,
 ) -> PyResult<PyFitResult> {
+    if degree != 2 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "unsupported degree {degree}: only quadratic (2) curves are fitted"
+        )));
+    }
+
     // 解析输入点
     let pts = parse_points(points)?;
 
-    // 配置（自动修正无效参数）
-    let config = FitConfig::new_clamped(min_segment_len, max_segment_len, max_error);
+    // 配置（与命令行、HTTP 服务相同的规则，无效参数报错）
+    let config = FitParams {
+        min_segment_len,
+        max_segment_len,
+        max_error,
+        preset,
+        backend,
+        metric,
+        presmooth,
+    }
+    .to_config()
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
 
     // 拟合
-    let result = fit_curve(&pts, &config);
+    let result = try_fit_curve(&pts, &config)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
 
-    Ok(PyFitResult {
-        total_error: result.total_error,
-        num_segments: result.num_segments,
-        inner: result,
-    })
+    Ok(PyFitResult::from(result))
 }
 
-/// 解析Python输入的点（支持列表和numpy数组）
-fn parse_points(obj: &Bound<'_, PyAny>) -> PyResult<Vec<Point2D>> {
-    // 尝试作为numpy数组
-    if let Ok(arr) = obj.cast::<PyArray2<f64>>() {
-        let readonly = arr.readonly();
-        let shape = readonly.shape();
-        let (rows, cols) = match shape {
-            [rows, cols] => (*rows, *cols),
-            _ => {
-                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                    "Numpy array must have shape (N, 2)",
-                ));
-            }
-        };
+/// Python接口：转角检测，`angle_threshold` 为弧度，返回角点下标
+#[pyfunction(name = "detect_corners")]
+pub fn detect_corners_py(points: &Bound<'_, PyAny>, angle_threshold: f64) -> PyResult<Vec<usize>> {
+    let pts = parse_points(points)?;
+    Ok(preprocess::detect_corners(&pts, angle_threshold))
+}
+
+/// Python接口：高斯平滑，返回 (N, 2) 数组
+#[pyfunction(name = "smooth")]
+pub fn smooth_py<'py>(
+    py: Python<'py>,
+    points: &Bound<'py, PyAny>,
+    sigma: f64,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    if !sigma.is_finite() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "sigma must be finite",
+        ));
+    }
+    let pts = parse_points(points)?;
+    Ok(points_to_array(py, &preprocess::smooth_gaussian(&pts, sigma)))
+}
+
+/// Python接口：Douglas-Peucker 折线简化，返回 (M, 2) 数组
+#[pyfunction(name = "simplify")]
+pub fn simplify_py<'py>(
+    py: Python<'py>,
+    points: &Bound<'py, PyAny>,
+    epsilon: f64,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    let pts = parse_points(points)?;
+    Ok(points_to_array(py, &preprocess::simplify(&pts, epsilon)))
+}
+
+fn points_to_array<'py>(py: Python<'py>, points: &[Point2D]) -> Bound<'py, PyArray2<f64>> {
+    let flat: Vec<f64> = points.iter().flat_map(|p| [p.x, p.y]).collect();
+    Array2::from_shape_vec((points.len(), 2), flat)
+        .expect("two coordinates per point")
+        .into_pyarray(py)
+}
 
-        if cols != 2 {
+/// 二维数组转点集：C 连续的 (N, 2) 直接按切片读取，其余形状和步长按视图逐行读取；
+/// (2, 2) 视为两个点
+fn points_from_array<T>(arr: &PyReadonlyArray2<'_, T>) -> PyResult<Vec<Point2D>>
+where
+    T: Element + Copy + Into<f64>,
+{
+    let view = arr.as_array();
+    let view = match view.shape() {
+        [_, 2] => view,
+        [2, _] => view.reversed_axes(),
+        _ => {
             return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                "Numpy array must have shape (N, 2)",
+                "Numpy array must have shape (N, 2) or (2, N)",
             ));
         }
+    };
 
-        let mut points = Vec::with_capacity(rows);
-        for i in 0..rows {
-            points.push(Point2D::new(
-                *readonly.get([i, 0]).unwrap(),
-                *readonly.get([i, 1]).unwrap(),
-            ));
-        }
-        return Ok(points);
+    if let Some(flat) = view.as_slice() {
+        return Ok(flat
+            .chunks_exact(2)
+            .map(|c| Point2D::new(c[0].into(), c[1].into()))
+            .collect());
+    }
+    Ok(view
+        .rows()
+        .into_iter()
+        .map(|row| Point2D::new(row[0].into(), row[1].into()))
+        .collect())
+}
+
+/// 解析Python输入的点（支持列表和numpy数组）
+fn parse_points(obj: &Bound<'_, PyAny>) -> PyResult<Vec<Point2D>> {
+    // 尝试作为numpy数组，接受 f64 / f32，(N, 2) 或 (2, N)，以及非连续视图
+    if let Ok(arr) = obj.cast::<PyArray2<f64>>() {
+        return points_from_array(&arr.readonly());
+    }
+    if let Ok(arr) = obj.cast::<PyArray2<f32>>() {
+        return points_from_array(&arr.readonly());
     }
 
     // 尝试作为列表
@@ -0,0 +1,149 @@
+#![cfg(feature = "service")]
+
+//! HTTP 拟合服务：`POST /fit` 接受 JSON 或 `.npy` 点集，返回 `FitResult` 的版本化 JSON（见 `io::json`）。
+//!
+//! JSON 请求体为 `{"points": [[x, y], ...]}` 或 `{"strokes": [[[x, y], ...], ...]}`，
+//! 可附带 [`FitParams`] 的参数（与 Python 接口同名）；多条笔画按 `try_fit_strokes`
+//! 批量拟合，返回结果数组。
+//! `Content-Type: application/x-npy`（或 `application/octet-stream`）时请求体按 `.npy` 解析，
+//! 参数改由查询字符串给出。
+//!
+//! 服务面向不受信任的客户端：`max_segment_len` 不得超过 [`MAX_SEGMENT_LEN`]，误差缓存超过
+//! [`MAX_CACHE_BYTES`] 时改为不建缓存，请求体大小受 axum 默认的 2 MB 上限约束；
+//! 不接受 `backend=cuda-verified`。
+
+use std::net::SocketAddr;
+
+use axum::body::Bytes;
+use axum::extract::Query;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+
+use crate::geometry::Point2D;
+use crate::io::json::v1;
+use crate::io::npy::from_npy;
+use crate::optimizer::{try_fit_curve, try_fit_strokes, Backend, FitConfig, FitParams};
+
+/// 请求允许的最大段长
+pub const MAX_SEGMENT_LEN: usize = 500;
+/// 误差缓存的内存上限（字节，多条笔画批量计算时按总量），写入 `FitConfig::max_cache_bytes`
+pub const MAX_CACHE_BYTES: usize = 256 << 20;
+
+/// 按 `FitParams::to_config` 解析参数（与命令行和 Python 接口相同，无效时报错），
+/// 再加上服务的资源上限：段长超过 `MAX_SEGMENT_LEN` 或后端为 `cuda-verified` 时报错，
+/// 缓存上限取 `MAX_CACHE_BYTES`
+pub fn to_config(params: &FitParams) -> Result<FitConfig, String> {
+    let mut config = params.to_config().map_err(|e| e.to_string())?;
+    if config.max_segment_len > MAX_SEGMENT_LEN {
+        return Err(format!("max_segment_len must be <= {MAX_SEGMENT_LEN}"));
+    }
+    // 抽查区间要在 CPU 上重算，只用于调试 CUDA 内核
+    if config.backend == Backend::CudaVerified {
+        return Err("backend cuda-verified is not available".to_string());
+    }
+    config.max_cache_bytes = Some(
+        config
+            .max_cache_bytes
+            .map_or(MAX_CACHE_BYTES, |bytes| bytes.min(MAX_CACHE_BYTES)),
+    );
+    Ok(config)
+}
+
+/// 点支持 `[x, y]` 和 `{"x": .., "y": ..}` 两种形式
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonPoint {
+    Pair([f64; 2]),
+    Object(Point2D),
+}
+
+impl From<JsonPoint> for Point2D {
+    fn from(p: JsonPoint) -> Self {
+        match p {
+            JsonPoint::Pair([x, y]) => Point2D::new(x, y),
+            JsonPoint::Object(p) => p,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FitRequest {
+    #[serde(flatten)]
+    params: FitParams,
+    points: Option<Vec<JsonPoint>>,
+    strokes: Option<Vec<Vec<JsonPoint>>>,
+}
+
+enum Payload {
+    Single(Vec<Point2D>),
+    Strokes(Vec<Vec<Point2D>>),
+}
+
+/// `/fit` 与 `/health` 两个路由，可合并进已有的 axum 应用
+pub fn router() -> Router {
+    Router::new()
+        .route("/fit", post(fit))
+        .route("/health", get(|| async { "ok" }))
+}
+
+/// 在 `addr` 上监听并运行服务，直到出错
+pub async fn serve(addr: SocketAddr) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router()).await
+}
+
+async fn fit(Query(query): Query<FitParams>, headers: HeaderMap, body: Bytes) -> Response {
+    let is_npy = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| {
+            v.starts_with("application/x-npy") || v.starts_with("application/octet-stream")
+        });
+    let parsed = if is_npy {
+        from_npy(&body).map(|points| (query, Payload::Single(points)))
+    } else {
+        parse_json(&body)
+    };
+    let (params, payload) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let config = match to_config(&params) {
+        Ok(config) => config,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    // 拟合是 CPU 密集的，不占用异步运行时的线程
+    let fitted = tokio::task::spawn_blocking(move || match payload {
+        Payload::Single(points) => {
+            try_fit_curve(&points, &config).map(|r| Json(v1::FitResult::from(&r)).into_response())
+        }
+        Payload::Strokes(strokes) => try_fit_strokes(&strokes, &config).map(|results| {
+            Json(results.iter().map(v1::FitResult::from).collect::<Vec<_>>()).into_response()
+        }),
+    })
+    .await;
+    match fitted {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+fn parse_json(body: &[u8]) -> Result<(FitParams, Payload), String> {
+    let request: FitRequest = serde_json::from_slice(body).map_err(|e| format!("json: {e}"))?;
+    let payload = match (request.points, request.strokes) {
+        (Some(points), None) => Payload::Single(points.into_iter().map(Into::into).collect()),
+        (None, Some(strokes)) => Payload::Strokes(
+            strokes
+                .into_iter()
+                .map(|s| s.into_iter().map(Into::into).collect())
+                .collect(),
+        ),
+        _ => return Err("expected exactly one of `points` or `strokes`".to_string()),
+    };
+    Ok((request.params, payload))
+}
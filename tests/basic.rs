@@ -1,4 +1,4 @@
-use bezier_dp_fit::{Point2D, FitConfig, fit_curve};
+use bezier_dp_fit::{Backend, BezierFitter, CacheMode, ConfigError, CudaOptions, EndpointPolicy, ErrorMetric, Point2D, FitConfig, FitCurveError, FitEstimate, FitParams, FitResult, FitStatus, DPOptimizer, Objective, ErrorCache, ParityReport, Precision, QuadraticBezier, Smoothing, StreamingFitter, SvgDocumentOptions, SvgOptions, fit_curve, try_fit_curve};
 
 #[test]
 fn test_simple_line() {
@@ -67,4 +67,901 @@ fn test_control_points() {
     for (i, points) in cp.iter().enumerate() {
         println!("段{}: {:?}", i, points);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_binary_roundtrip() {
+    let points: Vec<Point2D> = (0..60)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.2).sin() * 10.0))
+        .collect();
+
+    let config = FitConfig::new(5, 30, 2.0);
+    let result = fit_curve(&points, &config);
+
+    let bytes = result.to_bytes().unwrap();
+    let restored = FitResult::from_bytes(&bytes).unwrap();
+    assert_eq!(restored.num_segments, result.num_segments);
+    assert_eq!(restored.to_svg_path(), result.to_svg_path());
+
+    // 用恢复的缓存继续优化，结果应与直接拟合一致
+    let cache = DPOptimizer::compute_error_cache(&points, &config);
+    let mut warm = ErrorCache::from_bytes(&cache.to_bytes().unwrap()).unwrap();
+    assert_eq!(warm.len(), cache.len());
+    let resumed = DPOptimizer::optimize_with_cache(&points, &config, &mut warm);
+    assert_eq!(resumed.num_segments, result.num_segments);
+}
+
+#[test]
+fn test_svg_document() {
+    let points: Vec<Point2D> = (0..40)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.3).cos() * 5.0))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 20, 1.0));
+
+    let options = SvgDocumentOptions {
+        stroke: "blue".to_string(),
+        overlay_points: Some(&points),
+        ..Default::default()
+    };
+    let doc = result.to_svg_document(&options);
+    assert!(doc.starts_with("<svg"));
+    assert!(doc.contains("viewBox="));
+    assert!(doc.contains("stroke=\"blue\""));
+    assert_eq!(doc.matches("<circle").count(), points.len());
+    assert!(doc.ends_with("</svg>"));
+}
+
+#[test]
+fn test_svg_options() {
+    let points: Vec<Point2D> = (0..40)
+        .map(|i| Point2D::new(i as f64 * 0.5, (i as f64 * 0.2).sin() * 3.0))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 20, 0.5));
+
+    assert_eq!(result.to_svg_path(), result.to_svg_path_with(&SvgOptions::default()));
+
+    let options = SvgOptions {
+        precision: 4,
+        relative: true,
+        close_path: true,
+    };
+    let svg = result.to_svg_path_with(&options);
+    assert!(svg.starts_with("m "));
+    assert!(svg.contains(" q "));
+    assert!(!svg.contains('Q'));
+    assert!(svg.ends_with(" z"));
+
+    let first = svg.split_whitespace().nth(1).unwrap();
+    assert_eq!(first.split('.').nth(1).unwrap().len(), 4);
+}
+
+#[test]
+fn test_residuals() {
+    let points: Vec<Point2D> = (0..90)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).sin() * 15.0))
+        .collect();
+    let config = FitConfig::new(5, 30, 1.0);
+    let result = fit_curve(&points, &config);
+
+    assert_eq!(result.breakpoints.len(), result.num_segments + 1);
+    assert_eq!(result.breakpoints[0], 0);
+    assert_eq!(*result.breakpoints.last().unwrap(), points.len() - 1);
+
+    assert_eq!(result.segment_errors.len(), result.num_segments);
+    let summed: f64 = result.segment_errors.iter().sum();
+    assert!((summed - result.total_error).abs() < 1e-9);
+
+    let residuals = result.residuals(&points);
+    assert_eq!(residuals.len(), points.len());
+    assert!(residuals.iter().all(|r| r.is_finite() && *r >= 0.0));
+
+    let mse = residuals.iter().map(|r| r * r).sum::<f64>() / residuals.len() as f64;
+    assert!(mse <= config.max_error);
+}
+
+#[test]
+fn test_line_segments() {
+    // 折线：前半段直线，后半段正弦
+    let points: Vec<Point2D> = (0..80)
+        .map(|i| {
+            let x = i as f64;
+            let y = if i < 40 { 0.0 } else { ((x - 40.0) * 0.15).sin() * 10.0 };
+            Point2D::new(x, y)
+        })
+        .collect();
+
+    let plain = fit_curve(&points, &FitConfig::new(10, 40, 0.5));
+    assert_eq!(plain.segments.len(), plain.curves.len());
+    assert!(plain.segments.iter().all(|s| !s.is_line()));
+    assert!(!plain.to_svg_path().contains(" L "));
+
+    let config = FitConfig::new(10, 40, 0.5).with_line_tolerance(0.1);
+    let result = fit_curve(&points, &config);
+    assert!(result.segments[0].is_line());
+    assert!(result.segments.iter().any(|s| !s.is_line()));
+    for (segment, curve) in result.segments.iter().zip(&result.curves) {
+        assert_eq!(segment.start(), curve.p0);
+        assert_eq!(segment.end(), curve.p2);
+    }
+
+    let svg = result.to_svg_path();
+    assert!(svg.contains(" L "));
+    assert!(svg.contains(" Q "));
+    let relative = result.to_svg_path_with(&SvgOptions {
+        relative: true,
+        ..Default::default()
+    });
+    assert!(relative.contains(" l "));
+}
+
+#[test]
+fn test_cuda_options_config() {
+    let config: FitConfig =
+        serde_json::from_str(r#"{"min_segment_len":5,"max_segment_len":20,"max_error":1.0}"#)
+            .unwrap();
+    assert_eq!(config.cuda, CudaOptions::default());
+    assert_eq!(config.cuda.block_size, 128);
+
+    // 旧配置里的 cuda 字段缺少显存预算时取默认值
+    let partial: CudaOptions = serde_json::from_str(r#"{"device_id":2,"block_size":64}"#).unwrap();
+    assert_eq!(partial.device_id, 2);
+    assert_eq!(partial.memory_budget, CudaOptions::default().memory_budget);
+
+    let options = CudaOptions {
+        device_id: 1,
+        block_size: 256,
+        memory_budget: 64 << 20,
+        verify_samples: 32,
+        newton_refine: true,
+        multi_gpu: true,
+        streams: 3,
+        device_dp: true,
+    };
+    let config = config.with_cuda(options);
+    let json = serde_json::to_string(&config).unwrap();
+    let restored: FitConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.cuda, options);
+
+    let no_streams = config.with_cuda(CudaOptions {
+        streams: 0,
+        ..options
+    });
+    assert!(no_streams.validate().is_err());
+}
+
+#[test]
+fn test_error_cache_build_reuse() {
+    let points: Vec<Point2D> = (0..120)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.15).sin() * 12.0))
+        .collect();
+
+    let mut cache = ErrorCache::build(&points, 5..=30);
+    let built = cache.len();
+    assert!(built > 0);
+
+    // 同一缓存扫描多个容差，结果与逐次重新计算一致且不再补算
+    for max_error in [0.05, 0.5, 2.0, 8.0] {
+        let config = FitConfig::new(5, 30, max_error);
+        let reused = DPOptimizer::optimize_with_cache(&points, &config, &mut cache);
+        let fresh = fit_curve(&points, &config);
+        assert_eq!(reused.breakpoints, fresh.breakpoints);
+        assert_eq!(cache.len(), built);
+    }
+
+    // 分片计算的缓存合并后与整体一致
+    let mut left = cache.clone();
+    left.retain(|start, _| start < 60);
+    let mut right = cache.clone();
+    right.retain(|start, _| start >= 60);
+    left.merge(right);
+    assert_eq!(left.to_bytes().unwrap(), cache.to_bytes().unwrap());
+}
+
+#[test]
+fn test_error_cache_reuse_across_limits() {
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| Point2D::new(i as f64 * 0.5, (i as f64 * 0.05).sin() * 10.0))
+        .collect();
+
+    // 按严格上限计算的缓存中超限的区间记为正无穷，放宽上限复用时须重新计算
+    let tight = FitConfig::new(5, 40, 0.001);
+    let mut cache = DPOptimizer::compute_error_cache(&points, &tight);
+    let mut restored = ErrorCache::from_bytes(&cache.to_bytes().unwrap()).unwrap();
+    for max_error in [0.001, 0.05, 0.5, 2.0, 0.01] {
+        let config = FitConfig::new(5, 40, max_error);
+        let fresh = fit_curve(&points, &config);
+        let reused = DPOptimizer::optimize_with_cache(&points, &config, &mut cache);
+        assert_eq!(reused.breakpoints, fresh.breakpoints, "max_error {max_error}");
+        let reused = DPOptimizer::optimize_with_cache(&points, &config, &mut restored);
+        assert_eq!(reused.breakpoints, fresh.breakpoints, "max_error {max_error}");
+    }
+
+    // 最小二乘缓存用于最大误差度量时整体重新计算，每段仍在容差内
+    let mut cache = ErrorCache::build(&points, 5..=40);
+    let config = FitConfig::new(5, 40, 0.05).with_metric(ErrorMetric::Max);
+    let reused = DPOptimizer::optimize_with_cache(&points, &config, &mut cache);
+    assert_eq!(reused.breakpoints, fit_curve(&points, &config).breakpoints);
+    assert!(reused.residuals(&points).iter().all(|r| r * r <= 0.05 + 1e-9));
+
+    // 按度量建好的缓存在不同容差下复用不会再补算
+    let mut cache = ErrorCache::build_for(&points, &config);
+    let built = cache.len();
+    for max_error in [0.05, 0.5] {
+        let config = FitConfig {
+            max_error,
+            ..config.clone()
+        };
+        let reused = DPOptimizer::optimize_with_cache(&points, &config, &mut cache);
+        assert_eq!(reused.breakpoints, fit_curve(&points, &config).breakpoints);
+        assert_eq!(cache.len(), built);
+    }
+}
+
+#[test]
+fn test_streaming_fitter() {
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).sin() * 15.0))
+        .collect();
+    let config = FitConfig::new(5, 40, 0.5);
+
+    let mut streaming = StreamingFitter::new(config.clone());
+    let mut cached = 0;
+    for chunk in points.chunks(50) {
+        streaming.extend(chunk);
+        let result = streaming.finalize();
+        let fresh = fit_curve(streaming.points(), &config);
+        assert_eq!(result.breakpoints, fresh.breakpoints);
+        assert!(streaming.cache().len() > cached);
+        cached = streaming.cache().len();
+    }
+
+    // 平滑会改动尾部的预处理结果，失效的区间需要重新计算
+    let smoothed = config.with_presmooth(Smoothing::Gaussian { sigma: 2.0 });
+    let mut streaming = StreamingFitter::new(smoothed.clone());
+    for chunk in points.chunks(37) {
+        for p in chunk {
+            streaming.push(*p);
+        }
+        streaming.finalize();
+    }
+    let result = streaming.finalize();
+    assert_eq!(result.breakpoints, fit_curve(&points, &smoothed).breakpoints);
+
+    // LocalAverage 的端点平均到区间之后的点，尾部附近的区间在追加后失效
+    let averaged = FitConfig::new(5, 20, 0.5).with_endpoint_policy(EndpointPolicy::LocalAverage { radius: 3 });
+    let mut streaming = StreamingFitter::new(averaged.clone());
+    streaming.extend(&points[..40]);
+    streaming.finalize();
+    streaming.extend(&points[40..60]);
+    let result = streaming.finalize();
+    let mut fresh = ErrorCache::new();
+    let expected = DPOptimizer::optimize_with_cache(&points[..60], &averaged, &mut fresh);
+    assert_eq!(result.breakpoints, expected.breakpoints);
+    for start in 0..60 {
+        for end in start..60 {
+            if let (Some(a), Some(b)) = (streaming.cache().get(start, end), fresh.get(start, end)) {
+                assert_eq!(a.error, b.error, "stale entry ({start}, {end})");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_required_breakpoints() {
+    let points: Vec<Point2D> = (0..100)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).sin() * 10.0))
+        .collect();
+
+    // 17 与 20 的间隔小于最短段长，只能整体作为一段
+    let config = FitConfig::new(5, 40, 1.0).with_required_breakpoints([42, 17, 20, 0, 500]);
+    let result = fit_curve(&points, &config);
+    for r in [17, 20, 42] {
+        assert!(result.breakpoints.contains(&r), "missing {r}: {:?}", result.breakpoints);
+    }
+    assert_eq!(result.breakpoints.len(), result.num_segments + 1);
+    assert_eq!(result.config.required_breakpoints, config.required_breakpoints);
+
+    // 去重后下标仍指向原始输入
+    let mut doubled = Vec::new();
+    for p in &points {
+        doubled.push(*p);
+        doubled.push(*p);
+    }
+    let deduped = fit_curve(&doubled, &config.clone().with_required_breakpoints([61]).with_dedup(1e-9));
+    assert!(deduped.breakpoints.contains(&61), "{:?}", deduped.breakpoints);
+}
+
+#[test]
+fn test_forbidden_breakpoints() {
+    let points: Vec<Point2D> = (0..120)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.12).sin() * 12.0))
+        .collect();
+    let config = FitConfig::new(5, 40, 0.5);
+    let plain = fit_curve(&points, &config);
+
+    // 禁止原结果的所有内部断点，DP 必须另选位置
+    let mut mask = vec![false; points.len()];
+    for &b in &plain.breakpoints[1..plain.breakpoints.len() - 1] {
+        mask[b] = true;
+    }
+    let result = fit_curve(&points, &config.clone().with_forbidden_breakpoints(mask.clone()));
+    assert_eq!(result.breakpoints[0], 0);
+    assert_eq!(*result.breakpoints.last().unwrap(), points.len() - 1);
+    assert!(result.breakpoints.iter().all(|&b| !mask[b]));
+
+    // 必经点优先于禁止掩码
+    let required = plain.breakpoints[1];
+    let result = fit_curve(
+        &points,
+        &config.with_forbidden_breakpoints(mask).with_required_breakpoints([required]),
+    );
+    assert!(result.breakpoints.contains(&required));
+}
+
+#[test]
+fn test_penalized_objective() {
+    let points: Vec<Point2D> = (0..150)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.08).sin() * 20.0))
+        .collect();
+    let config = FitConfig::new(5, 60, f64::INFINITY);
+    let strict = fit_curve(&points, &FitConfig::new(5, 60, 0.2));
+
+    let mut last_segments = usize::MAX;
+    let mut last_error = 0.0;
+    for lambda in [0.01, 0.5, 5.0, 50.0] {
+        let result = fit_curve(&points, &config.clone().with_objective(Objective::Penalized { lambda }));
+        assert!(result.num_segments <= last_segments);
+        assert!(result.total_error >= last_error - 1e-9);
+        // 罚函数目标下不劣于按段数优先得到的解
+        let cost = |r: &FitResult| r.total_error + lambda * r.num_segments as f64;
+        assert!(cost(&result) <= cost(&strict) + 1e-9);
+        last_segments = result.num_segments;
+        last_error = result.total_error;
+    }
+    assert!(last_segments < strict.num_segments);
+}
+
+#[test]
+fn test_fit_status() {
+    let points: Vec<Point2D> = (0..100)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.2).sin() * 10.0))
+        .collect();
+
+    let result = fit_curve(&points, &FitConfig::new(5, 40, 1.0));
+    assert_eq!(result.status, FitStatus::Optimal);
+    assert!(result.blocking_intervals.is_empty());
+
+    // 段长过短，任何路径都达不到容差
+    let result = fit_curve(&points, &FitConfig::new(30, 40, 1e-6));
+    match result.status {
+        FitStatus::Relaxed { achieved_error } => assert!(achieved_error > 1e-6),
+        other => panic!("expected Relaxed, got {other:?}"),
+    }
+    assert!(!result.blocking_intervals.is_empty());
+    for (start, end) in &result.blocking_intervals {
+        assert!(result.breakpoints.windows(2).any(|w| w == [*start, *end]));
+    }
+
+    // 50 个点无法划分为 35..=40 点长的分段
+    let result = fit_curve(&points[..50], &FitConfig::new(35, 40, 1.0));
+    assert_eq!(result.status, FitStatus::Infeasible);
+    assert!(!result.blocking_intervals.is_empty());
+
+    let restored = FitResult::from_json(&result.to_json().unwrap()).unwrap();
+    assert_eq!(restored.status, result.status);
+    assert_eq!(restored.blocking_intervals, result.blocking_intervals);
+}
+
+#[test]
+fn test_num_threads() {
+    let points: Vec<Point2D> = (0..150)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).cos() * 8.0))
+        .collect();
+    let config = FitConfig::new(5, 40, 0.5);
+    let expected = fit_curve(&points, &config);
+
+    let limited = fit_curve(&points, &config.clone().with_num_threads(2));
+    assert_eq!(limited.breakpoints, expected.breakpoints);
+
+    // 外部线程池
+    #[cfg(feature = "parallel")]
+    {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let pooled = pool.install(|| fit_curve(&points, &config));
+        assert_eq!(pooled.breakpoints, expected.breakpoints);
+    }
+}
+
+#[test]
+fn test_estimate() {
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).sin() * 10.0))
+        .collect();
+    let config = FitConfig::new(5, 40, f64::INFINITY);
+
+    let estimate = DPOptimizer::estimate(points.len(), &config);
+    let cache = DPOptimizer::compute_error_cache(&points, &config);
+    assert_eq!(estimate.intervals, cache.len());
+    // 候选区间为段长在 [5, 40] 内、终点不早于第 5 个下标的全部区间
+    for i in 5..points.len() {
+        for j in i.saturating_sub(39)..=i - 4 {
+            assert!(cache.contains(j, i));
+        }
+    }
+    assert!(estimate.cache_bytes > 0);
+    assert!(estimate.approx_flops > 0);
+
+    // 规模随最大段长增长
+    let wider = DPOptimizer::estimate(points.len(), &FitConfig::new(5, 80, 1.0));
+    assert!(wider.intervals > estimate.intervals);
+    assert!(wider.approx_flops > estimate.approx_flops);
+
+    let empty = DPOptimizer::estimate(3, &config);
+    assert_eq!(empty, FitEstimate { intervals: 0, cache_bytes: 0, approx_flops: 0 });
+}
+
+#[test]
+fn test_config_presets() {
+    let points: Vec<Point2D> = (0..120)
+        .map(|i| Point2D::new(i as f64 * 0.5, (i as f64 * 0.1).sin() * 8.0))
+        .collect();
+
+    for name in ["handwriting", "gps_track", "font_outline", "plotter"] {
+        let config = FitConfig::preset(name).unwrap();
+        assert!(config.min_segment_len >= 3);
+        assert!(config.max_segment_len >= config.min_segment_len);
+        let result = fit_curve(&points, &config);
+        assert!(result.num_segments >= 1, "{name}");
+        assert_eq!(*result.breakpoints.last().unwrap(), points.len() - 1);
+    }
+    assert!(FitConfig::preset("unknown").is_none());
+    assert!(FitConfig::handwriting().presmooth.is_some());
+    assert!(FitConfig::gps_track().robust.is_some());
+}
+
+#[test]
+fn test_auto_error() {
+    // 归一化到 [0, 1] 的数据，默认容差 2.0 会把整条曲线拟合为一段
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| {
+            let t = i as f64 / 199.0;
+            Point2D::new(t, 0.5 + 0.4 * (t * 12.0).sin())
+        })
+        .collect();
+    let config = FitConfig::new(5, 200, 2.0);
+    assert_eq!(fit_curve(&points, &config).num_segments, 1);
+
+    let tuned = config.auto_error(&points, 0.005);
+    let diagonal = (1.0f64 + 0.8 * 0.8).sqrt();
+    assert!((tuned.max_error.sqrt() - diagonal * 0.005).abs() < 1e-3);
+    assert!(fit_curve(&points, &tuned).num_segments > 1);
+
+    // 尺度放大后相对容差得到相近的分段数
+    let scaled: Vec<Point2D> = points.iter().map(|p| Point2D::new(p.x * 1000.0, p.y * 1000.0)).collect();
+    let scaled_config = FitConfig::new(5, 200, 2.0).auto_error(&scaled, 0.005);
+    let a = fit_curve(&scaled, &scaled_config).num_segments;
+    let b = fit_curve(&points, &tuned).num_segments;
+    assert!(a.abs_diff(b) <= 1, "{a} vs {b}");
+
+    // 无法确定尺度时保持原值
+    assert_eq!(FitConfig::new(5, 200, 2.0).auto_error(&[], 0.01).max_error, 2.0);
+}
+
+#[test]
+fn test_normalize() {
+    let local: Vec<Point2D> = (0..150)
+        .map(|i| Point2D::new(i as f64 * 2.0, (i as f64 * 0.08).sin() * 40.0))
+        .collect();
+    // UTM 量级的坐标
+    let utm: Vec<Point2D> = local
+        .iter()
+        .map(|p| Point2D::new(p.x + 500_000.0, p.y + 4_200_000.0))
+        .collect();
+
+    let config = FitConfig::new(5, 60, 1e-5).with_normalize(true);
+    let a = fit_curve(&local, &config);
+    let b = fit_curve(&utm, &config);
+    assert_eq!(a.breakpoints, b.breakpoints);
+    assert!(b.num_segments > 1);
+
+    // 曲线映射回原坐标
+    for (curve, w) in b.curves.iter().zip(b.breakpoints.windows(2)) {
+        assert!(curve.p0.distance_to(&utm[w[0]]) < 1e-6);
+        assert!(curve.p2.distance_to(&utm[w[1]]) < 1e-6);
+    }
+    let residuals = b.residuals(&utm);
+    assert!(residuals.iter().all(|r| *r < 2.0));
+}
+
+#[test]
+fn test_config_builder() {
+    let config = FitConfig::builder()
+        .min_segment_len(5)
+        .max_segment_len(40)
+        .max_error(0.25)
+        .metric(ErrorMetric::Max)
+        .line_tolerance(0.05)
+        .build()
+        .unwrap();
+    assert_eq!(config.min_segment_len, 5);
+    assert_eq!(config.max_segment_len, 40);
+    assert_eq!(config.metric, ErrorMetric::Max);
+    assert_eq!(config.backend, FitConfig::default().backend);
+
+    // 最大误差度量下每个点都在容差内
+    let points: Vec<Point2D> = (0..120)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).sin() * 10.0))
+        .collect();
+    let result = fit_curve(&points, &config);
+    assert!(result.residuals(&points).iter().all(|r| r * r <= config.max_error + 1e-9));
+
+    for invalid in [
+        FitConfig::builder().min_segment_len(2).build(),
+        FitConfig::builder().min_segment_len(50).max_segment_len(40).build(),
+        FitConfig::builder().max_error(f64::NAN).build(),
+        FitConfig::builder().num_threads(0).build(),
+    ] {
+        assert!(matches!(invalid, Err(ConfigError { .. })));
+    }
+}
+
+#[test]
+fn test_fit_params() {
+    // 显式参数覆盖预设，其余保留预设值
+    let params = FitParams {
+        max_error: Some(0.5),
+        preset: Some("gps_track".to_string()),
+        backend: Some("cpu".to_string()),
+        metric: Some("max".to_string()),
+        presmooth: Some(1.5),
+        ..FitParams::default()
+    };
+    let config = params.to_config().unwrap();
+    let preset = FitConfig::gps_track();
+    assert_eq!(config.min_segment_len, preset.min_segment_len);
+    assert_eq!(config.max_error, 0.5);
+    assert_eq!(config.backend, Backend::Cpu);
+    assert_eq!(config.metric, ErrorMetric::Max);
+    assert_eq!(config.presmooth, Some(Smoothing::Gaussian { sigma: 1.5 }));
+    assert!(config.robust.is_some());
+    let default = FitParams::default().to_config().unwrap();
+    assert_eq!(default.max_segment_len, FitConfig::default().max_segment_len);
+
+    // 无效参数报错，不自动修正
+    let name = |name: &str| Some(name.to_string());
+    let invalid = [
+        (FitParams { min_segment_len: Some(2), ..Default::default() }, "min_segment_len"),
+        (FitParams { max_error: Some(0.0), ..Default::default() }, "max_error"),
+        (FitParams { preset: name("unknown"), ..Default::default() }, "unknown preset"),
+        (FitParams { backend: name("tpu"), ..Default::default() }, "unknown backend"),
+        (FitParams { metric: name("l1"), ..Default::default() }, "unknown metric"),
+        (FitParams { presmooth: Some(-1.0), ..Default::default() }, "presmooth"),
+    ];
+    for (params, reason) in invalid {
+        let err = params.to_config().unwrap_err();
+        assert!(err.reason.contains(reason), "{err}");
+    }
+}
+
+#[test]
+fn test_timestamps() {
+    // 沿已知曲线运动，起步慢、之后加速
+    let curve = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(50.0, 80.0),
+        Point2D::new(100.0, 0.0),
+    );
+    let times: Vec<f64> = (0..50).map(|i| (i as f64 / 49.0).powi(2) * 10.0).collect();
+    let points: Vec<Point2D> = times.iter().map(|t| curve.evaluate(t / 10.0)).collect();
+
+    let config = FitConfig::new(3, 100, 1e-6).with_timestamps(times.clone());
+    let result = fit_curve(&points, &config);
+    assert_eq!(result.num_segments, 1);
+    assert!(result.total_error < 1e-12);
+    assert!((result.curves[0].p1.x - 50.0).abs() < 1e-6);
+    assert!((result.curves[0].p1.y - 80.0).abs() < 1e-6);
+    assert_eq!(result.segment_times, vec![(0.0, 10.0)]);
+
+    let chord = fit_curve(&points, &FitConfig::new(3, 100, 1e-6));
+    assert!(chord.segment_times.is_empty());
+
+    let split = fit_curve(&points, &config.clone().with_required_breakpoints([20]));
+    assert_eq!(split.segment_times, vec![(0.0, times[20]), (times[20], 10.0)]);
+
+    assert!(FitConfig::new(3, 100, 1.0).with_timestamps([1.0, 0.0]).validate().is_err());
+
+    // 个数与点数不符时 try_fit_curve 报错，不会静默退回弦长参数化
+    let short = config.clone().with_timestamps(times[..49].to_vec());
+    assert_eq!(
+        try_fit_curve(&points, &short).unwrap_err(),
+        FitCurveError::TimestampCount { expected: 50, found: 49 }
+    );
+    assert!(try_fit_curve(&points, &config).is_ok());
+}
+
+#[test]
+fn test_function_graph() {
+    // 抛物线 y = 0.01 x^2 恰好是一段二次曲线
+    let parabola: Vec<Point2D> = (0..=100)
+        .map(|i| Point2D::new(i as f64, 0.01 * (i * i) as f64))
+        .collect();
+    let config = FitConfig::new(3, 200, 1e-6).with_function_graph(true);
+    let result = fit_curve(&parabola, &config);
+    assert_eq!(result.num_segments, 1);
+    assert!(result.total_error < 1e-12);
+    assert!((result.curves[0].p1.x - 50.0).abs() < 1e-9);
+
+    // 非均匀采样的信号，每段在 x 上单调，竖直误差不超过上限
+    let signal: Vec<Point2D> = (0..300)
+        .map(|i| {
+            let x = i as f64 + 0.3 * ((i * 7) % 5) as f64;
+            Point2D::new(x, 10.0 * (x * 0.05).sin())
+        })
+        .collect();
+    let result = fit_curve(&signal, &FitConfig::new(5, 80, 0.01).with_function_graph(true));
+    for (curve, w) in result.curves.iter().zip(result.breakpoints.windows(2)) {
+        assert!(curve.p0.x < curve.p1.x && curve.p1.x < curve.p2.x);
+        let width = curve.p2.x - curve.p0.x;
+        let mse = signal[w[0]..=w[1]]
+            .iter()
+            .map(|p| (p.y - curve.evaluate((p.x - curve.p0.x) / width).y).powi(2))
+            .sum::<f64>()
+            / (w[1] - w[0] + 1) as f64;
+        assert!(mse <= 0.01 + 1e-12);
+    }
+
+    // x 回退的输入不是函数图像：try_fit_curve 报错，单段拟合不退回正交误差
+    let mut folded = parabola.clone();
+    folded[60].x = 58.5;
+    assert_eq!(
+        try_fit_curve(&folded, &config).unwrap_err(),
+        FitCurveError::NonIncreasingX { index: 60 }
+    );
+    assert_eq!(BezierFitter::fit_segment_graph(&folded[50..70]).error, f64::INFINITY);
+    assert!(BezierFitter::fit_segment_graph(&folded[..50]).error < 1e-12);
+    assert_eq!(BezierFitter::fit_segment_graph(&[]).error, 0.0);
+}
+
+#[test]
+fn test_segment_info() {
+    let curve = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(0.5, 0.0),
+        Point2D::new(2.0, 0.0),
+    );
+    assert!((curve.arc_length() - 2.0).abs() < 1e-12);
+    let curve = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(5.0, 10.0),
+        Point2D::new(10.0, 0.0),
+    );
+    let polyline: f64 = curve
+        .sample(10001)
+        .windows(2)
+        .map(|w| w[0].distance_to(&w[1]))
+        .sum();
+    assert!((curve.arc_length() - polyline).abs() < 1e-6);
+
+    // 去重后分段下标和偏差仍对应原始输入
+    let mut points: Vec<Point2D> = (0..120)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).sin() * 8.0))
+        .collect();
+    points.insert(60, points[60]);
+    let config = FitConfig::new(5, 40, 0.2).with_dedup(1e-9);
+    let result = fit_curve(&points, &config);
+    assert_eq!(result.segment_info.len(), result.num_segments);
+    let residuals = result.residuals(&points);
+    for (k, info) in result.segment_info.iter().enumerate() {
+        assert_eq!(info.start_index, result.breakpoints[k]);
+        assert_eq!(info.end_index, result.breakpoints[k + 1]);
+        assert!((info.arc_length - result.curves[k].arc_length()).abs() < 1e-9);
+        let first = if k == 0 { 0 } else { info.start_index + 1 };
+        let worst = residuals[first..=info.end_index].iter().fold(0.0, |a: f64, &b| a.max(b));
+        assert!(info.max_deviation >= worst - 1e-9);
+    }
+    assert_eq!(result.segment_info.last().unwrap().end_index, points.len() - 1);
+}
+
+#[test]
+fn test_fit_quality() {
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.05).sin() * 20.0))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(10, 60, 0.5));
+    let quality = result.quality(&points);
+
+    let residuals = result.residuals(&points);
+    let rmse = (residuals.iter().map(|e| e * e).sum::<f64>() / residuals.len() as f64).sqrt();
+    let worst = residuals.iter().fold(0.0, |a: f64, &b| a.max(b));
+    assert!((quality.rmse - rmse).abs() < 1e-12);
+    assert_eq!(quality.max_error, worst);
+    assert!(quality.rmse <= quality.max_error);
+    assert!(quality.p95_error <= quality.max_error);
+    let below = residuals.iter().filter(|&&e| e <= quality.p95_error).count();
+    assert!(below as f64 >= 0.95 * residuals.len() as f64);
+    assert_eq!(
+        quality.compression_ratio,
+        200.0 / (2 * result.num_segments + 1) as f64
+    );
+
+    assert_eq!(quality.per_segment.len(), result.num_segments);
+    for (k, seg) in quality.per_segment.iter().enumerate() {
+        assert_eq!(seg.start_index, result.breakpoints[k]);
+        assert_eq!(seg.end_index, result.breakpoints[k + 1]);
+        assert!(seg.rmse <= seg.max_error && seg.max_error <= quality.max_error);
+    }
+    let max_of_segments = quality.per_segment.iter().fold(0.0, |a: f64, s| a.max(s.max_error));
+    assert_eq!(max_of_segments, quality.max_error);
+}
+
+#[test]
+fn test_lower_bound_pruning() {
+    let points: Vec<Point2D> = (0..250)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.08).sin() * 15.0))
+        .collect();
+    for metric in [ErrorMetric::Mse, ErrorMetric::Max] {
+        let config = FitConfig::new(5, 80, 0.5).with_metric(metric);
+        // 剪枝只影响超过 max_error 的区间：是否超限与完整计算一致
+        let pruned = DPOptimizer::compute_error_cache(&points, &config);
+        let full_config = FitConfig { max_error: f64::INFINITY, ..config.clone() };
+        let full = DPOptimizer::compute_error_cache(&points, &full_config);
+        assert_eq!(pruned.len(), full.len());
+        for end in 5..points.len() {
+            for start in end.saturating_sub(79)..=end - 4 {
+                let exact = full.get(start, end).unwrap().error;
+                let bounded = pruned.get(start, end).unwrap().error;
+                assert_eq!(bounded > config.max_error, exact > config.max_error);
+                if exact <= config.max_error {
+                    assert_eq!(bounded, exact);
+                }
+            }
+        }
+
+        let mut cache = full;
+        let reference = DPOptimizer::optimize_with_cache(&points, &config, &mut cache);
+        assert_eq!(fit_curve(&points, &config).breakpoints, reference.breakpoints);
+    }
+}
+
+#[test]
+fn test_max_cache_bytes() {
+    let points: Vec<Point2D> = (0..300)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.05).sin() * 20.0))
+        .collect();
+    let config = FitConfig::new(5, 60, 0.3).with_required_breakpoints([150]);
+    let cached = fit_curve(&points, &config);
+
+    // 预算小于估计值时不建缓存，结果与缓存路径一致
+    let budget = DPOptimizer::estimate(points.len(), &config).cache_bytes / 10;
+    let limited = config.clone().with_max_cache_bytes(budget);
+    let mut uncached = fit_curve(&points, &limited);
+    assert_eq!(uncached.status, FitStatus::Degraded);
+    assert_eq!(cached.status, FitStatus::Optimal);
+    let restored = FitResult::from_json(&uncached.to_json().unwrap()).unwrap();
+    assert_eq!(restored.status, FitStatus::Degraded);
+    uncached.config = config.clone();
+    uncached.status = FitStatus::Optimal;
+    assert_eq!(uncached.to_bytes().unwrap(), cached.to_bytes().unwrap());
+
+    // 预算足够或显式不建缓存时不算降级
+    let ample = DPOptimizer::estimate(points.len(), &config).cache_bytes;
+    assert_eq!(fit_curve(&points, &config.clone().with_max_cache_bytes(ample)).status, FitStatus::Optimal);
+    assert_eq!(fit_curve(&points, &config.clone().with_cache_mode(CacheMode::None)).status, FitStatus::Optimal);
+
+    // 误差要求无法满足时同样回退到放宽误差的求解
+    let strict = FitConfig::new(5, 8, 1e-9).with_max_cache_bytes(0);
+    let relaxed = fit_curve(&points, &strict);
+    assert!(matches!(relaxed.status, FitStatus::Relaxed { .. }));
+    assert_eq!(relaxed.breakpoints.last(), Some(&(points.len() - 1)));
+}
+
+#[test]
+fn test_cache_mode_none() {
+    let points: Vec<Point2D> = (0..300)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.07).cos() * 15.0))
+        .collect();
+
+    // 按需计算的结果与完整缓存一致，两种目标都覆盖
+    for objective in [Objective::MinSegments, Objective::Penalized { lambda: 0.5 }] {
+        let config = FitConfig::new(4, 50, 0.4).with_objective(objective);
+        let cached = fit_curve(&points, &config);
+        let mut lazy = fit_curve(&points, &config.clone().with_cache_mode(CacheMode::None));
+        assert_eq!(lazy.status, cached.status);
+        lazy.config = config.clone();
+        assert_eq!(lazy.to_bytes().unwrap(), cached.to_bytes().unwrap());
+    }
+}
+
+#[test]
+fn test_device_dp_fallback() {
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.09).sin() * 20.0))
+        .collect();
+
+    // 没有可用的 CUDA 设备时回退到常规路径，结果不变
+    let config = FitConfig::new(4, 40, 0.5);
+    let device = config.clone().with_cuda(CudaOptions {
+        device_dp: true,
+        ..CudaOptions::default()
+    });
+    let expected = fit_curve(&points, &config);
+    let mut result = fit_curve(&points, &device);
+    assert_eq!(result.status, FitStatus::Optimal);
+    result.config = config.clone();
+    assert_eq!(result.to_bytes().unwrap(), expected.to_bytes().unwrap());
+}
+
+#[test]
+fn test_opencl_backend_fallback() {
+    assert_eq!("OpenCL".parse::<Backend>(), Ok(Backend::OpenCl));
+    let points: Vec<Point2D> = (0..150)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).cos() * 10.0))
+        .collect();
+
+    // 未启用 opencl feature 或没有设备时回退到 CPU
+    let config = FitConfig::new(5, 40, 0.8);
+    let opencl = fit_curve(&points, &config.clone().with_backend(Backend::OpenCl));
+    assert_eq!(opencl.breakpoints, fit_curve(&points, &config).breakpoints);
+}
+
+#[test]
+fn test_verify_cache() {
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.06).sin() * 25.0))
+        .collect();
+    let config = FitConfig::new(5, 40, 2.0);
+    let cache = DPOptimizer::compute_error_cache(&points, &config);
+
+    // 与自身一致；抽样确定且数量接近要求
+    let report = DPOptimizer::verify_cache(&points, &config, &cache, 100);
+    assert!(report.passed());
+    assert_eq!(report.max_divergence, 0.0);
+    assert!((50..=150).contains(&report.samples));
+    assert_eq!(report, DPOptimizer::verify_cache(&points, &config, &cache, 100));
+
+    // 篡改误差或缺失区间时不通过
+    let mut tampered = cache.clone();
+    tampered.retain(|start, _| start % 2 == 0);
+    let report = DPOptimizer::verify_cache(&points, &config, &tampered, 100);
+    assert!(!report.passed());
+    assert!(report.max_divergence.is_infinite());
+    assert_eq!(report.worst_interval.map(|(start, _)| start % 2), Some(1));
+
+    // 单精度缓存的偏差在单精度容差内
+    let single = config.clone().with_precision(Precision::F32);
+    let report = DPOptimizer::verify_cache(&points, &config, &DPOptimizer::compute_error_cache(&points, &single), 100);
+    assert!(report.max_divergence < 1e-3);
+    assert_eq!(report.tolerance, 1e-6);
+
+    // 没有 CUDA 时与 Cuda 一样回退到 CPU
+    assert_eq!("cuda-verified".parse::<Backend>(), Ok(Backend::CudaVerified));
+    let verified = fit_curve(&points, &config.clone().with_backend(Backend::CudaVerified));
+    assert_eq!(verified.breakpoints, fit_curve(&points, &config).breakpoints);
+    let verified = try_fit_curve(&points, &config.clone().with_backend(Backend::CudaVerified));
+    assert_eq!(verified.unwrap().breakpoints, fit_curve(&points, &config).breakpoints);
+    // 抽查未通过时以错误返回报告，不会 panic
+    let report = DPOptimizer::verify_cache(&points, &config, &tampered, 100);
+    let error = FitCurveError::CacheDivergence { report };
+    assert!(error.to_string().starts_with("cuda error cache diverges from cpu"));
+
+    // newton_refine 的误差与 CPU 采样不一致，抽查不会通过；多分辨率的粗拟合内同样要报告
+    let diverging = config
+        .clone()
+        .with_backend(Backend::CudaVerified)
+        .with_cuda(CudaOptions { newton_refine: true, ..CudaOptions::default() })
+        .with_multiresolution(4, 3);
+    #[cfg(feature = "cuda")]
+    let has_cuda = bezier_dp_fit::optimizer::cuda::list_devices().is_ok_and(|d| !d.is_empty());
+    #[cfg(not(feature = "cuda"))]
+    let has_cuda = false;
+    match try_fit_curve(&points, &diverging) {
+        Err(FitCurveError::CacheDivergence { report }) => assert!(has_cuda && !report.passed()),
+        Ok(result) => {
+            assert!(!has_cuda);
+            assert_eq!(result.breakpoints, fit_curve(&points, &diverging).breakpoints);
+        }
+        Err(e) => panic!("unexpected error: {e}"),
+    }
+    let empty: ParityReport = DPOptimizer::verify_cache(&points[..3], &config, &cache, 100);
+    assert_eq!((empty.samples, empty.worst_interval), (0, None));
+}
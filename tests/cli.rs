@@ -0,0 +1,96 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bezier-dp-fit"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn sine_csv(n: usize) -> String {
+    (0..n)
+        .map(|i| {
+            let x = i as f64 * 0.5;
+            format!("{x},{}\n", (x * 0.2).sin() * 10.0)
+        })
+        .collect()
+}
+
+#[test]
+fn test_cli_path_output() {
+    let out = run(
+        &[
+            "--min-len",
+            "5",
+            "--max-len",
+            "40",
+            "--max-error",
+            "0.05",
+            "--precision",
+            "3",
+        ],
+        &sine_csv(200),
+    );
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let path = String::from_utf8(out.stdout).unwrap();
+    assert!(path.starts_with("M 0.000 0.000 Q "), "{path}");
+    assert!(path.ends_with('\n'));
+    // 每段一条 Q 指令，坐标按 --precision 保留三位小数
+    assert!(path.matches('Q').count() > 1);
+    assert!(path
+        .split_whitespace()
+        .filter(|t| t.parse::<f64>().is_ok())
+        .all(|t| t.split('.').nth(1).map(str::len) == Some(3)));
+
+    // JSON 输入和 SVG 文档输出
+    let json = r#"[[0, 0], [1, 1], [2, 4], [3, 9], [4, 16], [5, 25], [6, 36]]"#;
+    let out = run(&["--format", "json", "--min-len", "3", "--svg"], json);
+    assert!(out.status.success());
+    let svg = String::from_utf8(out.stdout).unwrap();
+    assert!(svg.starts_with("<svg") && svg.contains("<path"), "{svg}");
+}
+
+#[test]
+fn test_cli_rejects_invalid_config() {
+    // 参数无效时报错退出，不会被静默改成其他值
+    for args in [
+        ["--max-error", "0"],
+        ["--min-len", "2"],
+        ["--max-len", "10"],
+    ] {
+        let out = run(&args, &sine_csv(50));
+        assert!(!out.status.success(), "{args:?}");
+        assert!(out.stdout.is_empty());
+        let stderr = String::from_utf8(out.stderr).unwrap();
+        assert!(
+            stderr.starts_with("bezier-dp-fit: invalid config: "),
+            "{stderr}"
+        );
+    }
+
+    // 低于旧版下限 0.1 的容差照常使用
+    let strict = run(&["--min-len", "5", "--max-error", "0.01"], &sine_csv(200));
+    let loose = run(&["--min-len", "5", "--max-error", "0.1"], &sine_csv(200));
+    assert!(strict.status.success() && loose.status.success());
+    let count = |o: &Output| String::from_utf8_lossy(&o.stdout).matches('Q').count();
+    assert!(count(&strict) > count(&loose));
+
+    let out = run(&["--format", "json"], "not json");
+    assert!(!out.status.success());
+    assert!(String::from_utf8(out.stderr).unwrap().contains("json: "));
+}
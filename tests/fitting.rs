@@ -0,0 +1,926 @@
+use approx::assert_relative_eq;
+use bezier_dp_fit::{
+    fit_curve, fit_curve_chunked, interpolate_catmull_rom, optimizer::fit_curve_serial,
+    AdaptiveWindow, BezierFitter, CacheMode, DPOptimizer, ErrorCache, ErrorMetric, FitConfig,
+    FitError, FitStatus, FitterKind, Objective, Point2, Point2D, Precision, PrimitiveSet,
+    QuadraticBezier, RansacParams, RobustLoss, Transform2D,
+};
+
+/// 抛物线上叠加少量尖刺，模拟 GPS 跳点
+fn parabola_with_spikes(n: usize) -> Vec<Point2D> {
+    (0..n)
+        .map(|i| {
+            let x = i as f64;
+            let spike = if i % 15 == 7 { 25.0 } else { 0.0 };
+            Point2D::new(x, 0.01 * x * x + spike)
+        })
+        .collect()
+}
+
+#[test]
+fn test_robust_segment_ignores_outliers() {
+    let points = parabola_with_spikes(60);
+    let clean: Vec<Point2D> = points
+        .iter()
+        .map(|p| Point2D::new(p.x, 0.01 * p.x * p.x))
+        .collect();
+    let reference = BezierFitter::fit_segment(&clean).bezier;
+
+    let plain = BezierFitter::fit_segment(&points).bezier;
+    let robust = BezierFitter::fit_segment_robust(&points, &RobustLoss::Tukey { c: 3.0 }).bezier;
+
+    let plain_dev = plain.p1.distance_to(&reference.p1);
+    let robust_dev = robust.p1.distance_to(&reference.p1);
+    assert!(robust_dev < plain_dev * 0.1, "{robust_dev} vs {plain_dev}");
+}
+
+#[test]
+fn test_robust_loss_shapes() {
+    let huber = RobustLoss::Huber { delta: 1.0 };
+    assert_eq!(huber.loss(0.5), 0.25);
+    assert_eq!(huber.loss(3.0), 5.0);
+    assert_eq!(huber.weight(4.0), 0.25);
+
+    let tukey = RobustLoss::Tukey { c: 2.0 };
+    assert!((tukey.loss(0.01) - 0.0001).abs() < 1e-6);
+    assert_eq!(tukey.loss(10.0), 4.0 / 3.0);
+    assert_eq!(tukey.weight(10.0), 0.0);
+}
+
+#[test]
+fn test_robust_config_reduces_breakpoints() {
+    let points = parabola_with_spikes(150);
+    let base = FitConfig::new(5, 80, 2.0);
+    let plain = fit_curve(&points, &base);
+    let robust = fit_curve(
+        &points,
+        &base.clone().with_robust(RobustLoss::Huber { delta: 1.0 }),
+    );
+    assert!(robust.num_segments < plain.num_segments);
+}
+
+#[test]
+fn test_ransac_segment() {
+    let points = parabola_with_spikes(60);
+    // y = 0.01x² 在 x ∈ [0, 59] 上恰好是控制点为 (29.5, 0) 的二次贝塞尔曲线
+    let exact = Point2D::new(29.5, 0.0);
+
+    let params = RansacParams::default();
+    let fit = BezierFitter::fit_segment_ransac(&points, &params);
+    let plain = BezierFitter::fit_segment(&points);
+    assert!(fit.bezier.p1.distance_to(&exact) < 1.0);
+    assert!(plain.bezier.p1.distance_to(&exact) > 5.0);
+    // 4 个跳点各贡献 threshold²，其余点几乎贴合
+    assert!(fit.error < 4.0 * 4.0 / 60.0 + 0.2);
+
+    // 同样的输入得到同样的结果
+    let again = BezierFitter::fit_segment_ransac(&points, &params);
+    assert_eq!(again.bezier.p1, fit.bezier.p1);
+
+    let config = FitConfig::new(5, 80, 0.5).with_fitter(FitterKind::Ransac(params));
+    let result = fit_curve(&points, &config);
+    assert!(result.num_segments < fit_curve(&points, &FitConfig::new(5, 80, 0.5)).num_segments);
+}
+
+#[test]
+fn test_arc_segment() {
+    use bezier_dp_fit::fitting::fit_arc_segment;
+
+    // 圆心 (10, -5)、半径 40 的 120° 圆弧
+    let points: Vec<Point2D> = (0..60)
+        .map(|i| {
+            let a = 0.3 + i as f64 / 59.0 * 2.0 * std::f64::consts::FRAC_PI_3;
+            Point2D::new(10.0 + 40.0 * a.cos(), -5.0 + 40.0 * a.sin())
+        })
+        .collect();
+
+    let fit = fit_arc_segment(&points).unwrap();
+    let arc = fit.arc.unwrap();
+    assert_relative_eq!(arc.center.x, 10.0, epsilon = 1e-6);
+    assert_relative_eq!(arc.center.y, -5.0, epsilon = 1e-6);
+    assert_relative_eq!(arc.radius, 40.0, epsilon = 1e-6);
+    assert_relative_eq!(arc.sweep, 2.0 * std::f64::consts::FRAC_PI_3, epsilon = 1e-6);
+    assert!(fit.error < 1e-10);
+    assert!(BezierFitter::fit_segment(&points).error > fit.error);
+
+    // 反向遍历得到顺时针圆弧
+    let reversed: Vec<Point2D> = points.iter().rev().copied().collect();
+    let arc = fit_arc_segment(&reversed).unwrap().arc.unwrap();
+    assert!(arc.sweep < 0.0);
+
+    let line: Vec<Point2D> = (0..10).map(|i| Point2D::new(i as f64, 2.0)).collect();
+    assert!(fit_arc_segment(&line).is_none());
+}
+
+#[test]
+fn test_arc_primitive_config() {
+    // 半圆加一段直线，DP 应为圆弧部分选择圆弧
+    let mut points: Vec<Point2D> = (0..90)
+        .map(|i| {
+            let a = std::f64::consts::PI * (1.0 - i as f64 / 89.0);
+            Point2D::new(50.0 + 50.0 * a.cos(), 50.0 * a.sin())
+        })
+        .collect();
+    points.extend((1..40).map(|i| Point2D::new(100.0 + i as f64, 0.0)));
+
+    let config = FitConfig::new(10, 60, 0.05).with_primitives(PrimitiveSet::BezierAndArc);
+    let result = fit_curve(&points, &config);
+    assert!(result.segments.iter().any(|s| s.is_arc()));
+    assert_eq!(result.segments.len(), result.curves.len());
+    assert!(result.to_svg_path().contains(" A 50.00 50.00 0 0 0 "));
+
+    let plain = fit_curve(&points, &FitConfig::new(10, 60, 0.05));
+    assert!(plain.segments.iter().all(|s| !s.is_arc()));
+    assert!(result.num_segments <= plain.num_segments);
+
+    // 旋转保持圆弧，非等比缩放退化为二次曲线
+    let rotated = result.transform(&Transform2D::rotate(0.7));
+    assert!(rotated.segments.iter().any(|s| s.is_arc()));
+    let stretched = result.transform(&Transform2D::scale(2.0, 1.0));
+    assert!(stretched.segments.iter().all(|s| !s.is_arc()));
+}
+
+#[test]
+fn test_arc_primitive_max_metric() {
+    // 带噪声的圆弧：均方误差在容差内但个别点超出，最大误差度量下不能选这样的圆弧
+    let points: Vec<Point2D> = (0..300)
+        .map(|i| {
+            let a = i as f64 * 0.02;
+            let r = 40.0 + if i % 17 == 0 { 0.35 } else { 0.0 } + ((i * 7919) % 11) as f64 * 0.01;
+            Point2D::new(r * a.cos(), r * a.sin())
+        })
+        .collect();
+    let config = FitConfig {
+        max_error: 0.05,
+        ..FitConfig::plotter()
+    }
+    .with_metric(ErrorMetric::Max);
+    let result = fit_curve(&points, &config);
+
+    assert_eq!(result.status, FitStatus::Optimal);
+    assert!(result.segments.iter().any(|s| s.is_arc()));
+    for (info, error) in result.segment_info.iter().zip(&result.segment_errors) {
+        assert!(info.max_deviation.powi(2) <= 0.05 + 1e-9, "{info:?}");
+        assert!(*error <= 0.05);
+    }
+}
+
+#[test]
+fn test_arc_primitive_robust_and_graph() {
+    // 圆弧在鲁棒损失下按同样的损失计分，分段误差与实际偏差的平均损失一致
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| {
+            let a = i as f64 * 0.015;
+            let r = 30.0 + if i % 23 == 0 { 2.0 } else { 0.0 };
+            Point2D::new(r * a.cos(), r * a.sin())
+        })
+        .collect();
+    let loss = RobustLoss::Huber { delta: 0.2 };
+    let config = FitConfig::new(10, 200, 0.5)
+        .with_primitives(PrimitiveSet::BezierAndArc)
+        .with_robust(loss);
+    let result = fit_curve(&points, &config);
+    assert!(result.segments.iter().any(|s| s.is_arc()));
+    for ((segment, error), w) in result
+        .segments
+        .iter()
+        .zip(&result.segment_errors)
+        .zip(result.breakpoints.windows(2))
+    {
+        let span = &points[w[0]..=w[1]];
+        let expected = span
+            .iter()
+            .map(|p| loss.loss(segment.distance_to_point(p)))
+            .sum::<f64>()
+            / span.len() as f64;
+        if segment.is_arc() {
+            assert_relative_eq!(*error, expected, epsilon = 1e-9);
+        }
+    }
+
+    // 函数图像的竖直误差无法与圆弧比较：配置校验拒绝，直接拟合时不选圆弧
+    let graph: Vec<Point2D> = (0..100)
+        .map(|i| {
+            let x = i as f64 * 0.5 - 25.0;
+            Point2D::new(x, (900.0 - x * x).sqrt())
+        })
+        .collect();
+    let config = FitConfig::new(10, 100, 0.01)
+        .with_primitives(PrimitiveSet::BezierAndArc)
+        .with_function_graph(true);
+    assert!(config.validate().is_err());
+    let result = fit_curve(&graph, &config);
+    assert!(result.segments.iter().all(|s| !s.is_arc()));
+    let timed = FitConfig::new(10, 100, 0.01)
+        .with_primitives(PrimitiveSet::BezierAndArc)
+        .with_timestamps((0..100).map(f64::from));
+    assert!(timed.validate().is_err());
+}
+
+#[test]
+fn test_f32_precision() {
+    // 大坐标偏移下单精度误差仍应与双精度一致
+    let points: Vec<Point2D> = (0..120)
+        .map(|i| {
+            let x = 50_000.0 + i as f64;
+            Point2D::new(x, 20_000.0 + (i as f64 * 0.08).sin() * 12.0)
+        })
+        .collect();
+
+    let segment = &points[10..60];
+    let bezier = BezierFitter::fit_segment(segment).bezier;
+    let f64_error = BezierFitter::compute_error(&bezier, segment);
+    let f32_error = BezierFitter::compute_error_f32(&bezier, segment, f64::INFINITY);
+    assert_relative_eq!(f32_error, f64_error, epsilon = 1e-3);
+
+    let config = FitConfig::new(10, 60, 0.5);
+    let double = fit_curve(&points, &config);
+    let single = fit_curve(&points, &config.clone().with_precision(Precision::F32));
+    assert_eq!(single.breakpoints, double.breakpoints);
+    assert_relative_eq!(single.total_error, double.total_error, epsilon = 1e-3);
+}
+
+#[test]
+fn test_fit_quad_f32_matches_f64() {
+    let points: Vec<Point2D> = (0..40)
+        .map(|i| {
+            let x = i as f64 * 0.5;
+            Point2D::new(x, 0.1 * x * x - x)
+        })
+        .collect();
+    let points32: Vec<Point2<f32>> = points.iter().map(|p| p.cast::<f32>()).collect();
+
+    let (bezier64, error64) = BezierFitter::fit_quad(&points);
+    let (bezier32, error32) = BezierFitter::fit_quad(&points32);
+
+    let p1 = bezier32.p1.cast::<f64>();
+    assert_relative_eq!(p1.x, bezier64.p1.x, epsilon = 1e-3);
+    assert_relative_eq!(p1.y, bezier64.p1.y, epsilon = 1e-3);
+    assert!((error32 as f64 - error64).abs() < 1e-3);
+
+    let fit = BezierFitter::fit_segment(&points);
+    assert_eq!(fit.bezier.p1, bezier64.p1);
+    assert_eq!(bezier32.cast::<f64>().cast::<f32>().p1, bezier32.p1);
+}
+
+#[test]
+fn test_fit_curve_3d_helix() {
+    use bezier_dp_fit::{fit_curve_3d, Point3D};
+
+    let points: Vec<Point3D> = (0..200)
+        .map(|i| {
+            let t = i as f64 * 0.05;
+            Point3D::new(20.0 * t.cos(), 20.0 * t.sin(), 2.0 * t)
+        })
+        .collect();
+    let config = FitConfig::new(5, 60, 0.05);
+    let result = fit_curve_3d(&points, &config);
+
+    assert!(result.num_segments > 1);
+    assert_eq!(result.breakpoints.first(), Some(&0));
+    assert_eq!(result.breakpoints.last(), Some(&199));
+    assert!(result.segment_errors.iter().all(|&e| e <= 0.05));
+    for (curve, w) in result.curves.iter().zip(result.breakpoints.windows(2)) {
+        assert_eq!(curve.p0, points[w[0]]);
+        assert_eq!(curve.p2, points[w[1]]);
+    }
+
+    // z 恒为 0 时与二维拟合一致
+    let flat: Vec<Point3D> = points.iter().map(|p| Point3D::new(p.x, p.y, 0.0)).collect();
+    let planar: Vec<Point2D> = points.iter().map(|p| Point2D::new(p.x, p.y)).collect();
+    let result_3d = fit_curve_3d(&flat, &config);
+    let result_2d = fit_curve(&planar, &config);
+    assert_eq!(result_3d.breakpoints, result_2d.breakpoints);
+    assert_relative_eq!(result_3d.total_error, result_2d.total_error, epsilon = 1e-9);
+}
+
+#[test]
+fn test_fit_segment_with_tangents() {
+    let curve = bezier_dp_fit::QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(40.0, 60.0),
+        Point2D::new(100.0, 0.0),
+    );
+    let points = curve.sample(60);
+
+    // 控制点落在起点切线上
+    let along =
+        BezierFitter::fit_segment_with_tangents(&points, Some(Point2D::new(2.0, 3.0)), None)
+            .unwrap();
+    assert_relative_eq!(
+        along.bezier.p1.x * 3.0,
+        along.bezier.p1.y * 2.0,
+        epsilon = 1e-9
+    );
+    let free = BezierFitter::fit_segment(&points);
+
+    // 起点水平切线：控制点落在 x 轴上
+    let flat_in =
+        BezierFitter::fit_segment_with_tangents(&points, Some(Point2D::new(1.0, 0.0)), None)
+            .unwrap();
+    assert!(flat_in.bezier.p1.y.abs() < 1e-12);
+    assert!(flat_in.bezier.p1.x >= 0.0);
+    assert!(flat_in.error > free.error);
+
+    // 终点竖直切线：控制点 x 等于终点 x
+    let down_out =
+        BezierFitter::fit_segment_with_tangents(&points, None, Some(Point2D::new(0.0, -1.0)))
+            .unwrap();
+    assert_relative_eq!(down_out.bezier.p1.x, 100.0, epsilon = 1e-12);
+
+    // 两端都约束时取切线交点
+    let both = BezierFitter::fit_segment_with_tangents(
+        &points,
+        Some(Point2D::new(1.0, 1.0)),
+        Some(Point2D::new(1.0, -1.0)),
+    )
+    .unwrap();
+    assert_relative_eq!(both.bezier.p1.x, 50.0, epsilon = 1e-9);
+    assert_relative_eq!(both.bezier.p1.y, 50.0, epsilon = 1e-9);
+
+    // 无法满足的约束返回 None，而不是忽略某一端或让控制点与端点重合
+    let backward =
+        BezierFitter::fit_segment_with_tangents(&points, Some(Point2D::new(-1.0, 0.0)), None);
+    assert!(backward.is_none());
+    let diverging = BezierFitter::fit_segment_with_tangents(
+        &points,
+        Some(Point2D::new(-1.0, 1.0)),
+        Some(Point2D::new(1.0, 1.0)),
+    );
+    assert!(diverging.is_none());
+    let parallel = BezierFitter::fit_segment_with_tangents(
+        &points,
+        Some(Point2D::new(1.0, 1.0)),
+        Some(Point2D::new(1.0, 1.0)),
+    );
+    assert!(parallel.is_none());
+    let unconstrained = BezierFitter::fit_segment_with_tangents(&points, None, None).unwrap();
+    assert_eq!(unconstrained.bezier.p1, free.bezier.p1);
+}
+
+#[test]
+fn test_fit_segment_timed_lengths() {
+    let points: Vec<Point2D> = (0..20)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.3).sin()))
+        .collect();
+    let times: Vec<f64> = (0..20).map(f64::from).collect();
+    let chord = BezierFitter::fit_segment(&points);
+
+    // 空输入和个数不符的时间戳退回弦长参数化，不会越界
+    assert_eq!(BezierFitter::fit_segment_timed(&[], &[]).error, 0.0);
+    let short = BezierFitter::fit_segment_timed(&points, &times[..5]);
+    assert_eq!(short.bezier.p1, chord.bezier.p1);
+    let empty = BezierFitter::fit_segment_timed(&points, &[]);
+    assert_eq!(empty.bezier.p1, chord.bezier.p1);
+    let timed = BezierFitter::fit_segment_timed(&points, &times);
+    assert!(timed.error.is_finite());
+}
+
+#[test]
+fn test_endpoint_policy() {
+    use bezier_dp_fit::{fit_curve, EndpointPolicy};
+
+    // 抛物线上首末采样点带噪声
+    let mut points: Vec<Point2D> = (0..=40)
+        .map(|i| {
+            let x = i as f64;
+            Point2D::new(x, 0.05 * (x - 20.0).powi(2))
+        })
+        .collect();
+    points[0].y += 3.0;
+    points[40].y -= 3.0;
+
+    let fixed = BezierFitter::fit_segment(&points);
+    let free = BezierFitter::fit_segment_free(&points);
+    assert_eq!(fixed.bezier.p0, points[0]);
+    assert!(free.error < fixed.error);
+    assert_ne!(free.bezier.p0, points[0]);
+
+    let config = FitConfig::new(5, 40, 0.5);
+    let averaged = fit_curve(
+        &points,
+        &config
+            .clone()
+            .with_endpoint_policy(EndpointPolicy::LocalAverage { radius: 2 }),
+    );
+    // 相邻分段共用端点
+    for w in averaged.curves.windows(2) {
+        assert_eq!(w[0].p2, w[1].p0);
+    }
+    let first = averaged.curves[0].p0;
+    let expected = (points[0].y + points[1].y + points[2].y) / 3.0;
+    assert_relative_eq!(first.y, expected, epsilon = 1e-12);
+
+    let result = fit_curve(&points, &config.with_endpoint_policy(EndpointPolicy::Free));
+    assert_eq!(result.segment_errors.len(), result.num_segments);
+    assert!(result.segment_errors.iter().all(|&e| e <= 0.5));
+}
+
+#[test]
+fn test_orthogonal_fitter() {
+    // 按参数均匀采样的宽曲线，弦长参数化会低估曲率
+    let curve = bezier_dp_fit::QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(50.0, 100.0),
+        Point2D::new(100.0, 0.0),
+    );
+    let points = curve.sample(40);
+
+    let ls = BezierFitter::fit_segment(&points);
+    let odr = BezierFitter::fit_segment_orthogonal(&points, 20);
+    assert!(odr.error < ls.error);
+    assert!((odr.bezier.p1.y - 100.0).abs() < (ls.bezier.p1.y - 100.0).abs());
+    assert!((odr.bezier.p1.y - 100.0).abs() < 0.5);
+
+    let config = FitConfig::new(5, 40, 0.5).with_fitter(FitterKind::Orthogonal { iterations: 10 });
+    let wave: Vec<Point2D> = (0..150)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.08).sin() * 20.0))
+        .collect();
+    let result = fit_curve(&wave, &config);
+    assert!(result.segment_errors.iter().all(|&e| e <= 0.5));
+    let baseline = fit_curve(&wave, &FitConfig::new(5, 40, 0.5));
+    assert!(result.num_segments <= baseline.num_segments);
+}
+
+#[test]
+fn test_merge_segments() {
+    // 平缓的长弧被 max_segment_len 切成多段
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| {
+            let x = i as f64;
+            Point2D::new(x, 0.002 * (x - 100.0).powi(2))
+        })
+        .collect();
+    let config = FitConfig::new(5, 30, 0.5);
+    let result = fit_curve(&points, &config);
+    assert!(result.num_segments >= 7);
+
+    let merged = result.merge_segments(&points, &config);
+    assert!(merged.num_segments < result.num_segments);
+    assert_eq!(merged.curves.len(), merged.num_segments);
+    assert_eq!(merged.breakpoints.first(), Some(&0));
+    assert_eq!(merged.breakpoints.last(), Some(&199));
+    assert!(merged
+        .breakpoints
+        .iter()
+        .all(|b| result.breakpoints.contains(b)));
+    assert!(merged.segment_errors.iter().all(|&e| e <= config.max_error));
+    assert_relative_eq!(
+        merged.total_error,
+        merged.segment_errors.iter().sum::<f64>(),
+        epsilon = 1e-12
+    );
+
+    // 必经点保留
+    let pinned = config
+        .clone()
+        .with_required_breakpoints([result.breakpoints[3]]);
+    let merged = fit_curve(&points, &pinned).merge_segments(&points, &pinned);
+    assert!(merged.breakpoints.contains(&result.breakpoints[3]));
+}
+
+#[test]
+fn test_simplify_to_coarser_tolerance() {
+    let points: Vec<Point2D> = (0..300)
+        .map(|i| {
+            let x = i as f64;
+            Point2D::new(x, (x * 0.05).sin() * 30.0)
+        })
+        .collect();
+    let config = FitConfig::new(5, 100, 0.05);
+    let fine = fit_curve(&points, &config);
+    let coarse = fine.simplify(5.0);
+
+    assert!(coarse.num_segments < fine.num_segments);
+    assert_eq!(coarse.config.max_error, 5.0);
+    assert_eq!(coarse.breakpoints.first(), Some(&0));
+    assert_eq!(coarse.breakpoints.last(), Some(&299));
+    assert_eq!(coarse.curves[0].p0, fine.curves[0].p0);
+    // 相对原始点的偏差仍在新容差的量级内
+    let worst = coarse.residuals(&points).into_iter().fold(0.0, f64::max);
+    assert!(worst < 5.0, "worst residual {worst}");
+}
+
+#[test]
+fn test_quantize() {
+    let points: Vec<Point2D> = (0..300)
+        .map(|i| {
+            let x = i as f64 * 0.37;
+            Point2D::new(x, (x * 0.13).sin() * 17.3)
+        })
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 60, 0.05));
+    let grid = 0.25;
+    let quantized = result.quantize(&points, grid);
+
+    let on_grid = |v: f64| ((v / grid).round() * grid - v).abs() < 1e-12;
+    for c in &quantized.curves {
+        assert!([c.p0, c.p1, c.p2]
+            .iter()
+            .all(|p| on_grid(p.x) && on_grid(p.y)));
+    }
+    for pair in quantized.curves.windows(2) {
+        assert_eq!(pair[0].p2, pair[1].p0);
+    }
+    assert_eq!(quantized.breakpoints, result.breakpoints);
+    // 误差按取整后的曲线重新计算
+    assert_ne!(quantized.total_error, result.total_error);
+    let expected: f64 = quantized
+        .curves
+        .iter()
+        .zip(quantized.breakpoints.windows(2))
+        .map(|(c, w)| BezierFitter::compute_error(c, &points[w[0]..=w[1]]))
+        .sum();
+    assert_relative_eq!(quantized.total_error, expected, max_relative = 1e-12);
+    assert_eq!(quantized.segment_info.len(), quantized.curves.len());
+
+    // 取整到与原值同一网格时不再变化
+    let again = quantized.quantize(&points, grid);
+    assert_eq!(again.control_points(), quantized.control_points());
+    assert_eq!(
+        result.quantize(&points, 0.0).control_points(),
+        result.control_points()
+    );
+}
+
+#[test]
+fn test_transform_equivariance() {
+    let points: Vec<Point2D> = (0..240)
+        .map(|i| {
+            let t = i as f64 * 0.05;
+            Point2D::new(
+                t * 9.0 + (t * 1.7).cos(),
+                (t * 0.9).sin() * 14.0 + t * t * 0.3,
+            )
+        })
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(6, 50, 0.05));
+    for m in [
+        Transform2D::translate(125.0, -40.0),
+        Transform2D::rotate(1.1),
+        Transform2D::scale(-1.0, 1.0),
+        Transform2D::translate(3.0, 7.0).then(&Transform2D::rotate(-2.4)),
+    ] {
+        let report = result.transform_equivariance_check(&points, &m);
+        assert!(report.passed(), "{report:?}");
+    }
+    // 缩放改变误差的尺度，不保证通过
+    let report = result.transform_equivariance_check(&points, &Transform2D::scale(20.0, 20.0));
+    assert!(!report.same_breakpoints);
+}
+
+#[test]
+fn test_interpolate_catmull_rom() {
+    let waypoints = vec![
+        Point2D::new(0.0, 0.0),
+        Point2D::new(10.0, 5.0),
+        Point2D::new(20.0, 5.0),
+        Point2D::new(20.0, 5.0),
+        Point2D::new(35.0, -10.0),
+        Point2D::new(50.0, 0.0),
+    ];
+    for alpha in [0.0, 0.5, 1.0] {
+        let result = interpolate_catmull_rom(&waypoints, alpha);
+        assert_eq!(result.num_segments, waypoints.len() - 1);
+        assert_eq!(result.breakpoints, (0..waypoints.len()).collect::<Vec<_>>());
+        assert_eq!(result.total_error, 0.0);
+        assert_eq!(result.segment_info.len(), result.curves.len());
+        for (k, c) in result.curves.iter().enumerate() {
+            assert_eq!(c.p0, waypoints[k]);
+            assert_eq!(c.p2, waypoints[k + 1]);
+            assert!(c.p1.x.is_finite() && c.p1.y.is_finite());
+        }
+    }
+
+    // 等距共线的点插值为直线，控制点在弦的中点
+    let line: Vec<Point2D> = (0..5).map(|i| Point2D::new(i as f64 * 2.0, 1.0)).collect();
+    let straight = interpolate_catmull_rom(&line, 0.5);
+    for c in &straight.curves {
+        assert_relative_eq!(c.p1.x, (c.p0.x + c.p2.x) / 2.0, epsilon = 1e-12);
+        assert_relative_eq!(c.p1.y, 1.0, epsilon = 1e-12);
+    }
+    assert!(interpolate_catmull_rom(&line[..1], 0.5).curves.is_empty());
+}
+
+#[test]
+fn test_deterministic_ties() {
+    // 所有区间误差都为零时任意三段分法等价，每步取最小的起点
+    let line: Vec<Point2D> = (0..61).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    let mut cache: ErrorCache = (9..61usize)
+        .flat_map(|i| (i.saturating_sub(29)..=i.saturating_sub(9)).map(move |j| (j, i)))
+        .map(|(j, i)| {
+            let (p0, p2) = (line[j], line[i]);
+            let fit = FitError {
+                bezier: QuadraticBezier::new(p0, p0.lerp(&p2, 0.5), p2),
+                error: 0.0,
+                arc: None,
+            };
+            ((j, i), fit)
+        })
+        .collect();
+    let config = FitConfig::new(10, 30, 0.1).with_deterministic(true);
+    let result = DPOptimizer::optimize_with_cache(&line, &config, &mut cache);
+    assert_eq!(result.breakpoints, vec![0, 10, 31, 60]);
+
+    let points: Vec<Point2D> = (0..300)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.07).sin() * 12.0))
+        .collect();
+    let config = FitConfig::new(8, 50, 0.3).with_deterministic(true);
+    let reference = fit_curve(&points, &config).to_bytes().unwrap();
+    for threads in [1, 3, 8] {
+        let mut result = fit_curve(&points, &config.clone().with_num_threads(threads));
+        result.config = config.clone();
+        assert_eq!(result.to_bytes().unwrap(), reference);
+    }
+
+    // 缓存按键排序序列化，与哈希表的迭代顺序无关
+    let a = ErrorCache::build(&points, 8..=50);
+    let b = ErrorCache::build(&points, 8..=50);
+    assert_eq!(a.to_bytes().unwrap(), b.to_bytes().unwrap());
+    let restored = ErrorCache::from_bytes(&a.to_bytes().unwrap()).unwrap();
+    assert_eq!(restored.len(), a.len());
+}
+
+#[test]
+fn test_limit_semantics() {
+    // 超过上限的误差一律为正无穷，各种带上限的误差计算一致
+    let points: Vec<Point2D> = (0..40)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.3).sin() * 8.0))
+        .collect();
+    let full = BezierFitter::fit_segment(&points).error;
+    assert!(full > 0.5);
+    assert_eq!(
+        BezierFitter::fit_segment_with_limit(&points, 0.5).error,
+        f64::INFINITY
+    );
+    assert_eq!(
+        BezierFitter::fit_segment_f32(&points, 0.5).error,
+        f64::INFINITY
+    );
+    assert_eq!(
+        BezierFitter::fit_segment_max(&points, 0.5).error,
+        f64::INFINITY
+    );
+    assert_eq!(
+        BezierFitter::fit_segment_with_limit(&points, f64::INFINITY).error,
+        full
+    );
+    assert_eq!(BezierFitter::apply_limit(full, full), full);
+
+    let config = FitConfig::new(5, 30, 0.5);
+    let cache = DPOptimizer::compute_error_cache(&points, &config);
+    let mut exceeded = 0;
+    for end in 5..points.len() {
+        for start in end.saturating_sub(29)..=end - 4 {
+            let error = cache.get(start, end).unwrap().error;
+            assert!(error <= config.max_error || error == f64::INFINITY);
+            exceeded += error.is_infinite() as usize;
+        }
+    }
+    assert!(exceeded > 0);
+}
+
+#[test]
+fn test_parallel_dp_rows() {
+    // 候选行足够长时 DP 分块并行归约，结果须与逐个松弛的朴素 DP 一致（误差有大量并列）
+    let n: usize = 700;
+    let (min_len, max_len): (usize, usize) = (3, 600);
+    let line: Vec<Point2D> = (0..n).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    let error = |j: usize, i: usize| ((j * 31 + i * 17) % 7) as f64 * 0.01;
+    let cache: ErrorCache = (min_len..n)
+        .flat_map(|i| {
+            (i.saturating_sub(max_len - 1)..=i.saturating_sub(min_len - 1)).map(move |j| (j, i))
+        })
+        .map(|(j, i)| {
+            let (p0, p2) = (line[j], line[i]);
+            let fit = FitError {
+                bezier: QuadraticBezier::new(p0, p0.lerp(&p2, 0.5), p2),
+                error: error(j, i),
+                arc: None,
+            };
+            ((j, i), fit)
+        })
+        .collect();
+
+    for objective in [
+        Objective::MinSegments,
+        Objective::Penalized { lambda: 0.03 },
+    ] {
+        let cost = |seg: usize, err: f64| match objective {
+            Objective::MinSegments => (seg as f64, err),
+            Objective::Penalized { lambda } => (err + lambda * seg as f64, 0.0),
+        };
+        let mut seg = vec![usize::MAX; n];
+        let mut err = vec![f64::INFINITY; n];
+        let mut parent = vec![0; n];
+        (seg[0], err[0]) = (0, 0.0);
+        for i in min_len..n {
+            for j in i.saturating_sub(max_len - 1)..=i + 1 - min_len {
+                if seg[j] == usize::MAX {
+                    continue;
+                }
+                let (s, e) = (seg[j] + 1, err[j] + error(j, i));
+                if seg[i] == usize::MAX || cost(s, e) < cost(seg[i], err[i]) {
+                    (seg[i], err[i], parent[i]) = (s, e, j);
+                }
+            }
+        }
+        let mut expected = vec![n - 1];
+        while let Some(&i) = expected.last().filter(|&&i| i > 0) {
+            expected.push(parent[i]);
+        }
+        expected.reverse();
+
+        let config = FitConfig::new(min_len, max_len, 0.1).with_objective(objective);
+        for threads in [1, 4] {
+            let config = config.clone().with_num_threads(threads);
+            let result = DPOptimizer::optimize_with_cache(&line, &config, &mut cache.clone());
+            assert_eq!(result.breakpoints, expected);
+            assert_eq!(result.total_error, err[n - 1]);
+        }
+    }
+}
+
+#[test]
+fn test_multiresolution() {
+    let points: Vec<Point2D> = (0..1200)
+        .map(|i| {
+            let t = i as f64 * 0.025;
+            Point2D::new(t * 40.0, (t * 1.3).sin() * 30.0 + (t * 0.4).cos() * 10.0)
+        })
+        .collect();
+    let config = FitConfig::new(10, 60, 0.05).with_required_breakpoints([617]);
+    let exact = fit_curve(&points, &config);
+    let approx = fit_curve(&points, &config.clone().with_multiresolution(6, 6));
+
+    assert_eq!(approx.status, FitStatus::Optimal);
+    assert_eq!(approx.breakpoints.first(), Some(&0));
+    assert_eq!(approx.breakpoints.last(), Some(&(points.len() - 1)));
+    assert!(approx.breakpoints.contains(&617));
+    for (w, &error) in approx.breakpoints.windows(2).zip(&approx.segment_errors) {
+        assert!((10..=60).contains(&(w[1] - w[0] + 1)));
+        assert!(error <= config.max_error);
+    }
+    // 近似解的分段数接近精确解
+    assert!(approx.num_segments >= exact.num_segments);
+    assert!(approx.num_segments as f64 <= exact.num_segments as f64 * 1.3);
+}
+
+#[test]
+fn test_fit_curve_chunked() {
+    let points: Vec<Point2D> = (0..900)
+        .map(|i| {
+            let t = i as f64 * 0.03;
+            Point2D::new(t * 30.0, (t * 1.1).sin() * 25.0)
+        })
+        .collect();
+    let config = FitConfig::new(10, 60, 0.05).with_required_breakpoints([500]);
+    let whole = fit_curve(&points, &config);
+    let chunked = fit_curve_chunked(&points, &config, 250, 80);
+
+    assert_eq!(chunked.status, FitStatus::Optimal);
+    assert_eq!(chunked.num_segments, chunked.curves.len());
+    assert_eq!(chunked.breakpoints.len(), chunked.num_segments + 1);
+    assert_eq!(chunked.breakpoints.first(), Some(&0));
+    assert_eq!(chunked.breakpoints.last(), Some(&(points.len() - 1)));
+    assert!(chunked.breakpoints.contains(&500));
+    for (w, &error) in chunked.breakpoints.windows(2).zip(&chunked.segment_errors) {
+        assert!((10..=60).contains(&(w[1] - w[0] + 1)));
+        assert!(error <= config.max_error);
+    }
+    for (pair, info) in chunked.curves.windows(2).zip(&chunked.segment_info) {
+        assert_eq!(pair[0].p2, pair[1].p0);
+        assert!(info.end_index > info.start_index);
+    }
+    assert_relative_eq!(
+        chunked.total_error,
+        chunked.segment_errors.iter().sum::<f64>()
+    );
+    // 重叠足够时分段数与整体拟合相差很小
+    assert!(chunked.num_segments <= whole.num_segments + 2);
+}
+
+#[test]
+fn test_fit_curve_serial_matches_dp() {
+    let points: Vec<Point2D> = (0..300)
+        .map(|i| {
+            let t = i as f64 * 0.04;
+            Point2D::new(t * 20.0, (t * 1.3).sin() * 15.0)
+        })
+        .collect();
+    let config = FitConfig::new(5, 40, 0.05);
+    let result = fit_curve(&points, &config);
+    let serial = fit_curve_serial(&points, 5, 40, 0.05).unwrap();
+
+    assert_eq!(serial.breakpoints, result.breakpoints);
+    assert_eq!(serial.curves.len(), result.num_segments);
+    assert_relative_eq!(serial.total_error, result.total_error, epsilon = 1e-9);
+    // 段长约束无法满足时返回 None
+    assert!(fit_curve_serial(&points[..3], 5, 40, 0.05).is_none());
+}
+
+#[test]
+fn test_error_overrides() {
+    let points: Vec<Point2D> = (0..240)
+        .map(|i| {
+            let t = i as f64 * 0.05;
+            Point2D::new(t * 10.0, (t * 1.7).sin() * 12.0)
+        })
+        .collect();
+    let base = FitConfig::new(5, 60, 0.5);
+    let config = base.clone().with_error_overrides([(100..140, 0.001)]);
+    assert_eq!(config.max_error_for(0, 99), 0.5);
+    assert_eq!(config.max_error_for(90, 100), 0.001);
+    assert_eq!(config.max_error_for(139, 160), 0.001);
+    assert_eq!(config.max_error_for(140, 160), 0.5);
+
+    let loose = fit_curve(&points, &base);
+    let result = fit_curve(&points, &config);
+    assert_eq!(result.status, FitStatus::Optimal);
+    assert!(result.num_segments > loose.num_segments);
+    for (w, &error) in result.breakpoints.windows(2).zip(&result.segment_errors) {
+        assert!(error <= config.max_error_for(w[0], w[1]));
+    }
+
+    // 不建缓存时逐行比较使用同样的上限
+    let uncached = fit_curve(&points, &config.clone().with_cache_mode(CacheMode::None));
+    assert_eq!(uncached.breakpoints, result.breakpoints);
+
+    let invalid = config.with_error_overrides([(0..10, -1.0)]);
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn test_adaptive_window() {
+    // 长直线接一个小半径的整圆
+    let mut points: Vec<Point2D> = (0..150).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    points.extend((1..150).map(|i| {
+        let t = i as f64 / 150.0 * std::f64::consts::TAU;
+        Point2D::new(149.0 + 20.0 * t.sin(), 20.0 - 20.0 * t.cos())
+    }));
+    let base = FitConfig::new(5, 120, 0.05);
+    let adaptive = base.clone().with_adaptive_window(AdaptiveWindow::default());
+    assert!(adaptive.validate().is_ok());
+
+    let exact = fit_curve(&points, &base);
+    let result = fit_curve(&points, &adaptive);
+    assert_eq!(result.status, FitStatus::Optimal);
+    assert!(result.num_segments <= exact.num_segments + 1);
+    assert!(result.segment_errors.iter().all(|&e| e <= 0.05));
+    assert_eq!(result.config.max_segment_lens, Vec::<usize>::new());
+    let uncached = fit_curve(&points, &adaptive.clone().with_cache_mode(CacheMode::None));
+    assert_eq!(uncached.breakpoints, result.breakpoints);
+
+    // 逐终点的段长上限直接缩小候选区间
+    let narrowed = FitConfig {
+        max_segment_lens: vec![20; points.len()],
+        ..base.clone()
+    };
+    assert_eq!(narrowed.max_segment_len_at(10), 20);
+    assert_eq!(narrowed.max_segment_len_at(points.len()), 120);
+    assert!(
+        DPOptimizer::estimate(points.len(), &narrowed).intervals
+            < DPOptimizer::estimate(points.len(), &base).intervals
+    );
+    let invalid = FitConfig {
+        max_segment_lens: vec![2; 10],
+        ..base
+    };
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn test_snap_endpoints() {
+    // 正弦曲线叠加确定性的噪声
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| {
+            let x = i as f64 * 0.5;
+            let noise = ((i * 7919) % 13) as f64 / 13.0 - 0.5;
+            Point2D::new(x, (x * 0.2).sin() * 10.0 + noise * 0.6)
+        })
+        .collect();
+    let config = FitConfig::new(5, 40, 0.2);
+    assert!(config.snap_endpoints);
+    let snapped = fit_curve(&points, &config);
+    let refined = fit_curve(&points, &config.clone().with_snap_endpoints(false));
+
+    assert_eq!(refined.breakpoints, snapped.breakpoints);
+    assert!(refined.total_error < snapped.total_error);
+    for (pair, w) in refined
+        .curves
+        .windows(2)
+        .zip(refined.breakpoints.windows(3))
+    {
+        // 相邻两段共用端点，且端点不会离开断点太远
+        assert_eq!(pair[0].p2, pair[1].p0);
+        assert!(pair[0].p2.distance_to(&points[w[1]]) <= 1.2);
+    }
+    assert_eq!(refined.curves.first().unwrap().p0, points[0]);
+    assert!(refined.segment_errors.iter().all(|&e| e <= 0.2));
+
+    // 旧的序列化结果没有该字段时仍固定端点
+    let restored: FitConfig =
+        serde_json::from_str(r#"{"min_segment_len":5,"max_segment_len":40,"max_error":0.2}"#)
+            .unwrap();
+    assert!(restored.snap_endpoints);
+}
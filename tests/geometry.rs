@@ -0,0 +1,496 @@
+use approx::assert_relative_eq;
+use bezier_dp_fit::{
+    fit_curve, BoundingBox, FitConfig, PathMetric, Point2D, QuadraticBezier, Transform2D,
+};
+
+#[test]
+fn test_bezier_bounding_box() {
+    let curve = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(1.0, 2.0),
+        Point2D::new(2.0, 0.0),
+    );
+    let bbox = curve.bounding_box();
+    assert_relative_eq!(bbox.min.x, 0.0);
+    assert_relative_eq!(bbox.max.x, 2.0);
+    assert_relative_eq!(bbox.min.y, 0.0);
+    // 极值在 t = 0.5 处，y = 1.0（而不是控制点的 2.0）
+    assert_relative_eq!(bbox.max.y, 1.0);
+}
+
+#[test]
+fn test_result_bounding_box() {
+    let points: Vec<Point2D> = (0..80)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).sin() * 20.0))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 40, 1.0));
+
+    let bbox = result.bounding_box().unwrap();
+    for p in result.sample_points(20) {
+        let p = Point2D::from(p);
+        assert!(bbox.min.x - 1e-9 <= p.x && p.x <= bbox.max.x + 1e-9);
+        assert!(bbox.min.y - 1e-9 <= p.y && p.y <= bbox.max.y + 1e-9);
+    }
+
+    assert!(BoundingBox::from_points(&[]).is_none());
+
+    // 手工构造的曲线：控制点 (1, 2) 和 (4, -4) 都在紧包围盒之外，第三条在 t = 4/7 处 x 取极大值
+    let mut hand = result.clone();
+    hand.curves = vec![
+        QuadraticBezier::new(
+            Point2D::new(0.0, 0.0),
+            Point2D::new(1.0, 2.0),
+            Point2D::new(2.0, 0.0),
+        ),
+        QuadraticBezier::new(
+            Point2D::new(2.0, 0.0),
+            Point2D::new(4.0, -4.0),
+            Point2D::new(6.0, 0.0),
+        ),
+        QuadraticBezier::new(
+            Point2D::new(6.0, 0.0),
+            Point2D::new(10.0, 1.0),
+            Point2D::new(7.0, 3.0),
+        ),
+    ];
+    let bbox = hand.bounding_box().unwrap();
+    assert_relative_eq!(bbox.min.x, 0.0);
+    assert_relative_eq!(bbox.min.y, -2.0);
+    assert_relative_eq!(bbox.max.x, 406.0 / 49.0, epsilon = 1e-12);
+    assert_relative_eq!(bbox.max.y, 3.0);
+
+    hand.curves.truncate(1);
+    let bbox = hand.bounding_box().unwrap();
+    assert_eq!(
+        bbox,
+        BoundingBox::new(Point2D::new(0.0, 0.0), Point2D::new(2.0, 1.0))
+    );
+
+    hand.curves.clear();
+    assert!(hand.bounding_box().is_none());
+}
+
+#[test]
+fn test_split_and_subdivide() {
+    let curve = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(4.0, 8.0),
+        Point2D::new(10.0, 2.0),
+    );
+
+    let (left, right) = curve.split(0.3);
+    assert_eq!(left.p0, curve.p0);
+    assert_eq!(right.p2, curve.p2);
+    assert_eq!(left.p2, right.p0);
+    for i in 0..=10 {
+        let s = i as f64 / 10.0;
+        let a = left.evaluate(s);
+        let b = curve.evaluate(0.3 * s);
+        assert_relative_eq!(a.x, b.x, epsilon = 1e-9);
+        assert_relative_eq!(a.y, b.y, epsilon = 1e-9);
+    }
+
+    let pieces = curve.subdivide(4);
+    assert_eq!(pieces.len(), 4);
+    for (k, piece) in pieces.iter().enumerate() {
+        let start = curve.evaluate(k as f64 / 4.0);
+        assert_relative_eq!(piece.p0.x, start.x, epsilon = 1e-9);
+        assert_relative_eq!(piece.p0.y, start.y, epsilon = 1e-9);
+    }
+    assert_eq!(pieces[3].p2, curve.p2);
+    assert_eq!(curve.subdivide(0).len(), 1);
+}
+
+#[test]
+fn test_degree_elevation() {
+    let curve = QuadraticBezier::new(
+        Point2D::new(1.0, 1.0),
+        Point2D::new(5.0, 9.0),
+        Point2D::new(12.0, -3.0),
+    );
+    let cubic = curve.to_cubic();
+    for i in 0..=20 {
+        let t = i as f64 / 20.0;
+        let a = curve.evaluate(t);
+        let b = cubic.evaluate(t);
+        assert_relative_eq!(a.x, b.x, epsilon = 1e-9);
+        assert_relative_eq!(a.y, b.y, epsilon = 1e-9);
+    }
+
+    let points: Vec<Point2D> = (0..50)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.15).sin() * 8.0))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 25, 1.0));
+    let path = result.to_svg_path_cubic();
+    assert!(path.starts_with("M"));
+    assert_eq!(path.matches('C').count(), result.num_segments);
+    assert_eq!(result.to_cubic_curves().len(), result.num_segments);
+}
+
+#[test]
+fn test_transform_compose_and_inverse() {
+    let m = Transform2D::scale(2.0, 3.0)
+        .then(&Transform2D::rotate(std::f64::consts::FRAC_PI_2))
+        .then(&Transform2D::translate(5.0, -1.0));
+    let p = Point2D::new(1.0, 1.0);
+    // 缩放 -> (2, 3)，旋转 90° -> (-3, 2)，平移 -> (2, 1)
+    let q = p.transform(&m);
+    assert_relative_eq!(q.x, 2.0, epsilon = 1e-12);
+    assert_relative_eq!(q.y, 1.0, epsilon = 1e-12);
+
+    let back = q.transform(&m.inverse().unwrap());
+    assert_relative_eq!(back.x, p.x, epsilon = 1e-12);
+    assert_relative_eq!(back.y, p.y, epsilon = 1e-12);
+    assert!(Transform2D::scale(0.0, 1.0).inverse().is_none());
+
+    let curve = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(3.0, 4.0),
+        Point2D::new(6.0, 0.0),
+    );
+    let moved = curve.transform(&m);
+    let a = moved.evaluate(0.4);
+    let b = curve.evaluate(0.4).transform(&m);
+    assert_relative_eq!(a.x, b.x, epsilon = 1e-12);
+    assert_relative_eq!(a.y, b.y, epsilon = 1e-12);
+
+    let points: Vec<Point2D> = (0..30).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 30, 1.0));
+    let shifted = result.transform(&Transform2D::translate(10.0, 0.0));
+    assert_eq!(shifted.num_segments, result.num_segments);
+    assert_relative_eq!(shifted.curves[0].p0.x, result.curves[0].p0.x + 10.0);
+}
+
+#[test]
+fn test_evaluate_by_arclength() {
+    let curve = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(5.0, 10.0),
+        Point2D::new(10.0, 0.0),
+    );
+    let length = curve.arc_length();
+    for s in [0.0, 0.1, 3.0, length / 2.0, length - 0.01] {
+        let t = curve.parameter_at_length(s);
+        assert_relative_eq!(curve.arc_length_to(t), s, epsilon = 1e-9);
+    }
+    // 对称曲线的弧长中点就是 t = 0.5
+    assert_relative_eq!(curve.parameter_at_length(length / 2.0), 0.5, epsilon = 1e-9);
+
+    let points: Vec<Point2D> = (0..=100).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 30, 0.1));
+    assert!(result.num_segments > 1);
+    assert_relative_eq!(result.arc_length(), 100.0, epsilon = 1e-6);
+    let p = result.evaluate(0.37).unwrap();
+    assert_relative_eq!(p.x, 37.0, epsilon = 1e-6);
+    assert_relative_eq!(
+        result.point_at_arclength(62.5).unwrap().x,
+        62.5,
+        epsilon = 1e-6
+    );
+    assert_eq!(result.evaluate(-1.0), Some(points[0]));
+    assert_relative_eq!(
+        result.point_at_arclength(1e9).unwrap().x,
+        100.0,
+        epsilon = 1e-9
+    );
+
+    let empty = fit_curve(&Vec::<Point2D>::new(), &FitConfig::default());
+    assert_eq!(empty.evaluate(0.5), None);
+}
+
+#[test]
+fn test_derivative_tangent_normal() {
+    let curve = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(5.0, 10.0),
+        Point2D::new(10.0, 0.0),
+    );
+    assert_eq!(curve.derivative(0.0), Point2D::new(10.0, 20.0));
+    assert_eq!(curve.derivative(0.5), Point2D::new(10.0, 0.0));
+    // 与差分比较
+    let h = 1e-6;
+    let (a, b) = (curve.evaluate(0.3 - h), curve.evaluate(0.3 + h));
+    let d = curve.derivative(0.3);
+    assert_relative_eq!(d.x, (b.x - a.x) / (2.0 * h), epsilon = 1e-6);
+    assert_relative_eq!(d.y, (b.y - a.y) / (2.0 * h), epsilon = 1e-6);
+
+    assert_eq!(curve.tangent(0.5), Point2D::new(1.0, 0.0));
+    assert_eq!(curve.normal(0.5), Point2D::new(0.0, 1.0));
+    let n = curve.normal(0.2);
+    let t = curve.tangent(0.2);
+    assert_relative_eq!(n.x * t.x + n.y * t.y, 0.0, epsilon = 1e-12);
+    assert_relative_eq!(t.x.hypot(t.y), 1.0, epsilon = 1e-12);
+
+    // 控制点与起点重合时起点导数为零，退回弦方向
+    let degenerate = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(0.0, 0.0),
+        Point2D::new(0.0, 4.0),
+    );
+    assert_eq!(degenerate.tangent(0.0), Point2D::new(0.0, 1.0));
+
+    // 沿 y = x 的路径航向恒为 45°
+    let points: Vec<Point2D> = (0..=60).map(|i| Point2D::new(i as f64, i as f64)).collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 20, 0.1));
+    let heading = result.tangent_at_arclength(30.0).unwrap();
+    assert_relative_eq!(
+        heading.y.atan2(heading.x),
+        std::f64::consts::FRAC_PI_4,
+        epsilon = 1e-6
+    );
+}
+
+#[test]
+fn test_offset_path() {
+    // 逆时针的半圆，左侧是圆心一侧
+    let points: Vec<Point2D> = (0..=180)
+        .map(|i| {
+            let a = (i as f64).to_radians();
+            Point2D::new(50.0 * a.cos(), 50.0 * a.sin())
+        })
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 60, 0.01));
+    for (distance, radius) in [(5.0, 45.0), (-5.0, 55.0)] {
+        let offset = result.offset(distance, 0.05);
+        assert_eq!(offset.num_segments, offset.curves.len());
+        assert!(offset.breakpoints.is_empty());
+        for w in offset.curves.windows(2) {
+            assert_eq!(w[0].p2, w[1].p0);
+        }
+        for (x, y) in offset.sample_points(16) {
+            assert!((x.hypot(y) - radius).abs() < 0.2, "{x} {y}");
+        }
+    }
+
+    // 直角折线：外侧的缺口用直线补上，路径保持连续
+    let mut corner: Vec<Point2D> = (0..=30).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    corner.extend((1..=30).map(|i| Point2D::new(30.0, i as f64)));
+    let config = FitConfig::new(5, 40, 0.01).with_required_breakpoints([30]);
+    let offset = fit_curve(&corner, &config).offset(-2.0, 0.01);
+    for w in offset.curves.windows(2) {
+        assert_eq!(w[0].p2, w[1].p0);
+    }
+    assert_eq!(offset.curves[0].p0, Point2D::new(0.0, -2.0));
+    assert_eq!(offset.curves.last().unwrap().p2, Point2D::new(32.0, 30.0));
+}
+
+#[test]
+fn test_self_intersections() {
+    let a = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(5.0, 10.0),
+        Point2D::new(10.0, 0.0),
+    );
+    let b = QuadraticBezier::new(
+        Point2D::new(0.0, 2.0),
+        Point2D::new(5.0, 2.0),
+        Point2D::new(10.0, 2.0),
+    );
+    let hits = a.intersections(&b);
+    assert_eq!(hits.len(), 2);
+    for (s, u) in hits {
+        let (p, q) = (a.evaluate(s), b.evaluate(u));
+        assert_relative_eq!(p.y, 2.0, epsilon = 1e-9);
+        assert!(p.distance_to(&q) < 1e-9);
+    }
+
+    // 8 字形：两个相切的圆只在中心交叉一次
+    let points: Vec<Point2D> = (0..=720)
+        .map(|i| {
+            let a = (i as f64 / 2.0).to_radians();
+            Point2D::new(30.0 * (2.0 * a).sin(), 30.0 * a.sin())
+        })
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 60, 0.01));
+    let crossings = result.self_intersections();
+    assert!(!crossings.is_empty());
+    for &(i, ti, j, tj, p) in &crossings {
+        assert!(i < j);
+        assert!(p.x.hypot(p.y) < 0.1, "{p:?}");
+        assert!(result.curves[j].evaluate(tj).distance_to(&p) < 1e-6);
+        assert!((0.0..=1.0).contains(&ti));
+    }
+
+    // 简单的闭合圆没有自交
+    let circle: Vec<Point2D> = (0..=360)
+        .map(|i| {
+            let a = (i as f64).to_radians();
+            Point2D::new(20.0 * a.cos(), 20.0 * a.sin())
+        })
+        .collect();
+    assert!(fit_curve(&circle, &FitConfig::new(5, 60, 0.01))
+        .self_intersections()
+        .is_empty());
+}
+
+#[test]
+fn test_signed_area_and_winding() {
+    let circle: Vec<Point2D> = (0..=360)
+        .map(|i| {
+            let a = (i as f64).to_radians();
+            Point2D::new(10.0 + 20.0 * a.cos(), 5.0 + 20.0 * a.sin())
+        })
+        .collect();
+    let config = FitConfig::new(5, 60, 0.01);
+    let ccw = fit_curve(&circle, &config);
+    let area = std::f64::consts::PI * 400.0;
+    assert_relative_eq!(ccw.signed_area(), area, max_relative = 1e-2);
+    // 解析积分与密集采样的多边形面积一致
+    let samples = ccw.sample_points(2000);
+    let shoelace: f64 = samples
+        .iter()
+        .zip(samples.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - a.1 * b.0)
+        .sum::<f64>()
+        / 2.0;
+    assert_relative_eq!(ccw.signed_area(), shoelace, max_relative = 1e-5);
+    assert_eq!(ccw.winding_of(Point2D::new(10.0, 5.0)), 1);
+    assert_eq!(ccw.winding_of(Point2D::new(29.0, 5.0)), 1);
+    assert_eq!(ccw.winding_of(Point2D::new(31.0, 5.0)), 0);
+    assert_eq!(ccw.winding_of(Point2D::new(-50.0, 5.0)), 0);
+    // 射线恰好穿过分段端点时只计一次
+    let joint = ccw.curves[1].p0;
+    assert_eq!(ccw.winding_of(Point2D::new(joint.x - 1.0, joint.y)), 1);
+
+    let reversed: Vec<Point2D> = circle.iter().rev().copied().collect();
+    let cw = fit_curve(&reversed, &config);
+    assert_relative_eq!(cw.signed_area(), -area, max_relative = 1e-2);
+    assert_eq!(cw.winding_of(Point2D::new(10.0, 5.0)), -1);
+
+    // 未闭合的折线按直线闭合：直角三角形
+    let mut open: Vec<Point2D> = (0..=10).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    open.extend((1..=10).map(|i| Point2D::new(10.0, i as f64)));
+    let triangle = fit_curve(
+        &open,
+        &FitConfig::new(3, 20, 1e-6).with_required_breakpoints([10]),
+    );
+    assert_relative_eq!(triangle.signed_area(), 50.0, epsilon = 1e-6);
+    assert_eq!(triangle.winding_of(Point2D::new(8.0, 2.0)), 1);
+    assert_eq!(triangle.winding_of(Point2D::new(2.0, 8.0)), 0);
+}
+
+#[test]
+fn test_flatten_with_tolerance() {
+    fn distance_to_polyline(p: Point2D, polyline: &[Point2D]) -> f64 {
+        polyline
+            .windows(2)
+            .map(|w| {
+                let (dx, dy) = (w[1].x - w[0].x, w[1].y - w[0].y);
+                let len2 = dx * dx + dy * dy;
+                let t = if len2 > 0.0 {
+                    (((p.x - w[0].x) * dx + (p.y - w[0].y) * dy) / len2).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                p.distance_to(&w[0].lerp(&w[1], t))
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    let points: Vec<Point2D> = (0..200)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.05).sin() * 30.0))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 60, 0.05));
+    for tolerance in [0.5, 0.05, 0.005] {
+        let polyline = result.flatten(tolerance);
+        assert_eq!(polyline[0], result.curves[0].p0);
+        assert_eq!(*polyline.last().unwrap(), result.curves.last().unwrap().p2);
+        for curve in &result.curves {
+            for p in curve.sample(50) {
+                assert!(distance_to_polyline(p, &polyline) <= tolerance * 1.0001);
+            }
+        }
+    }
+    assert!(result.flatten(0.5).len() < result.flatten(0.005).len());
+
+    // 直线只需两个端点
+    let line = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(3.0, 3.0),
+        Point2D::new(10.0, 10.0),
+    );
+    assert_eq!(line.flatten(1e-6).len(), 2);
+}
+
+#[test]
+fn test_path_distance() {
+    let points: Vec<Point2D> = (0..150)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.04).sin() * 15.0))
+        .collect();
+    let config = FitConfig::new(10, 60, 0.5);
+    let result = fit_curve(&points, &config);
+    for metric in [PathMetric::Hausdorff, PathMetric::Frechet] {
+        assert_eq!(result.distance_to(&result, metric), 0.0);
+    }
+
+    // 同心圆弧，半径相差 3：两种距离都是 3
+    let arc = |r: f64| -> Vec<Point2D> {
+        (0..200)
+            .map(|i| {
+                let a = i as f64 / 199.0 * std::f64::consts::PI;
+                Point2D::new(r * a.cos(), r * a.sin())
+            })
+            .collect()
+    };
+    let inner = fit_curve(&arc(50.0), &config);
+    let outer = fit_curve(&arc(53.0), &config);
+    for metric in [PathMetric::Hausdorff, PathMetric::Frechet] {
+        assert_relative_eq!(inner.distance_to(&outer, metric), 3.0, epsilon = 0.05);
+    }
+
+    // 反向路径：Hausdorff 不变，Fréchet 至少为首末端点间距离
+    let reversed: Vec<Point2D> = points.iter().rev().copied().collect();
+    let backwards = fit_curve(&reversed, &config);
+    assert!(result.distance_to(&backwards, PathMetric::Hausdorff) < 0.5);
+    let frechet = result.distance_to(&backwards, PathMetric::Frechet);
+    assert!(frechet >= points[0].distance_to(&points[149]) - 1e-9);
+    assert!(frechet >= result.distance_to(&backwards, PathMetric::Hausdorff));
+}
+
+#[test]
+fn test_to_bspline() {
+    // 分段处样条的参数与各段参数线性对应，逐段比较
+    let check = |result: &bezier_dp_fit::FitResult| {
+        let spline = result.to_bspline().unwrap();
+        assert_eq!(spline.knots.len(), spline.control_points.len() + 3);
+        let mut breaks = spline.knots.clone();
+        breaks.dedup();
+        assert_eq!(breaks.len(), result.curves.len() + 1);
+        for (k, curve) in result.curves.iter().enumerate() {
+            for t in [0.0, 0.3, 0.5, 0.9, 1.0] {
+                let u = breaks[k] + t * (breaks[k + 1] - breaks[k]);
+                let p = spline.evaluate(u);
+                let q = curve.evaluate(t);
+                assert!(
+                    p.distance_to(&q) < 1e-9,
+                    "segment {k} t {t}: {p:?} vs {q:?}"
+                );
+            }
+        }
+        spline
+    };
+
+    // 共线的分段处处 G1，只有单节点
+    let line: Vec<Point2D> = (0..200)
+        .map(|i| Point2D::new(i as f64 + (i % 3) as f64 * 0.2, 0.0))
+        .collect();
+    let straight = fit_curve(&line, &FitConfig::new(5, 40, 0.01));
+    assert!(straight.num_segments > 1);
+    let spline = check(&straight);
+    assert_eq!(spline.control_points.len(), straight.num_segments + 2);
+
+    // 一般的拟合结果在分段处有转角，取二重节点
+    let wave: Vec<Point2D> = (0..300)
+        .map(|i| {
+            let x = i as f64 * 0.3;
+            Point2D::new(x, (x * 0.2).sin() * 8.0)
+        })
+        .collect();
+    let result = fit_curve(&wave, &FitConfig::new(5, 60, 0.05));
+    let spline = check(&result);
+    assert_eq!(spline.control_points.len(), 2 * result.num_segments + 1);
+    assert_eq!(spline.evaluate(-1.0), result.curves[0].p0);
+
+    let empty = fit_curve(&Vec::<Point2D>::new(), &FitConfig::default());
+    assert!(empty.to_bspline().is_none());
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "gpu-wgpu")]
+
+use approx::assert_relative_eq;
+use bezier_dp_fit::optimizer::wgpu_backend::WgpuEngine;
+use bezier_dp_fit::{fit_curve, Backend, DPOptimizer, FitConfig, Point2D, Precision};
+
+#[test]
+fn test_wgpu_matches_cpu() {
+    // 没有可用适配器的环境（如 CI）跳过
+    let Ok(engine) = WgpuEngine::shared() else {
+        return;
+    };
+
+    let points: Vec<Point2D> = (0..150)
+        .map(|i| Point2D::new(1000.0 + i as f64, (i as f64 * 0.07).sin() * 20.0))
+        .collect();
+    let config = FitConfig::new(10, 50, 1.0).with_precision(Precision::F32);
+
+    let gpu = engine.compute_error_cache(&points, &config).unwrap();
+    let cpu = DPOptimizer::compute_error_cache(&points, &config);
+    assert_eq!(gpu.len(), cpu.len());
+    for (start, end) in [(0, 10), (20, 69), (100, 149)] {
+        let g = gpu.get(start, end).unwrap().error;
+        let c = cpu.get(start, end).unwrap().error;
+        if c <= config.max_error {
+            assert_relative_eq!(g, c, epsilon = 1e-3, max_relative = 1e-3);
+        } else {
+            // 超过上限的误差与 CPU 一样记为正无穷
+            assert_eq!(g, f64::INFINITY);
+            assert_eq!(c, f64::INFINITY);
+        }
+    }
+
+    let on_gpu = fit_curve(&points, &config.clone().with_backend(Backend::Wgpu));
+    let on_cpu = fit_curve(&points, &config.with_backend(Backend::Cpu));
+    assert_eq!(on_gpu.breakpoints, on_cpu.breakpoints);
+}
@@ -0,0 +1,232 @@
+#[cfg(feature = "kurbo")]
+#[test]
+fn test_kurbo_conversions() {
+    use bezier_dp_fit::{fit_curve, FitConfig, Point2D, QuadraticBezier};
+    use kurbo::{PathEl, QuadBez};
+
+    let curve = QuadraticBezier::new(
+        Point2D::new(0.0, 0.0),
+        Point2D::new(1.0, 2.0),
+        Point2D::new(3.0, 0.5),
+    );
+    let quad: QuadBez = curve.into();
+    assert_eq!(quad.p1, kurbo::Point::new(1.0, 2.0));
+    let back: QuadraticBezier = quad.into();
+    assert_eq!(back.p2, curve.p2);
+
+    let points: Vec<Point2D> = (0..120)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.08).sin() * 15.0))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(10, 40, 1.0));
+    let path = result.to_kurbo_bezpath();
+    let elements = path.elements();
+    assert_eq!(elements.len(), result.num_segments + 1);
+    assert_eq!(elements[0], PathEl::MoveTo(result.curves[0].p0.into()));
+    for (el, curve) in elements[1..].iter().zip(&result.curves) {
+        assert_eq!(*el, PathEl::QuadTo(curve.p1.into(), curve.p2.into()));
+    }
+}
+
+#[cfg(feature = "lyon")]
+#[test]
+fn test_lyon_path() {
+    use bezier_dp_fit::{fit_curve, FitConfig, Point2D};
+    use lyon_path::math::point;
+    use lyon_path::Event;
+
+    let points: Vec<Point2D> = (0..120)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.08).sin() * 15.0))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(10, 40, 1.0));
+    let path = result.to_lyon_path();
+
+    let events: Vec<_> = path.iter().collect();
+    assert_eq!(events.len(), result.num_segments + 2);
+    let first = result.curves[0].p0;
+    assert_eq!(
+        events[0],
+        Event::Begin {
+            at: point(first.x as f32, first.y as f32)
+        }
+    );
+    let quads = events
+        .iter()
+        .filter(|e| matches!(e, Event::Quadratic { .. }))
+        .count();
+    assert_eq!(quads, result.num_segments);
+    assert!(matches!(
+        events.last(),
+        Some(Event::End { close: false, .. })
+    ));
+}
+
+#[test]
+fn test_array_points_input() {
+    use bezier_dp_fit::{fit_curve, try_fit_curve, FitConfig, Point2D};
+
+    let points: Vec<Point2D> = (0..120)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.08).sin() * 15.0))
+        .collect();
+    let arrays: Vec<[f64; 2]> = points.iter().map(|p| [p.x, p.y]).collect();
+    let config = FitConfig::new(10, 40, 1.0);
+
+    let expected = fit_curve(&points, &config);
+    let result = fit_curve(&arrays, &config);
+    assert_eq!(result.breakpoints, expected.breakpoints);
+    assert_eq!(result.total_error, expected.total_error);
+
+    let result = try_fit_curve(&arrays[..], &config).unwrap();
+    assert_eq!(result.breakpoints, expected.breakpoints);
+    assert!(try_fit_curve(&[[0.0, 0.0], [f64::NAN, 1.0]], &config).is_err());
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_nalgebra_points_input() {
+    use bezier_dp_fit::{fit_curve, FitConfig, Point2D};
+
+    let points: Vec<nalgebra::Point2<f64>> = (0..120)
+        .map(|i| nalgebra::Point2::new(i as f64, (i as f64 * 0.08).sin() * 15.0))
+        .collect();
+    let copied: Vec<Point2D> = points.iter().map(|p| Point2D::new(p.x, p.y)).collect();
+    let config = FitConfig::new(10, 40, 1.0);
+
+    let result = fit_curve(&points, &config);
+    assert_eq!(result.breakpoints, fit_curve(&copied, &config).breakpoints);
+}
+
+#[cfg(feature = "glam")]
+#[test]
+fn test_glam_points_input() {
+    use bezier_dp_fit::{fit_curve, FitConfig, Point2D};
+    use glam::DVec2;
+
+    let points: Vec<DVec2> = (0..120)
+        .map(|i| DVec2::new(i as f64, (i as f64 * 0.08).sin() * 15.0))
+        .collect();
+    let copied: Vec<Point2D> = points.iter().map(|p| Point2D::new(p.x, p.y)).collect();
+    let config = FitConfig::new(10, 40, 1.0);
+
+    let result = fit_curve(&points, &config);
+    assert_eq!(result.breakpoints, fit_curve(&copied, &config).breakpoints);
+}
+
+#[cfg(feature = "protobuf")]
+#[test]
+fn test_protobuf_roundtrip() {
+    use bezier_dp_fit::io::protobuf::{decode_batch, encode_batch};
+    use bezier_dp_fit::{fit_curve, ErrorMetric, FitConfig, FitResult, FitStatus, Point2D};
+
+    let points: Vec<Point2D> = (0..120)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.08).sin() * 15.0))
+        .collect();
+    let config = FitConfig::new(10, 40, 1.0)
+        .with_metric(ErrorMetric::Max)
+        .with_line_tolerance(0.2);
+    let mut result = fit_curve(&points, &config);
+    result.status = FitStatus::Relaxed {
+        achieved_error: 2.5,
+    };
+    result.blocking_intervals = vec![(3, 17)];
+
+    let back = FitResult::from_protobuf(&result.to_protobuf()).unwrap();
+    assert_eq!(back.to_bytes().unwrap(), result.to_bytes().unwrap());
+
+    let decoded = FitConfig::from_protobuf(&config.to_protobuf()).unwrap();
+    assert_eq!(decoded.metric, ErrorMetric::Max);
+    assert_eq!(decoded.line_tolerance, Some(0.2));
+
+    let batch = decode_batch(&encode_batch(&[result.clone(), back])).unwrap();
+    assert_eq!(batch.len(), 2);
+    assert_eq!(batch[1].to_bytes().unwrap(), result.to_bytes().unwrap());
+    assert!(FitResult::from_protobuf(&[0xff, 0xff]).is_err());
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn test_service_fit() {
+    use bezier_dp_fit::service::{to_config, MAX_CACHE_BYTES, MAX_SEGMENT_LEN};
+    use bezier_dp_fit::FitParams;
+    use std::io::{Read, Write};
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let listener = runtime
+        .block_on(tokio::net::TcpListener::bind("127.0.0.1:0"))
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    runtime.spawn(async move { axum::serve(listener, bezier_dp_fit::service::router()).await });
+
+    let post = |content_type: &str, query: &str, body: &[u8]| {
+        let mut stream = std::net::TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "POST /fit{query} HTTP/1.1\r\nHost: localhost\r\nContent-Type: {content_type}\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )
+        .unwrap();
+        stream.write_all(body).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+
+    let points: Vec<[f64; 2]> = (0..100)
+        .map(|i| [i as f64, (i as f64 * 0.1).sin() * 10.0])
+        .collect();
+    let body = serde_json::json!({ "points": points, "max_error": 0.5, "backend": "cpu" });
+    let response = post("application/json", "", body.to_string().as_bytes());
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    let json: serde_json::Value =
+        serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+    assert!(json["num_segments"].as_u64().unwrap() >= 1);
+
+    let body = serde_json::json!({ "strokes": [&points[..50], &points[50..]] });
+    let response = post("application/json", "", body.to_string().as_bytes());
+    let json: serde_json::Value =
+        serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+
+    let header = "{'descr': '<f8', 'fortran_order': False, 'shape': (100, 2), }";
+    let mut npy = b"\x93NUMPY\x01\x00".to_vec();
+    npy.extend((header.len() as u16).to_le_bytes());
+    npy.extend(header.as_bytes());
+    npy.extend(points.iter().flatten().flat_map(|v| v.to_le_bytes()));
+    let response = post("application/x-npy", "?max_error=0.5&metric=max", &npy);
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+    let response = post(
+        "application/json",
+        "",
+        br#"{"points": [[0, 0]], "backend": "tpu"}"#,
+    );
+    assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+
+    // 资源上限：段长过大和调试用的后端直接拒绝，不开始拟合
+    let body = serde_json::json!({ "points": points, "max_segment_len": 100_000 });
+    let response = post("application/json", "", body.to_string().as_bytes());
+    assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+    assert!(
+        response.contains("max_segment_len must be <= 500"),
+        "{response}"
+    );
+    let response = post("application/x-npy", "?max_segment_len=100000", &npy);
+    assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+    let body = serde_json::json!({ "points": points, "backend": "cuda-verified" });
+    let response = post("application/json", "", body.to_string().as_bytes());
+    assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+
+    let config = to_config(&FitParams::default()).unwrap();
+    assert_eq!(config.max_cache_bytes, Some(MAX_CACHE_BYTES));
+    let params = FitParams {
+        max_segment_len: Some(MAX_SEGMENT_LEN),
+        ..FitParams::default()
+    };
+    assert_eq!(to_config(&params).unwrap().max_segment_len, MAX_SEGMENT_LEN);
+
+    // 参数无效时与命令行一样报错，不自动修正
+    let body = serde_json::json!({ "points": points, "min_segment_len": 1 });
+    let response = post("application/json", "", body.to_string().as_bytes());
+    assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+    assert!(response.contains("min_segment_len must be at least 3"), "{response}");
+}
@@ -0,0 +1,454 @@
+use approx::assert_relative_eq;
+use bezier_dp_fit::io::csv::{self, CsvOptions};
+use bezier_dp_fit::io::geojson::from_geojson;
+use bezier_dp_fit::io::polyline;
+use bezier_dp_fit::{
+    fit_curve, fit_multistroke, DxfEntity, DxfOptions, FitConfig, FitDocument, FitResult,
+    JsonSchemaError, Objective, Point2D, Sampling, SvgOptions, TrueTypePoint,
+};
+
+#[test]
+fn test_geojson_roundtrip() {
+    let input = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {"type": "Feature", "properties": {},
+             "geometry": {"type": "LineString", "coordinates": [[0, 0], [1, 1], [2, 4.5]]}},
+            {"type": "Feature", "properties": {},
+             "geometry": {"type": "MultiLineString", "coordinates": [[[5, 5], [6, 6]], [[7, 7], [8, 8, 10]]]}},
+            {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [1, 2]}},
+            {"type": "Feature", "properties": {}, "geometry": null}
+        ]
+    }"#;
+    let lines = from_geojson(input).unwrap();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0][2], Point2D::new(2.0, 4.5));
+    assert_eq!(lines[2][1], Point2D::new(8.0, 8.0));
+
+    assert!(from_geojson(r#"{"type": "LineString", "coordinates": [[0, "a"]]}"#).is_err());
+    assert!(from_geojson("not json").is_err());
+
+    let points: Vec<Point2D> = (0..60)
+        .map(|i| Point2D::new(i as f64 * 0.001, (i as f64 * 0.1).sin() * 0.01))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 30, 1e-6));
+    let out = from_geojson(&result.to_geojson_linestring(8)).unwrap();
+    assert_eq!(out.len(), 1);
+    assert_eq!(out[0].len(), result.num_segments * 7 + 1);
+}
+
+#[test]
+fn test_polyline_decode_encode() {
+    // Google 文档中的示例
+    let encoded = "_p~iF~ps|U_ulLnnqC_mqNvxq`@";
+    let points = polyline::decode(encoded, 5).unwrap();
+    assert_eq!(points.len(), 3);
+    assert_relative_eq!(points[0].y, 38.5);
+    assert_relative_eq!(points[0].x, -120.2);
+    assert_relative_eq!(points[2].y, 43.252);
+    assert_relative_eq!(points[2].x, -126.453);
+    assert_eq!(polyline::encode(&points, 5), encoded);
+
+    assert!(polyline::decode("_p~iF", 5).is_err());
+    assert!(polyline::decode("_p~iF~ps|", 5).is_err());
+
+    let track: Vec<Point2D> = (0..50)
+        .map(|i| {
+            Point2D::new(
+                116.3 + i as f64 * 1e-4,
+                39.9 + (i as f64 * 0.2).sin() * 1e-4,
+            )
+        })
+        .collect();
+    let decoded = polyline::decode(&polyline::encode(&track, 6), 6).unwrap();
+    let result = fit_curve(&decoded, &FitConfig::new(5, 25, 1e-8));
+    let sampled = polyline::decode(&result.to_encoded_polyline(5, 6), 6).unwrap();
+    assert_eq!(sampled.len(), result.num_segments * 4 + 1);
+}
+
+#[test]
+fn test_fit_document_svg() {
+    let mut points: Vec<Point2D> = (0..40)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.2).sin() * 5.0))
+        .collect();
+    points.extend((0..40).map(|i| Point2D::new(i as f64, 50.0 + (i as f64 * 0.1).cos() * 5.0)));
+
+    let doc: FitDocument = fit_multistroke(&points, 10.0, &FitConfig::new(3, 20, 0.5)).into();
+    assert_eq!(doc.len(), 2);
+
+    let path = doc.to_svg_path();
+    assert_eq!(path.matches('M').count(), 2);
+    assert_eq!(path.matches('Q').count(), doc.num_segments());
+    assert!(path.starts_with(&doc.paths[0].to_svg_path()));
+
+    let bbox = doc.bounding_box().unwrap();
+    assert!(bbox.min.y < 0.0 && bbox.max.y > 50.0);
+
+    // 相对坐标下第二个子路径仍以绝对 M 开始
+    let relative = doc.to_svg_path_with(&SvgOptions {
+        relative: true,
+        ..SvgOptions::default()
+    });
+    assert_eq!(relative.matches('M').count(), 2);
+    assert!(!relative.contains('m'));
+
+    let mut with_empty = doc.clone();
+    with_empty.push(fit_curve(&[] as &[Point2D], &FitConfig::new(3, 20, 0.5)));
+    assert_eq!(with_empty.to_svg_path(), path);
+    assert!(FitDocument::new().bounding_box().is_none());
+}
+
+/// DXF 文本拆成 (组码, 值) 对
+fn dxf_pairs(dxf: &str) -> Vec<(i32, String)> {
+    let lines: Vec<&str> = dxf.lines().collect();
+    lines
+        .chunks(2)
+        .map(|c| (c[0].trim().parse().unwrap(), c[1].to_string()))
+        .collect()
+}
+
+#[test]
+fn test_dxf_export() {
+    let points: Vec<Point2D> = (0..100)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).sin() * 10.0))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 30, 0.5));
+    let k = result.num_segments;
+
+    let pairs = dxf_pairs(&result.to_dxf(&DxfOptions::default()));
+    assert_eq!(pairs.last().unwrap(), &(0, "EOF".to_string()));
+    assert_eq!(pairs.iter().filter(|p| p.1 == "SPLINE").count(), 1);
+    let value = |code: i32| pairs.iter().find(|p| p.0 == code).unwrap().1.clone();
+    assert_eq!(value(71), "2");
+    assert_eq!(value(72), (2 * k + 4).to_string());
+    assert_eq!(value(73), (2 * k + 1).to_string());
+    assert_eq!(pairs.iter().filter(|p| p.0 == 40).count(), 2 * k + 4);
+    assert_eq!(pairs.iter().filter(|p| p.0 == 10).count(), 2 * k + 1);
+    let xs: Vec<f64> = pairs
+        .iter()
+        .filter(|p| p.0 == 10)
+        .map(|p| p.1.parse().unwrap())
+        .collect();
+    assert_relative_eq!(xs[1], result.curves[0].p1.x);
+
+    let options = DxfOptions {
+        entity: DxfEntity::Polyline {
+            samples_per_segment: 5,
+        },
+        layer: "cut".to_string(),
+    };
+    let pairs = dxf_pairs(&result.to_dxf(&options));
+    assert_eq!(pairs.iter().filter(|p| p.1 == "VERTEX").count(), 4 * k + 1);
+    assert!(pairs.iter().filter(|p| p.0 == 8).all(|p| p.1 == "cut"));
+
+    let doc: FitDocument = vec![result.clone(), result].into();
+    let pairs = dxf_pairs(&doc.to_dxf(&DxfOptions::default()));
+    assert_eq!(pairs.iter().filter(|p| p.1 == "SPLINE").count(), 2);
+}
+
+#[test]
+fn test_wkt_wkb_export() {
+    let points: Vec<Point2D> = (0..100)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).sin() * 10.0))
+        .collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 30, 0.5));
+    let k = result.num_segments;
+
+    let wkt = result.to_wkt_linestring(5);
+    assert!(wkt.starts_with("LINESTRING(") && wkt.ends_with(')'));
+    assert_eq!(wkt.matches(',').count() + 1, 4 * k + 1);
+
+    // 偏差越小采样越密
+    let coarse = result.to_wkt_linestring(Sampling::MaxDeviation(1.0));
+    let fine = result.to_wkt_linestring(Sampling::MaxDeviation(0.001));
+    assert!(fine.matches(',').count() > coarse.matches(',').count());
+
+    let wkb = result.to_wkb_linestring(Sampling::MaxDeviation(0.01));
+    assert_eq!(wkb[0], 1);
+    assert_eq!(u32::from_le_bytes(wkb[1..5].try_into().unwrap()), 2);
+    let n = u32::from_le_bytes(wkb[5..9].try_into().unwrap()) as usize;
+    assert_eq!(wkb.len(), 9 + 16 * n);
+    let x0 = f64::from_le_bytes(wkb[9..17].try_into().unwrap());
+    assert_eq!(x0, result.curves[0].p0.x);
+
+    // 每段弦的中点与曲线的偏差不超过容差
+    let tolerance = 0.01;
+    let curve = result.curves[0];
+    let samples: Vec<Point2D> = result
+        .to_wkt_linestring(Sampling::MaxDeviation(tolerance))
+        .trim_start_matches("LINESTRING(")
+        .trim_end_matches(')')
+        .split(", ")
+        .map(|xy| {
+            let mut it = xy.split(' ').map(|v| v.parse::<f64>().unwrap());
+            Point2D::new(it.next().unwrap(), it.next().unwrap())
+        })
+        .collect();
+    let first_end = samples.iter().position(|p| *p == curve.p2).unwrap();
+    for (i, w) in samples[..=first_end].windows(2).enumerate() {
+        let mid = w[0].lerp(&w[1], 0.5);
+        let on_curve = curve.evaluate((i as f64 + 0.5) / first_end as f64);
+        assert!(mid.distance_to(&on_curve) <= tolerance + 1e-12);
+    }
+
+    let empty = fit_curve(&[] as &[Point2D], &FitConfig::new(3, 20, 0.5));
+    assert_eq!(empty.to_wkt_linestring(4), "LINESTRING EMPTY");
+}
+
+#[test]
+fn test_truetype_points() {
+    use bezier_dp_fit::QuadraticBezier;
+
+    let mut points: Vec<Point2D> = (0..60)
+        .map(|i| {
+            let a = i as f64 / 60.0 * std::f64::consts::TAU;
+            Point2D::new(100.0 * a.cos(), 100.0 * a.sin())
+        })
+        .collect();
+    points.push(points[0]);
+    let mut result = fit_curve(&points, &FitConfig::new(5, 20, 0.5));
+    let k = result.num_segments;
+
+    // 闭合轮廓：起点不重复，每段一个 off-curve 点
+    let tt = result.to_truetype_points();
+    assert!(tt.len() <= 2 * k);
+    assert!(tt[0].on_curve);
+    assert_eq!(tt.iter().filter(|p| !p.on_curve).count(), k);
+
+    // 连接点恰为两侧控制点的中点时省略
+    let on = |x: f64, y: f64| TrueTypePoint {
+        x,
+        y,
+        on_curve: true,
+    };
+    let off = |x: f64, y: f64| TrueTypePoint {
+        x,
+        y,
+        on_curve: false,
+    };
+    result.curves = vec![
+        QuadraticBezier::new(
+            Point2D::new(0.0, 0.0),
+            Point2D::new(10.0, 10.0),
+            Point2D::new(20.0, 10.0),
+        ),
+        QuadraticBezier::new(
+            Point2D::new(20.0, 10.0),
+            Point2D::new(30.0, 10.0),
+            Point2D::new(40.0, 0.0),
+        ),
+        QuadraticBezier::new(
+            Point2D::new(40.0, 0.0),
+            Point2D::new(50.0, 5.0),
+            Point2D::new(60.0, 0.0),
+        ),
+    ];
+    result.segments.clear();
+    assert_eq!(
+        result.to_truetype_points(),
+        vec![
+            on(0.0, 0.0),
+            off(10.0, 10.0),
+            off(30.0, 10.0),
+            on(40.0, 0.0),
+            off(50.0, 5.0),
+            on(60.0, 0.0),
+        ]
+    );
+    // 放宽容差后 (40, 0) 与中点 (40, 7.5) 仍相距较远，不合并
+    assert_eq!(result.to_truetype_points_with_tolerance(1.0).len(), 6);
+}
+
+#[test]
+fn test_csv_read_write() {
+    let text = "# exported\nid;x;y\n0; 1.5; 2\n1; 3; 4\n\n2; 5; -6\n";
+    let options = CsvOptions {
+        delimiter: Some(';'),
+        x_column: 1,
+        y_column: 2,
+        ..CsvOptions::default()
+    };
+    let points = csv::parse_points(text, &options).unwrap();
+    assert_eq!(
+        points,
+        vec![
+            Point2D::new(1.5, 2.0),
+            Point2D::new(3.0, 4.0),
+            Point2D::new(5.0, -6.0),
+        ]
+    );
+
+    // 自动分隔符：逗号、空白混用
+    let points =
+        csv::read_points("x,y\n0,0\n1 1\n2;4\n".as_bytes(), &CsvOptions::default()).unwrap();
+    assert_eq!(points.len(), 3);
+
+    let err = csv::parse_points("0,0\n1,oops\n", &CsvOptions::default()).unwrap_err();
+    assert_eq!(err.line, Some(2));
+    assert_eq!(err.to_string(), "line 2: invalid number");
+    let err = csv::parse_points("0,0\n1\n", &CsvOptions::default()).unwrap_err();
+    assert_eq!(err.line, Some(2));
+    assert!(
+        csv::read_points_file("/nonexistent/points.csv", &CsvOptions::default())
+            .unwrap_err()
+            .line
+            .is_none()
+    );
+
+    let points: Vec<Point2D> = (0..=20).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    let result = fit_curve(&points, &FitConfig::new(5, 40, 0.5));
+    let mut out = Vec::new();
+    let options = CsvOptions {
+        header: true,
+        ..CsvOptions::default()
+    };
+    csv::write_samples(&mut out, &result, 4, &options).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.starts_with("x,y\n0,0\n"));
+    let back = csv::parse_points(&text, &CsvOptions::default()).unwrap();
+    assert_eq!(back.len(), 3 * result.num_segments + 1);
+    assert_eq!(back.last(), points.last());
+}
+
+#[test]
+fn test_npy_points() {
+    use bezier_dp_fit::io::npy::from_npy;
+
+    let npy = |descr: &str, fortran: bool, shape: &str, data: Vec<u8>| {
+        let header = format!(
+            "{{'descr': '{descr}', 'fortran_order': {}, 'shape': {shape}, }}",
+            if fortran { "True" } else { "False" }
+        );
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes.extend(data);
+        bytes
+    };
+    let f8 = |v: &[f64]| v.iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<u8>>();
+
+    let expected = vec![
+        Point2D::new(0.0, 1.0),
+        Point2D::new(2.0, 3.0),
+        Point2D::new(4.0, 5.0),
+    ];
+    let c_order = npy("<f8", false, "(3, 2)", f8(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0]));
+    assert_eq!(from_npy(&c_order).unwrap(), expected);
+    let fortran = npy("<f8", true, "(3, 2)", f8(&[0.0, 2.0, 4.0, 1.0, 3.0, 5.0]));
+    assert_eq!(from_npy(&fortran).unwrap(), expected);
+    let transposed = npy("<f8", false, "(2, 3)", f8(&[0.0, 2.0, 4.0, 1.0, 3.0, 5.0]));
+    assert_eq!(from_npy(&transposed).unwrap(), expected);
+    let f4: Vec<u8> = [0.0f32, 1.0, 2.0, 3.0, 4.0, 5.0]
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect();
+    assert_eq!(
+        from_npy(&npy("<f4", false, "(3, 2)", f4)).unwrap(),
+        expected
+    );
+
+    assert!(from_npy(b"not an npy file").is_err());
+    assert!(from_npy(&npy("<i8", false, "(3, 2)", f8(&[0.0; 6]))).is_err());
+    assert!(from_npy(&npy("<f8", false, "(3, 3)", f8(&[0.0; 9]))).is_err());
+    assert!(from_npy(&npy("<f8", false, "(3, 2)", f8(&[0.0; 4]))).is_err());
+}
+
+#[test]
+fn test_versioned_json_schema() {
+    let points: Vec<Point2D> = (0..80)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.15).sin() * 10.0))
+        .collect();
+    let result = fit_curve(
+        &points,
+        &FitConfig::new(5, 30, 0.1).with_line_tolerance(0.05),
+    );
+    let json = result.to_json().unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["schema_version"], 1);
+    assert_eq!(value["status"]["kind"], "optimal");
+    assert_eq!(value["curves"][0]["p0"]["x"], 0.0);
+    assert!(value["segments"][0]["type"].is_string());
+    assert_eq!(value["config"]["metric"], "mse");
+
+    let restored = FitResult::from_json(&json).unwrap();
+    // serde_json 解析浮点数可能差最后一位
+    for (a, b) in restored.curves.iter().zip(&result.curves) {
+        assert_relative_eq!(a.p1.x, b.p1.x, epsilon = 1e-12);
+        assert_relative_eq!(a.p1.y, b.p1.y, epsilon = 1e-12);
+    }
+    assert_eq!(restored.curves.len(), result.curves.len());
+    assert_eq!(restored.to_svg_path(), result.to_svg_path());
+    assert_eq!(restored.breakpoints, result.breakpoints);
+    for (a, b) in restored.segment_info.iter().zip(&result.segment_info) {
+        assert_eq!((a.start_index, a.end_index), (b.start_index, b.end_index));
+    }
+    assert_eq!(restored.config.max_error, 0.1);
+
+    // 版本、语法和一致性错误分别报告
+    let mut unversioned = value.clone();
+    unversioned
+        .as_object_mut()
+        .unwrap()
+        .remove("schema_version");
+    assert_eq!(
+        FitResult::from_json(&unversioned.to_string()).unwrap_err(),
+        JsonSchemaError::UnsupportedVersion { found: None }
+    );
+    let mut future = value.clone();
+    future["schema_version"] = 2.into();
+    assert_eq!(
+        FitResult::from_json(&future.to_string()).unwrap_err(),
+        JsonSchemaError::UnsupportedVersion { found: Some(2) }
+    );
+    let mut mistyped = value.clone();
+    mistyped["curves"][0]["p0"]["x"] = "0".into();
+    assert!(matches!(
+        FitResult::from_json(&mistyped.to_string()),
+        Err(JsonSchemaError::Syntax { .. })
+    ));
+    let mut miscounted = value.clone();
+    miscounted["num_segments"] = 1000.into();
+    match FitResult::from_json(&miscounted.to_string()) {
+        Err(JsonSchemaError::InvalidField { field, .. }) => assert_eq!(field, "num_segments"),
+        other => panic!("expected InvalidField, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_json_round_trip_penalized_unbounded() {
+    // 无误差上限的惩罚目标：max_error 写为 null，读回为无穷大
+    let points: Vec<Point2D> = (0..60)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.2).sin() * 8.0))
+        .collect();
+    let config = FitConfig {
+        max_error: f64::INFINITY,
+        ..FitConfig::new(3, 30, 1.0)
+    }
+    .with_objective(Objective::Penalized { lambda: 0.5 });
+    let result = fit_curve(&points, &config);
+    let json = result.to_json().unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(value["config"]["max_error"].is_null());
+    assert_eq!(value["config"]["objective"]["kind"], "penalized");
+    assert_eq!(value["config"]["objective"]["lambda"], 0.5);
+
+    let restored = FitResult::from_json(&json).unwrap();
+    assert_eq!(restored.config.max_error, f64::INFINITY);
+    assert_eq!(
+        restored.config.objective,
+        Objective::Penalized { lambda: 0.5 }
+    );
+    assert_eq!(restored.breakpoints, result.breakpoints);
+    assert_eq!(restored.to_svg_path(), result.to_svg_path());
+
+    // 旧文档没有 objective 字段时按 min_segments 读入
+    let mut legacy = value.clone();
+    legacy["config"]
+        .as_object_mut()
+        .unwrap()
+        .remove("objective");
+    let restored = FitResult::from_json(&legacy.to_string()).unwrap();
+    assert_eq!(restored.config.objective, Objective::MinSegments);
+}
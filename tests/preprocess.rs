@@ -0,0 +1,218 @@
+use bezier_dp_fit::preprocess::{
+    dedup, dedup_indices, detect_corners, filter_non_finite, simplify, simplify_indices,
+    smooth_gaussian, smooth_moving_average, split_on_gaps, validate_points,
+};
+use bezier_dp_fit::{
+    fit_curve, fit_multistroke, try_fit_curve, try_fit_strokes, Backend, FitConfig, FitCurveError,
+    FitStatus, Point2D, Smoothing,
+};
+
+fn jittered_line(n: usize) -> Vec<Point2D> {
+    (0..n)
+        .map(|i| {
+            let jitter = if i % 2 == 0 { 0.8 } else { -0.8 };
+            Point2D::new(i as f64, jitter)
+        })
+        .collect()
+}
+
+fn roughness(points: &[Point2D]) -> f64 {
+    points.windows(2).map(|w| (w[1].y - w[0].y).abs()).sum()
+}
+
+#[test]
+fn test_smoothing_filters() {
+    let points = jittered_line(50);
+
+    for smoothed in [
+        smooth_gaussian(&points, 1.5),
+        smooth_moving_average(&points, 5),
+    ] {
+        assert_eq!(smoothed.len(), points.len());
+        assert_eq!(smoothed[0], points[0]);
+        assert_eq!(smoothed[49], points[49]);
+        assert!(roughness(&smoothed) < roughness(&points) * 0.5);
+    }
+
+    // 无效参数原样返回
+    assert_eq!(smooth_gaussian(&points, 0.0), points);
+    assert_eq!(smooth_moving_average(&points, 1), points);
+    assert_eq!(smooth_gaussian(&points, f64::INFINITY), points);
+    assert_eq!(smooth_gaussian(&points, f64::NAN), points);
+
+    // 过大的 σ 和窗口按点数截断核半径
+    for smoothed in [
+        smooth_gaussian(&points, 1e300),
+        smooth_moving_average(&points, usize::MAX),
+    ] {
+        assert_eq!(smoothed.len(), points.len());
+        assert_eq!(smoothed[0], points[0]);
+        assert!(smoothed.iter().all(|p| p.y.abs() <= 0.8));
+        assert!(roughness(&smoothed) < roughness(&points) * 0.5);
+    }
+
+    let invalid = |sigma: f64| {
+        FitConfig::builder()
+            .presmooth(Smoothing::Gaussian { sigma })
+            .build()
+            .unwrap_err()
+            .reason
+    };
+    assert_eq!(
+        invalid(f64::INFINITY),
+        "presmooth sigma must be finite and positive"
+    );
+    assert_eq!(invalid(-1.0), "presmooth sigma must be finite and positive");
+}
+
+#[test]
+fn test_presmooth_config() {
+    let points = jittered_line(120);
+    let raw = fit_curve(&points, &FitConfig::new(4, 40, 0.3));
+    let config = FitConfig::new(4, 40, 0.3).with_presmooth(Smoothing::Gaussian { sigma: 2.0 });
+    let smoothed = fit_curve(&points, &config);
+
+    assert!(smoothed.num_segments <= raw.num_segments);
+    assert!(smoothed.total_error < raw.total_error);
+    assert_eq!(*smoothed.breakpoints.last().unwrap(), points.len() - 1);
+}
+
+#[test]
+fn test_dedup() {
+    let points = vec![
+        Point2D::new(0.0, 0.0),
+        Point2D::new(0.0, 0.0),
+        Point2D::new(1.0, 0.0),
+        Point2D::new(1.05, 0.0),
+        Point2D::new(2.0, 0.0),
+        Point2D::new(2.0, 0.0),
+    ];
+    assert_eq!(dedup_indices(&points, 0.0), vec![0, 2, 3, 5]);
+    assert_eq!(dedup_indices(&points, 0.1), vec![0, 2, 5]);
+    assert_eq!(dedup(&points, 0.1).last(), points.last());
+    assert!(dedup(&[], 1.0).is_empty());
+}
+
+#[test]
+fn test_dedup_config_keeps_original_indices() {
+    // 每个点重复三次，模拟数位笔驱动的突发重复
+    let points: Vec<Point2D> = (0..60)
+        .flat_map(|i| {
+            let p = Point2D::new(i as f64, (i as f64 * 0.2).sin() * 5.0);
+            [p, p, p]
+        })
+        .collect();
+    let config = FitConfig::new(5, 30, 0.5).with_dedup(0.0);
+    let result = fit_curve(&points, &config);
+
+    assert_eq!(result.breakpoints[0], 0);
+    assert_eq!(*result.breakpoints.last().unwrap(), points.len() - 1);
+    assert_eq!(result.residuals(&points).len(), points.len());
+}
+
+#[test]
+fn test_non_finite_input() {
+    let mut points: Vec<Point2D> = (0..20).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    points[7].y = f64::NAN;
+    points[12].x = f64::INFINITY;
+
+    let err = try_fit_curve(&points, &FitConfig::new(3, 10, 1.0)).unwrap_err();
+    match err {
+        FitCurveError::NonFinitePoint { index, .. } => assert_eq!(index, 7),
+        ref other => panic!("expected NonFinitePoint, got {other:?}"),
+    }
+    assert!(err.to_string().contains("point 7"));
+
+    let clean = filter_non_finite(&points);
+    assert_eq!(clean.len(), 18);
+    assert!(validate_points(&clean).is_ok());
+    assert!(try_fit_curve(&clean, &FitConfig::new(3, 10, 1.0)).is_ok());
+}
+
+#[test]
+fn test_detect_corners() {
+    // L 形折线，拐角在下标 10，重复点不影响方向
+    let mut points: Vec<Point2D> = (0..=10).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    points.push(Point2D::new(10.0, 0.0));
+    points.extend((1..=10).map(|i| Point2D::new(10.0, i as f64)));
+
+    let corners = detect_corners(&points, std::f64::consts::FRAC_PI_4);
+    assert_eq!(corners, vec![10]);
+    assert!(detect_corners(&points, 2.0).is_empty());
+    assert!(detect_corners(&points[..2], 0.1).is_empty());
+}
+
+#[test]
+fn test_simplify() {
+    let points: Vec<Point2D> = (0..100)
+        .map(|i| Point2D::new(i as f64, (i as f64 * 0.1).sin() * 10.0))
+        .collect();
+
+    let indices = simplify_indices(&points, 0.5);
+    assert_eq!(indices[0], 0);
+    assert_eq!(*indices.last().unwrap(), 99);
+    assert!(indices.len() < points.len() / 2);
+    assert!(indices.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(simplify(&points, 0.5).len(), indices.len());
+
+    // 直线只保留首尾
+    let line: Vec<Point2D> = (0..20)
+        .map(|i| Point2D::new(i as f64, 2.0 * i as f64))
+        .collect();
+    assert_eq!(simplify_indices(&line, 1e-9), vec![0, 19]);
+}
+
+#[test]
+fn test_split_on_gaps() {
+    // 两笔画拼在一个数组里，中间跳跃 50
+    let mut points: Vec<Point2D> = (0..40).map(|i| Point2D::new(i as f64, 0.0)).collect();
+    points.extend((0..30).map(|i| Point2D::new(90.0 + i as f64, (i as f64 * 0.2).sin())));
+
+    let strokes = split_on_gaps(&points, 5.0);
+    assert_eq!(strokes.len(), 2);
+    assert_eq!(strokes[0].len(), 40);
+    assert_eq!(strokes[1][0], points[40]);
+    assert_eq!(split_on_gaps(&points, 100.0).len(), 1);
+    assert!(split_on_gaps(&[], 1.0).is_empty());
+
+    let results = fit_multistroke(&points, 5.0, &FitConfig::new(3, 20, 0.5));
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].breakpoints.last(), Some(&39));
+    assert_eq!(results[1].curves[0].p0, points[40]);
+
+    // 选择 CUDA 但不可用时逐条回退，结果不变
+    let cuda = fit_multistroke(
+        &points,
+        5.0,
+        &FitConfig::new(3, 20, 0.5).with_backend(Backend::Cuda),
+    );
+    for (a, b) in cuda.iter().zip(&results) {
+        assert_eq!(a.breakpoints, b.breakpoints);
+    }
+
+    // 批量缓存同样受内存上限约束，超出时逐条按不建缓存求解
+    let limited = fit_multistroke(
+        &points,
+        5.0,
+        &FitConfig::new(3, 20, 0.5)
+            .with_backend(Backend::Cuda)
+            .with_max_cache_bytes(1),
+    );
+    for (a, b) in limited.iter().zip(&results) {
+        assert_eq!(a.breakpoints, b.breakpoints);
+        assert_eq!(a.status, FitStatus::Degraded);
+    }
+
+    // 已切分的笔画与 fit_multistroke 结果相同，含非法点时报告该笔画内的下标
+    let fitted = try_fit_strokes(&strokes, &FitConfig::new(3, 20, 0.5)).unwrap();
+    for (a, b) in fitted.iter().zip(&results) {
+        assert_eq!(a.breakpoints, b.breakpoints);
+        assert_eq!(a.segment_errors, b.segment_errors);
+    }
+    let mut invalid = strokes.clone();
+    invalid[1][4].y = f64::NAN;
+    assert!(matches!(
+        try_fit_strokes(&invalid, &FitConfig::new(3, 20, 0.5)),
+        Err(FitCurveError::NonFinitePoint { index: 4, .. })
+    ));
+}